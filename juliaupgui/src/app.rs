@@ -11,6 +11,7 @@ use juliaup::command_update_version_db::run_command_update_version_db;
 use juliaup::config_file::{
     load_config_db, load_mut_config_db, save_config_db, JuliaupConfigChannel, JuliaupConfigSettings,
 };
+use juliaup::disk_usage::{compute_disk_usage, format_size, DiskUsage};
 use juliaup::global_paths::GlobalPaths;
 use juliaup::jsonstructs_versionsdb::JuliaupVersionDB;
 use juliaup::operations::{get_channel_variations, get_julia_pr_title};
@@ -41,6 +42,10 @@ struct InstalledRow {
     is_default: bool,
     update: Option<UpdateInfo>,
     pr_number: Option<String>,
+    /// On-disk size of the installation; `None` for links and aliases.
+    size: Option<u64>,
+    /// Other channels that use the same installation.
+    shared_with: Vec<String>,
 }
 
 /// A pending channel update, split into what fits in the UI and what does not.
@@ -71,6 +76,9 @@ struct AppState {
     available: Vec<AvailableRow>,
     overrides: Vec<OverrideRow>,
     settings: JuliaupConfigSettings,
+    disk_total: u64,
+    /// Size of the `julia-temp-*` leftovers of interrupted downloads.
+    disk_leftovers: u64,
 }
 
 // ── worker IPC ────────────────────────────────────────────────────────────────
@@ -889,6 +897,21 @@ fn tab_installed(app: &mut App, ui: &mut egui::Ui) {
         None => return,
     };
 
+    ui.label(
+        RichText::new(if state.disk_leftovers > 0 {
+            format!(
+                "Disk usage: {} ({} in interrupted downloads)",
+                format_size(state.disk_total),
+                format_size(state.disk_leftovers)
+            )
+        } else {
+            format!("Disk usage: {}", format_size(state.disk_total))
+        })
+        .size(12.0)
+        .color(secondary_text(ui.visuals().dark_mode)),
+    );
+    ui.add_space(4.0);
+
     if state.installed.is_empty() {
         ui.add_space(16.0);
         ui.vertical_centered(|ui| {
@@ -1002,6 +1025,17 @@ fn tab_installed_tiles(app: &mut App, ui: &mut egui::Ui, state: &AppState) {
                             )
                             .on_hover_text(&row.version);
 
+                            if let Some((label, hint)) = size_label(&row) {
+                                let resp = ui.label(
+                                    RichText::new(label)
+                                        .size(11.0)
+                                        .color(secondary_text(ui.visuals().dark_mode)),
+                                );
+                                if let Some(hint) = hint {
+                                    resp.on_hover_text(hint);
+                                }
+                            }
+
                             if row.is_default || row.update.is_some() {
                                 ui.add_space(4.0);
                                 ui.horizontal_wrapped(|ui| {
@@ -1227,6 +1261,7 @@ fn tab_installed_list(app: &mut App, ui: &mut egui::Ui, state: &AppState) {
         .column(Column::auto_with_initial_suggestion(140.0).at_least(90.0)) // Channel
         .column(Column::remainder().at_least(90.0)) // Version (takes leftover)
         .column(Column::initial(100.0).at_least(70.0)) // Update
+        .column(Column::initial(110.0).at_least(70.0)) // Size
         .column(Column::exact(150.0)) // Launch
         .column(Column::exact(200.0)) // Actions
         .min_scrolled_height(0.0)
@@ -1244,6 +1279,9 @@ fn tab_installed_list(app: &mut App, ui: &mut egui::Ui, state: &AppState) {
             header.col(|ui| {
                 ui.strong("Update");
             });
+            header.col(|ui| {
+                ui.strong("Size");
+            });
             header.col(|ui| {
                 ui.strong("Launch");
             });
@@ -1306,6 +1344,18 @@ fn tab_installed_list(app: &mut App, ui: &mut egui::Ui, state: &AppState) {
                             );
                         }
                     });
+                    cells.col(|ui| {
+                        if let Some((label, hint)) = size_label(&row) {
+                            let resp = ui.label(
+                                RichText::new(label)
+                                    .size(12.0)
+                                    .color(secondary_text(ui.visuals().dark_mode)),
+                            );
+                            if let Some(hint) = hint {
+                                resp.on_hover_text(hint);
+                            }
+                        }
+                    });
                     cells.col(|ui| {
                         ui.horizontal(|ui| {
                             if accessible_button_name(
@@ -2931,7 +2981,7 @@ fn exec(op: &Op, paths: &GlobalPaths, tx: &mpsc::Sender<Msg>) -> Msg {
             Ok(_) => Msg::Ok("Juliaup updated successfully".to_string()),
             Err(e) => Msg::Err(format!("{e}")),
         },
-        Op::Gc => match run_command_gc(false, paths) {
+        Op::Gc => match run_command_gc(false, false, paths) {
             Ok(_) => Msg::Ok("Garbage collection complete".to_string()),
             Err(e) => Msg::Err(format!("{e}")),
         },
@@ -3017,7 +3067,8 @@ fn load_state(paths: &GlobalPaths) -> anyhow::Result<AppState> {
     let config = load_config_db(paths, None)?;
     let versiondb = load_versions_db(paths)?;
 
-    let installed = build_installed(&config, &versiondb);
+    let usage = compute_disk_usage(&config.data, &paths.juliauphome);
+    let installed = build_installed(&config, &versiondb, &usage);
     let installed_keys: std::collections::HashSet<_> =
         config.data.installed_channels.keys().cloned().collect();
     let available = build_available(&versiondb, &installed_keys)?;
@@ -3036,28 +3087,80 @@ fn load_state(paths: &GlobalPaths) -> anyhow::Result<AppState> {
         available,
         overrides,
         settings: config.data.settings.clone(),
+        disk_total: usage.total(),
+        disk_leftovers: usage.temp_dirs.iter().map(|t| t.size).sum(),
     })
 }
 
 fn build_installed(
     config: &juliaup::config_file::JuliaupReadonlyConfigFile,
     versiondb: &JuliaupVersionDB,
+    usage: &DiskUsage,
 ) -> Vec<InstalledRow> {
     config
         .data
         .installed_channels
         .iter()
         .sorted_by(|(a, _), (b, _)| cmp(a, b))
-        .map(|(name, ch)| InstalledRow {
-            version: fmt_version(ch),
-            is_default: config.data.default.as_deref() == Some(name.as_str()),
-            update: update_info(name, ch, config, versiondb),
-            pr_number: installed_pr_number(name, ch, config),
-            name: name.clone(),
+        .map(|(name, ch)| {
+            let (size, shared_with) = installed_size(name, ch, usage);
+            InstalledRow {
+                version: fmt_version(ch),
+                is_default: config.data.default.as_deref() == Some(name.as_str()),
+                update: update_info(name, ch, config, versiondb),
+                pr_number: installed_pr_number(name, ch, config),
+                name: name.clone(),
+                size,
+                shared_with,
+            }
         })
         .collect()
 }
 
+fn installed_size(
+    name: &str,
+    channel: &JuliaupConfigChannel,
+    usage: &DiskUsage,
+) -> (Option<u64>, Vec<String>) {
+    match channel {
+        JuliaupConfigChannel::SystemChannel { version } => usage
+            .versions
+            .iter()
+            .find(|v| &v.version == version)
+            .map_or((None, Vec::new()), |v| {
+                let others = v.channels.iter().filter(|c| *c != name).cloned().collect();
+                (Some(v.size), others)
+            }),
+        JuliaupConfigChannel::DirectDownloadChannel { .. } => (
+            usage
+                .channels
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| c.size),
+            Vec::new(),
+        ),
+        JuliaupConfigChannel::LinkedChannel { .. } | JuliaupConfigChannel::AliasChannel { .. } => {
+            (None, Vec::new())
+        }
+    }
+}
+
+/// The size column of the installed list, e.g. `612.3 MiB (shared)`.
+fn size_label(row: &InstalledRow) -> Option<(String, Option<String>)> {
+    let size = format_size(row.size?);
+    if row.shared_with.is_empty() {
+        Some((size, None))
+    } else {
+        Some((
+            format!("{size} (shared)"),
+            Some(format!(
+                "Also used by {}; removing this channel frees no space",
+                row.shared_with.join(", ")
+            )),
+        ))
+    }
+}
+
 fn installed_pr_number(
     name: &str,
     channel: &JuliaupConfigChannel,
@@ -154,6 +154,9 @@ struct Juliainstaller {
         value_name = "MINUTES"
     )]
     startup_selfupdate_interval: i64,
+    /// Read installer settings from a TOML file
+    #[clap(long = "config", value_name = "FILE")]
+    config: Option<std::path::PathBuf>,
 }
 
 #[cfg(feature = "selfupdate")]
@@ -164,6 +167,7 @@ struct InstallChoices {
     modifypath: bool,
     install_location: std::path::PathBuf,
     modifypath_files: Vec<std::path::PathBuf>,
+    additional_channels: Vec<String>,
 }
 
 #[cfg(feature = "selfupdate")]
@@ -222,12 +226,21 @@ fn print_install_choices(install_choices: &InstallChoices) -> Result<()> {
         println!();
     }
 
+    if !install_choices.additional_channels.is_empty() {
+        println!(
+            "In addition to the default channel, the following channels will be installed: {}",
+            install_choices.additional_channels.join(", ")
+        );
+        println!();
+    }
+
     Ok(())
 }
 
 #[cfg(feature = "selfupdate")]
 pub fn main() -> Result<()> {
     use anyhow::{anyhow, Context};
+    use clap::{CommandFactory, FromArgMatches, ValueEnum};
     use console::{style, Style};
     use dialoguer::{
         theme::{ColorfulTheme, SimpleTheme, Theme},
@@ -241,6 +254,7 @@ pub fn main() -> Result<()> {
         config_file::JuliaupSelfConfig,
         get_juliaup_target, get_own_version,
         global_paths::get_paths,
+        installer_config::InstallerConfig,
         operations::{download_extract_sans_parent, find_shell_scripts_to_be_modified},
         utils::get_juliaserver_base_url,
     };
@@ -259,7 +273,53 @@ pub fn main() -> Result<()> {
     env_logger::init_from_env(env);
 
     info!("Parsing command line arguments.");
-    let args = Juliainstaller::parse();
+    let matches = Juliainstaller::command().get_matches();
+    let args = Juliainstaller::from_arg_matches(&matches)?;
+
+    // Explicit command line arguments win over `JULIAINSTALLER_*` environment
+    // variables, which in turn win over the `--config` file.
+    let from_command_line =
+        |id: &str| matches.value_source(id) == Some(clap::parser::ValueSource::CommandLine);
+    let command_line_config = InstallerConfig {
+        path: args.alternate_path.clone(),
+        default_channel: from_command_line("default_channel").then(|| args.default_channel.clone()),
+        juliaup_channel: from_command_line("juliaup_channel")
+            .then(|| args.juliaup_channel.to_lowercase().to_string()),
+        add_to_path: if from_command_line("add_to_path") {
+            args.add_to_path
+        } else {
+            None
+        },
+        background_selfupdate: from_command_line("background_selfupdate_interval")
+            .then_some(args.background_selfupdate_interval),
+        startup_selfupdate: from_command_line("startup_selfupdate_interval")
+            .then_some(args.startup_selfupdate_interval),
+        ..Default::default()
+    };
+    let file_config = match &args.config {
+        Some(config_path) => InstallerConfig::from_file(config_path)?,
+        None => InstallerConfig::default(),
+    };
+    let config = command_line_config
+        .or(InstallerConfig::from_env_vars(|name| {
+            std::env::var(name).ok()
+        })?)
+        .or(file_config);
+    config.validate()?;
+
+    let default_channel = config
+        .default_channel
+        .clone()
+        .unwrap_or(args.default_channel);
+    let juliaup_channel = match &config.juliaup_channel {
+        Some(value) => JuliaupChannel::from_str(value, true).map_err(|_| {
+            anyhow!(
+                "Invalid Juliaup channel '{}'. Valid values are: release, releasepreview, dev.",
+                value
+            )
+        })?,
+        None => args.juliaup_channel,
+    };
 
     if !args.disable_confirmation_prompt && !std::io::stdin().is_terminal() {
         return Err(anyhow!(
@@ -279,10 +339,14 @@ pub fn main() -> Result<()> {
     let mut paths = get_paths().with_context(|| "Trying to load all global paths.")?;
 
     use juliaup::{
+        command_config_autoinstall::run_command_config_autoinstall,
         command_config_backgroundselfupdate::run_command_config_backgroundselfupdate,
+        command_config_manifestversiondetect::run_command_config_manifestversiondetect,
         command_config_modifypath::run_command_config_modifypath,
+        command_config_server::run_command_config_server,
         command_config_startupselfupdate::run_command_config_startupselfupdate,
         command_config_symlinks::run_command_config_symlinks,
+        command_config_versionsdbupdate::run_command_config_versionsdbupdate,
     };
     use log::{debug, info, trace};
 
@@ -329,12 +393,16 @@ pub fn main() -> Result<()> {
     }
 
     let mut install_choices = InstallChoices {
-        backgroundselfupdate: args.background_selfupdate_interval,
-        startupselfupdate: args.startup_selfupdate_interval,
-        symlinks: false,
-        modifypath: args.add_to_path.unwrap_or(false),
-        install_location: match args.alternate_path {
-            Some(alternate_path) => PathBuf::from(alternate_path),
+        backgroundselfupdate: config
+            .background_selfupdate
+            .unwrap_or(args.background_selfupdate_interval),
+        startupselfupdate: config
+            .startup_selfupdate
+            .unwrap_or(args.startup_selfupdate_interval),
+        symlinks: config.channel_symlinks.unwrap_or(false),
+        modifypath: config.add_to_path.or(args.add_to_path).unwrap_or(false),
+        install_location: match &config.path {
+            Some(alternate_path) => PathBuf::from(shellexpand::tilde(alternate_path).as_ref()),
             None => dirs::home_dir()
                 .ok_or(anyhow!(
                     "Could not determine the path of the user home directory."
@@ -343,6 +411,13 @@ pub fn main() -> Result<()> {
         },
         modifypath_files: find_shell_scripts_to_be_modified(true)
             .with_context(|| "Failed to identify the shell scripts that need to be modified.")?,
+        additional_channels: config
+            .channels
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|channel| *channel != default_channel)
+            .collect(),
     };
 
     print_install_choices(&install_choices)?;
@@ -468,6 +543,10 @@ pub fn main() -> Result<()> {
 
     let juliaup_target = get_juliaup_target();
 
+    // Juliaup itself and the Julia versions below already have to come from
+    // the configured servers.
+    config.apply_servers_to_environment();
+
     let juliaupserver_base =
         get_juliaserver_base_url().with_context(|| "Failed to get Juliaup server base URL.")?;
 
@@ -494,6 +573,8 @@ pub fn main() -> Result<()> {
             modify_path: false,
             juliaup_channel: None,
             last_selfupdate: None,
+            pinned_version: None,
+            previous_version: None,
         };
 
         let self_config_path = install_choices.install_location.join("juliaupself.json");
@@ -542,6 +623,24 @@ pub fn main() -> Result<()> {
         paths.juliaupselfconfig = self_config_path.clone();
     }
 
+    // Persist the servers for later juliaup commands.
+    for (server, value) in config.servers() {
+        run_command_config_server(server, Some(value.to_string()), true, &paths)
+            .with_context(|| format!("Failed to configure {}.", server.config_property()))?;
+    }
+    if let Some(value) = config.versionsdb_update_interval {
+        run_command_config_versionsdbupdate(Some(value), true, &paths)
+            .with_context(|| "Failed to configure versions db update interval.")?;
+    }
+    if let Some(value) = config.auto_install_channels {
+        run_command_config_autoinstall(Some(value.to_string()), true, &paths)
+            .with_context(|| "Failed to configure automatic channel installation.")?;
+    }
+    if let Some(value) = config.manifest_version_detect {
+        run_command_config_manifestversiondetect(Some(value), true, &paths)
+            .with_context(|| "Failed to configure manifest version detection.")?;
+    }
+
     run_command_config_backgroundselfupdate(
         Some(install_choices.backgroundselfupdate),
         true,
@@ -558,15 +657,20 @@ pub fn main() -> Result<()> {
     }
     run_command_config_symlinks(Some(install_choices.symlinks), true, &paths)
         .with_context(|| "Failed to configure channel symlinks setting.")?;
-    run_command_selfchannel(Some(args.juliaup_channel), &paths)
+    run_command_selfchannel(Some(juliaup_channel), &paths)
         .with_context(|| "Failed to set juliaup update channel.")?;
 
-    run_command_add(&args.default_channel, &paths)
+    run_command_add(&default_channel, &paths)
         .with_context(|| "Failed to run `run_command_add`.")?;
 
-    run_command_default(&args.default_channel, &paths)
+    run_command_default(&default_channel, &paths)
         .with_context(|| "Failed to run `run_command_default`.")?;
 
+    for channel in &install_choices.additional_channels {
+        run_command_add(channel, &paths)
+            .with_context(|| format!("Failed to install channel '{}'.", channel))?;
+    }
+
     let symlink_path = juliaupselfbin.join("julia");
 
     std::os::unix::fs::symlink(juliaupselfbin.join("julialauncher"), &symlink_path).with_context(
@@ -592,6 +696,31 @@ pub fn main() -> Result<()> {
             println!("  . {}", p.to_string_lossy());
         }
         println!();
+    } else {
+        let juliaup_path = juliaupselfbin.join("juliaup");
+
+        println!();
+        println!("Your shell startup scripts were not modified. To add Julia to your");
+        println!(
+            "{} environment variable, add the line for your shell to its configuration:",
+            style("PATH").bold()
+        );
+        println!();
+        println!(
+            "  bash: eval \"$('{}' shell-init bash)\"",
+            juliaup_path.display()
+        );
+        println!(
+            "  zsh:  eval \"$('{}' shell-init zsh)\"",
+            juliaup_path.display()
+        );
+        println!(
+            "  fish: '{}' shell-init fish | source",
+            juliaup_path.display()
+        );
+        println!();
+        println!("Pass `--hook` to `shell-init` to also follow directory overrides.");
+        println!();
     }
 
     Ok(())
@@ -2,13 +2,14 @@ use anyhow::{anyhow, bail, Context, Result};
 use console::{style, Term};
 use dialoguer::Select;
 use is_terminal::IsTerminal;
-use itertools::Itertools;
+use juliaup::command_override::find_override_channel;
 use juliaup::config_file::{
     load_config_db_lockfree, load_mut_config_db, save_config_db, JuliaupConfig,
     JuliaupConfigChannel, JuliaupConfigVersion,
 };
 use juliaup::global_paths::get_paths;
 use juliaup::jsonstructs_versionsdb::JuliaupVersionDB;
+use juliaup::launcher_tools::{resolve_tool, tool_name_from_argv0, ToolCommand};
 use juliaup::operations::{is_pr_channel, is_valid_channel};
 use juliaup::utils::{print_juliaup_style, resolve_julia_binary_path, JuliaupMessageType};
 use juliaup::version_selection::get_auto_channel;
@@ -116,8 +117,10 @@ fn run_selfupdate(config_file: &juliaup::config_file::JuliaupReadonlyConfigFile)
             let juliaup_path =
                 get_juliaup_path().with_context(|| "Failed to obtain juliaup path.")?;
 
+            // Use the same hidden command as the scheduled self-update so that
+            // this counts as a background download.
             std::process::Command::new(juliaup_path)
-                .args(["self", "update"])
+                .args([juliaup::operations::SECRET_SELFUPDATE_COMMAND])
                 .stdout(Stdio::null())
                 .stderr(Stdio::null())
                 .stdin(Stdio::null())
@@ -320,7 +323,7 @@ fn check_channel_uptodate(
 fn is_nightly_channel(channel: &str) -> bool {
     use regex::Regex;
     let nightly_re =
-        Regex::new(r"^((?:nightly|latest)|(\d+\.\d+)-(?:nightly|latest))(~|$)").unwrap();
+        Regex::new(r"^((?:nightly|latest)|(\d+\.\d+)-(?:nightly|latest))(@[^~]+)?(~|$)").unwrap();
     nightly_re.is_match(channel)
 }
 
@@ -598,18 +601,10 @@ fn get_override_channel(
 ) -> Result<Option<String>> {
     let curr_dir = std::env::current_dir()?.canonicalize()?;
 
-    let juliaup_override = config_file
-        .data
-        .overrides
-        .iter()
-        .filter(|i| curr_dir.starts_with(&i.path))
-        .sorted_by_key(|i| i.path.len())
-        .next_back();
-
-    match juliaup_override {
-        Some(val) => Ok(Some(val.channel.clone())),
-        None => Ok(None),
-    }
+    Ok(find_override_channel(
+        &config_file.data.overrides,
+        &curr_dir,
+    ))
 }
 
 fn run_app() -> Result<i32> {
@@ -680,6 +675,28 @@ fn run_app() -> Result<i32> {
         )
     })?;
 
+    // When started through a launcher tool entry, run that tool of the
+    // selected channel instead of `julia`.
+    let (julia_path, julia_args) = match tool_name_from_argv0(
+        args.first().map(String::as_str).unwrap_or_default(),
+        &config_file.data.settings.launcher_tools,
+    ) {
+        Some(tool) => match resolve_tool(&julia_path, tool).with_context(|| {
+            format!(
+                "The Julia launcher failed to find `{}` for the `{}` channel.",
+                tool, julia_channel_to_use
+            )
+        })? {
+            ToolCommand::Executable(tool_path) => (tool_path, Vec::new()),
+            ToolCommand::Script(script_path) => {
+                let mut script_args = julia_args;
+                script_args.push(script_path.to_string_lossy().to_string());
+                (julia_path, script_args)
+            }
+        },
+        None => (julia_path, julia_args),
+    };
+
     let mut new_args: Vec<String> = Vec::new();
 
     for i in julia_args {
@@ -2,13 +2,26 @@ use anyhow::{Context, Result};
 use clap::Parser;
 use juliaup::cli::{ConfigSubCmd, Juliaup, OverrideSubCmd, SelfSubCmd};
 use juliaup::command_api::run_command_api;
+use juliaup::command_bisect::run_command_bisect;
 use juliaup::command_completions::generate_completion_for_command;
 use juliaup::command_config_autoinstall::run_command_config_autoinstall;
+use juliaup::command_config_autoprunefinishedprs::run_command_config_autoprunefinishedprs;
+use juliaup::command_config_channelshims::run_command_config_channelshims;
+use juliaup::command_config_download_rate::{
+    run_command_config_download_rate, DownloadRateSetting,
+};
+use juliaup::command_config_launchertools::run_command_config_launchertools;
+#[cfg(target_os = "linux")]
+use juliaup::command_config_libc::run_command_config_libc;
 use juliaup::command_config_manifestversiondetect::run_command_config_manifestversiondetect;
+use juliaup::command_config_network::{run_command_config_network, NetworkSetting};
+use juliaup::command_config_server::run_command_config_server;
 #[cfg(not(windows))]
 use juliaup::command_config_symlinks::run_command_config_symlinks;
+use juliaup::command_config_tls::run_command_config_tls;
 use juliaup::command_config_versionsdbupdate::run_command_config_versionsdbupdate;
 use juliaup::command_default::run_command_default;
+use juliaup::command_doctor::run_command_doctor;
 use juliaup::command_gc::run_command_gc;
 use juliaup::command_info::run_command_info;
 use juliaup::command_initial_setup_from_launcher::run_command_initial_setup_from_launcher;
@@ -18,18 +31,25 @@ use juliaup::command_list_channels::run_command_list_channels;
 use juliaup::command_override::{run_command_override_status, run_command_override_unset};
 use juliaup::command_post_update::run_command_post_update;
 use juliaup::command_remove::run_command_remove;
+use juliaup::command_repair::run_command_repair;
+use juliaup::command_selfchangelog::run_command_selfchangelog;
 use juliaup::command_selfupdate::run_command_selfupdate;
+use juliaup::command_shell_init::{run_command_override_channel, run_command_shell_init};
 use juliaup::command_status::run_command_status;
 use juliaup::command_update::run_command_update;
 use juliaup::command_update_version_db::run_command_update_version_db;
+use juliaup::download_rate::{mark_background_downloads, set_download_rate_limit};
 use juliaup::global_paths::get_paths;
+use juliaup::temp_dirs::warn_about_leftover_temp_dirs;
+use juliaup::tls::TlsSetting;
+use juliaup::utils::JuliaupServer;
 use juliaup::{command_add::run_command_add, command_override::run_command_override_set};
 #[cfg(feature = "selfupdate")]
 use juliaup::{
     command_config_backgroundselfupdate::run_command_config_backgroundselfupdate,
     command_config_modifypath::run_command_config_modifypath,
     command_config_startupselfupdate::run_command_config_startupselfupdate,
-    command_selfchannel::run_command_selfchannel,
+    command_selfchannel::run_command_selfchannel, command_selfrollback::run_command_selfrollback,
 };
 
 #[cfg(feature = "selfupdate")]
@@ -94,13 +114,39 @@ fn main() -> Result<()> {
 
     let paths = get_paths().with_context(|| "Trying to load all global paths.")?;
 
+    if args.modifies_installations() {
+        warn_about_leftover_temp_dirs(&paths.juliauphome);
+    }
+
     match args {
         Juliaup::Default { channel } => run_command_default(&channel, &paths),
-        Juliaup::Add { channel } => run_command_add(&channel, &paths),
+        Juliaup::Add {
+            channel,
+            limit_rate,
+        } => {
+            if let Some(rate) = limit_rate {
+                set_download_rate_limit(rate);
+            }
+            run_command_add(&channel, &paths)
+        }
         Juliaup::Remove { channel } => run_command_remove(&channel, &paths),
-        Juliaup::Status {} => run_command_status(&paths),
-        Juliaup::Update { channel } => run_command_update(&channel, &paths),
-        Juliaup::Gc { prune_linked } => run_command_gc(prune_linked, &paths),
+        Juliaup::Status { size } => run_command_status(size, &paths),
+        Juliaup::Update {
+            channel,
+            limit_rate,
+        } => {
+            if let Some(rate) = limit_rate {
+                set_download_rate_limit(rate);
+            }
+            run_command_update(&channel, &paths)
+        }
+        Juliaup::Gc {
+            prune_linked,
+            prune_finished_prs,
+        } => run_command_gc(prune_linked, prune_finished_prs, &paths),
+        Juliaup::Doctor {} => run_command_doctor(&paths),
+        Juliaup::Repair {} => run_command_repair(&paths),
+        Juliaup::Bisect { good, bad, command } => run_command_bisect(&good, &bad, &command, &paths),
         Juliaup::Link {
             channel,
             target,
@@ -112,6 +158,9 @@ fn main() -> Result<()> {
             ConfigSubCmd::ChannelSymlinks { value } => {
                 run_command_config_symlinks(value, false, &paths)
             }
+            ConfigSubCmd::ChannelShims { value } => {
+                run_command_config_channelshims(value, false, &paths)
+            }
             #[cfg(feature = "selfupdate")]
             ConfigSubCmd::BackgroundSelfupdateInterval { value } => {
                 run_command_config_backgroundselfupdate(value, false, &paths)
@@ -124,19 +173,69 @@ fn main() -> Result<()> {
             ConfigSubCmd::ModifyPath { value } => {
                 run_command_config_modifypath(value, false, &paths)
             }
+            ConfigSubCmd::AutoPruneFinishedPrs { value } => {
+                run_command_config_autoprunefinishedprs(value, false, &paths)
+            }
             ConfigSubCmd::VersionsDbUpdateInterval { value } => {
                 run_command_config_versionsdbupdate(value, false, &paths)
             }
+            ConfigSubCmd::ConnectTimeout { value } => {
+                run_command_config_network(NetworkSetting::ConnectTimeout, value, false, &paths)
+            }
+            ConfigSubCmd::ReadTimeout { value } => {
+                run_command_config_network(NetworkSetting::ReadTimeout, value, false, &paths)
+            }
+            ConfigSubCmd::MaxDownloadRate { value } => run_command_config_download_rate(
+                DownloadRateSetting::Foreground,
+                value,
+                false,
+                &paths,
+            ),
+            ConfigSubCmd::MaxBackgroundDownloadRate { value } => run_command_config_download_rate(
+                DownloadRateSetting::Background,
+                value,
+                false,
+                &paths,
+            ),
+            ConfigSubCmd::DownloadRetries { value } => {
+                run_command_config_network(NetworkSetting::DownloadRetries, value, false, &paths)
+            }
             ConfigSubCmd::AutoInstallChannels { value } => {
                 run_command_config_autoinstall(value, false, &paths)
             }
+            ConfigSubCmd::LauncherTools { value } => {
+                run_command_config_launchertools(value, false, &paths)
+            }
+            #[cfg(target_os = "linux")]
+            ConfigSubCmd::Libc { value } => run_command_config_libc(value, false, &paths),
             ConfigSubCmd::ManifestVersionDetect { value } => {
                 run_command_config_manifestversiondetect(value, false, &paths)
             }
+            ConfigSubCmd::Server { value } => {
+                run_command_config_server(JuliaupServer::Release, value, false, &paths)
+            }
+            ConfigSubCmd::NightlyServer { value } => {
+                run_command_config_server(JuliaupServer::Nightly, value, false, &paths)
+            }
+            ConfigSubCmd::PrServer { value } => {
+                run_command_config_server(JuliaupServer::Pr, value, false, &paths)
+            }
+            ConfigSubCmd::CaBundle { value } => {
+                run_command_config_tls(TlsSetting::CaBundle, value, false, &paths)
+            }
+            ConfigSubCmd::ClientCertificate { value } => {
+                run_command_config_tls(TlsSetting::ClientCertificate, value, false, &paths)
+            }
+            ConfigSubCmd::ClientKey { value } => {
+                run_command_config_tls(TlsSetting::ClientKey, value, false, &paths)
+            }
         },
         Juliaup::Api { command } => run_command_api(&command, &paths),
         Juliaup::InitialSetupFromLauncher {} => run_command_initial_setup_from_launcher(&paths),
-        Juliaup::UpdateVersionDb {} => run_command_update_version_db(&paths),
+        Juliaup::UpdateVersionDb {} => {
+            mark_background_downloads();
+            run_command_update_version_db(&paths)
+        }
         Juliaup::OverrideSubCmd(subcmd) => match subcmd {
             OverrideSubCmd::Status {} => run_command_override_status(&paths),
             OverrideSubCmd::Set { channel, path } => {
@@ -148,16 +247,37 @@ fn main() -> Result<()> {
         },
         Juliaup::Info {} => run_command_info(&paths),
         #[cfg(feature = "selfupdate")]
-        Juliaup::SecretSelfUpdate {} => run_command_selfupdate(&paths),
+        Juliaup::SecretSelfUpdate {} => {
+            mark_background_downloads();
+            run_command_selfupdate(None, &paths)
+        }
         Juliaup::SelfSubCmd(subcmd) => match subcmd {
-            SelfSubCmd::Update {} => run_command_selfupdate(&paths),
+            #[cfg(feature = "selfupdate")]
+            SelfSubCmd::Update { limit_rate, to } => {
+                if let Some(rate) = limit_rate {
+                    set_download_rate_limit(rate);
+                }
+                run_command_selfupdate(to, &paths)
+            }
+            #[cfg(not(feature = "selfupdate"))]
+            SelfSubCmd::Update { limit_rate } => {
+                if let Some(rate) = limit_rate {
+                    set_download_rate_limit(rate);
+                }
+                run_command_selfupdate(&paths)
+            }
             #[cfg(feature = "selfupdate")]
             SelfSubCmd::Channel { channel } => run_command_selfchannel(channel, &paths),
+            SelfSubCmd::Changelog { since } => run_command_selfchangelog(since, &paths),
+            #[cfg(feature = "selfupdate")]
+            SelfSubCmd::Rollback {} => run_command_selfrollback(&paths),
             #[cfg(feature = "selfupdate")]
             SelfSubCmd::Uninstall {} => run_command_selfuninstall(&paths),
             #[cfg(not(feature = "selfupdate"))]
             SelfSubCmd::Uninstall {} => run_command_selfuninstall_unavailable(),
         },
+        Juliaup::ShellInit { shell, hook } => run_command_shell_init(shell, hook),
+        Juliaup::OverrideChannel {} => run_command_override_channel(&paths),
         Juliaup::ListChannels {} => run_command_list_channels(&paths),
         Juliaup::PostUpdate {} => run_command_post_update(&paths),
         Juliaup::Completions { shell } => {
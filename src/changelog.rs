@@ -0,0 +1,232 @@
+//! Juliaup release notes.
+//!
+//! The release workflow publishes `CHANGELOG.md` next to the juliaup channel
+//! version files on the juliaup server. The notes for the versions a
+//! self-update skips over are printed in condensed form after the update, and
+//! can be shown again with `juliaup self changelog`.
+
+use anyhow::{Context, Result};
+use semver::Version;
+
+use crate::operations::download_text;
+use crate::utils::get_juliaserver_base_url;
+
+const CHANGELOG_URL_PATH: &str = "juliaup/CHANGELOG.md";
+
+/// The release notes of a single juliaup version.
+#[derive(Debug, PartialEq)]
+pub struct ChangelogEntry {
+    pub version: Version,
+    pub date: Option<String>,
+    /// `(section, item)` pairs, e.g. `("Added", "Add `self changelog`.")`.
+    pub items: Vec<(String, String)>,
+}
+
+/// Parses a changelog in the "Keep a Changelog" format used by
+/// `CHANGELOG.md`. Headings that are not a version are skipped.
+pub fn parse_changelog(content: &str) -> Vec<ChangelogEntry> {
+    let mut entries: Vec<ChangelogEntry> = Vec::new();
+    let mut in_version = false;
+    let mut section = String::new();
+
+    for line in content.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            let (version, rest) = match heading.split_once(']') {
+                Some((version, rest)) => (version.trim_start_matches('['), rest),
+                None => (heading, ""),
+            };
+
+            match Version::parse(version.trim().trim_start_matches('v')) {
+                Ok(version) => {
+                    let date = rest.trim().trim_start_matches('-').trim().to_string();
+                    entries.push(ChangelogEntry {
+                        version,
+                        date: (!date.is_empty()).then_some(date),
+                        items: Vec::new(),
+                    });
+                    in_version = true;
+                }
+                Err(_) => {
+                    log::debug!("Skipping changelog heading `{}`.", heading);
+                    in_version = false;
+                }
+            }
+            section.clear();
+        } else if let Some(heading) = line.strip_prefix("### ") {
+            section = heading.trim().to_string();
+        } else if let Some(entry) = entries.last_mut().filter(|_| in_version) {
+            let trimmed = line.trim();
+
+            if let Some(item) = trimmed.strip_prefix("- ") {
+                entry.items.push((section.clone(), item.to_string()));
+            } else if !trimmed.is_empty() && line.starts_with(' ') {
+                // Continuation of a wrapped list item.
+                if let Some((_, item)) = entry.items.last_mut() {
+                    item.push(' ');
+                    item.push_str(trimmed);
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+/// The entries newer than `from` and no newer than `to`, newest first.
+pub fn entries_between<'a>(
+    entries: &'a [ChangelogEntry],
+    from: &Version,
+    to: &Version,
+) -> Vec<&'a ChangelogEntry> {
+    let mut result: Vec<&ChangelogEntry> = entries
+        .iter()
+        .filter(|entry| &entry.version > from && &entry.version <= to)
+        .collect();
+
+    result.sort_by(|a, b| b.version.cmp(&a.version));
+
+    result
+}
+
+/// Renders entries as a short plain text summary, one line per item.
+pub fn render_condensed(entries: &[&ChangelogEntry]) -> String {
+    let mut output = String::new();
+
+    for entry in entries {
+        match &entry.date {
+            Some(date) => output.push_str(&format!("{} ({})\n", entry.version, date)),
+            None => output.push_str(&format!("{}\n", entry.version)),
+        }
+
+        for (section, item) in &entry.items {
+            if section.is_empty() {
+                output.push_str(&format!("  - {}\n", item));
+            } else {
+                output.push_str(&format!("  - {}: {}\n", section, item));
+            }
+        }
+    }
+
+    output
+}
+
+/// Downloads and parses the published juliaup changelog.
+pub fn download_changelog() -> Result<Vec<ChangelogEntry>> {
+    let juliaupserver_base =
+        get_juliaserver_base_url().with_context(|| "Failed to get Juliaup server base URL.")?;
+
+    let changelog_url = juliaupserver_base
+        .join(CHANGELOG_URL_PATH)
+        .with_context(|| {
+            format!(
+                "Failed to construct a valid url from '{}' and '{}'.",
+                juliaupserver_base, CHANGELOG_URL_PATH
+            )
+        })?;
+
+    let content = download_text(changelog_url.as_ref())
+        .with_context(|| "Failed to download the juliaup changelog.")?;
+
+    Ok(parse_changelog(&content))
+}
+
+/// Prints the condensed release notes for the versions after `from` up to
+/// `to`. Release notes are a courtesy, so failing to fetch them is only
+/// logged.
+pub fn print_release_notes(from: &Version, to: &Version) {
+    match download_changelog() {
+        Ok(entries) => {
+            let entries = entries_between(&entries, from, to);
+
+            if !entries.is_empty() {
+                eprintln!();
+                eprintln!("What's new in Juliaup:");
+                eprint!("{}", render_condensed(&entries));
+                eprintln!("Run `juliaup self changelog` to see these notes again.");
+            }
+        }
+        Err(e) => log::debug!("Not showing the juliaup changelog: {:#}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog
+<!-- markdownlint-disable MD024 -->
+
+## [Unreleased]
+
+- Not released yet.
+
+## [1.19.3] - 1/19/2026
+
+### Fixed
+
+- Return valid file paths for alias channels in API.
+
+## [1.19.0] - 1/16/2026
+
+### Added
+
+- Add option to match against environment manifest version before using the default channel.
+  Opt-in with `juliaup config manifestversiondetect true`.
+
+### Fixed
+
+- Add error handling to notarization.
+
+## [1.18.9] - 10/30/2025
+
+### Fixed
+
+- Make `save_config_db` atomic.
+";
+
+    #[test]
+    fn parses_versions_sections_and_wrapped_items() {
+        let entries = parse_changelog(CHANGELOG);
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].version, Version::new(1, 19, 0));
+        assert_eq!(entries[1].date.as_deref(), Some("1/16/2026"));
+        assert_eq!(
+            entries[1].items,
+            vec![
+                (
+                    "Added".to_string(),
+                    "Add option to match against environment manifest version before using the default channel. Opt-in with `juliaup config manifestversiondetect true`.".to_string()
+                ),
+                (
+                    "Fixed".to_string(),
+                    "Add error handling to notarization.".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn selects_versions_after_from_up_to_to() {
+        let entries = parse_changelog(CHANGELOG);
+
+        let between = entries_between(&entries, &Version::new(1, 18, 9), &Version::new(1, 19, 3));
+        let versions: Vec<String> = between.iter().map(|e| e.version.to_string()).collect();
+        assert_eq!(versions, vec!["1.19.3", "1.19.0"]);
+
+        assert!(
+            entries_between(&entries, &Version::new(1, 19, 3), &Version::new(1, 19, 3)).is_empty()
+        );
+    }
+
+    #[test]
+    fn renders_one_line_per_item() {
+        let entries = parse_changelog(CHANGELOG);
+        let between = entries_between(&entries, &Version::new(1, 19, 0), &Version::new(1, 19, 3));
+
+        assert_eq!(
+            render_condensed(&between),
+            "1.19.3 (1/19/2026)\n  - Fixed: Return valid file paths for alias channels in API.\n"
+        );
+    }
+}
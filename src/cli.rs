@@ -1,4 +1,5 @@
 use crate::cli_styles;
+use crate::download_rate::parse_rate;
 use clap::{Parser, ValueEnum};
 
 /// Shell options for completions
@@ -12,6 +13,14 @@ pub enum CompletionShell {
     Zsh,
 }
 
+/// Shell options for `shell-init`
+#[derive(Clone, Copy, ValueEnum)]
+pub enum InitShell {
+    Bash,
+    Fish,
+    Zsh,
+}
+
 #[derive(Parser)]
 #[clap(name = "Juliaup", version)]
 #[command(
@@ -24,7 +33,12 @@ pub enum Juliaup {
     /// Set the default Julia version
     Default { channel: String },
     /// Add a specific Julia version or channel to your system. Access via `julia +{channel}` e.g. `julia +1.6`
-    Add { channel: String },
+    Add {
+        channel: String,
+        /// Limit the download rate, e.g. 500K or 2M bytes per second
+        #[clap(long, value_name = "RATE", value_parser = parse_rate)]
+        limit_rate: Option<u64>,
+    },
     /// Link an existing Julia binary or channel to a custom channel name
     Link {
         /// Name of the new channel to create
@@ -41,17 +55,45 @@ pub enum Juliaup {
     OverrideSubCmd(OverrideSubCmd),
     #[clap(alias = "up")]
     /// Update all or a specific channel to the latest Julia version
-    Update { channel: Option<String> },
+    Update {
+        channel: Option<String>,
+        /// Limit the download rate, e.g. 500K or 2M bytes per second
+        #[clap(long, value_name = "RATE", value_parser = parse_rate)]
+        limit_rate: Option<u64>,
+    },
     #[clap(alias = "rm")]
     /// Remove a Julia version from your system
     Remove { channel: String },
     #[clap(alias = "st")]
     /// Show all installed Julia versions
-    Status {},
+    Status {
+        /// Also show how much disk space each installation uses
+        #[clap(long)]
+        size: bool,
+    },
     /// Garbage collect uninstalled Julia versions
     Gc {
         #[clap(long)]
         prune_linked: bool,
+        /// Also remove PR channels whose pull request was merged or closed
+        #[clap(long)]
+        prune_finished_prs: bool,
+    },
+    /// Check the Juliaup installation for common problems
+    Doctor {},
+    /// Reconcile juliaup.json with the Julia installations on disk
+    Repair {},
+    /// Find the first Julia release for which a command fails
+    Bisect {
+        /// A Julia version or channel for which the command succeeds
+        #[clap(long)]
+        good: String,
+        /// A Julia version or channel for which the command fails, e.g. `nightly`
+        #[clap(long)]
+        bad: String,
+        /// The command to run with each candidate, e.g. `julia script.jl`. Exit code 0 means good, 125 means skip
+        #[clap(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
     },
     #[clap(subcommand, name = "config")]
     /// Juliaup configuration
@@ -71,6 +113,17 @@ pub enum Juliaup {
         #[arg(value_enum, value_name = "SHELL")]
         shell: CompletionShell,
     },
+    /// Print shell code that puts Juliaup on PATH and sets up completions, for use with `eval`
+    ShellInit {
+        #[arg(value_enum, value_name = "SHELL")]
+        shell: InitShell,
+        /// Set JULIAUP_CHANNEL from directory overrides whenever the working directory changes
+        #[clap(long)]
+        hook: bool,
+    },
+    #[clap(name = "_override-channel", hide = true)]
+    /// Print the channel of the directory override for the working directory (used by `shell-init --hook`)
+    OverrideChannel {},
     #[clap(name = "_list-channels", hide = true)]
     /// List installed channel names (used internally for shell completions)
     ListChannels {},
@@ -83,10 +136,27 @@ pub enum Juliaup {
     // This is used for the cron jobs that we create. By using this UUID for the command
     // We can identify the cron jobs that were created by juliaup for uninstall purposes
     #[cfg(feature = "selfupdate")]
-    #[clap(name = "4c79c12db1d34bbbab1f6c6f838f423f", hide = true)]
+    #[clap(name = crate::operations::SECRET_SELFUPDATE_COMMAND, hide = true)]
     SecretSelfUpdate {},
 }
 
+impl Juliaup {
+    /// Whether the command installs or removes Julia versions or channels.
+    /// `gc` is left out, it cleans up interrupted downloads itself.
+    pub fn modifies_installations(&self) -> bool {
+        matches!(
+            self,
+            Juliaup::Default { .. }
+                | Juliaup::Add { .. }
+                | Juliaup::Link { .. }
+                | Juliaup::Update { .. }
+                | Juliaup::Remove { .. }
+                | Juliaup::Repair {}
+                | Juliaup::Bisect { .. }
+        )
+    }
+}
+
 #[derive(Parser)]
 #[command(styles = cli_styles::get_styles())]
 /// Manage directory overrides
@@ -132,17 +202,37 @@ pub enum SelfSubCmd {
     #[cfg(not(feature = "selfupdate"))]
     #[clap(alias = "up")]
     /// Update the Julia versions database
-    Update {},
+    Update {
+        /// Limit the download rate, e.g. 500K or 2M bytes per second
+        #[clap(long, value_name = "RATE", value_parser = parse_rate)]
+        limit_rate: Option<u64>,
+    },
     #[cfg(feature = "selfupdate")]
     #[clap(alias = "up")]
     /// Update the Julia versions database and juliaup itself
-    Update {},
+    Update {
+        /// Limit the download rate, e.g. 500K or 2M bytes per second
+        #[clap(long, value_name = "RATE", value_parser = parse_rate)]
+        limit_rate: Option<u64>,
+        /// Install and pin this juliaup version, or `latest` to remove the pin
+        #[clap(long, value_name = "VERSION")]
+        to: Option<String>,
+    },
     #[cfg(feature = "selfupdate")]
     /// Configure the channel to use for juliaup updates. Leave CHANNEL blank to see current channel.
     Channel {
         #[arg(value_enum)]
         channel: Option<JuliaupChannel>,
     },
+    /// Show the release notes of juliaup versions since the last self-update
+    Changelog {
+        /// Show the release notes of all versions after this one
+        #[clap(long, value_name = "VERSION")]
+        since: Option<String>,
+    },
+    #[cfg(feature = "selfupdate")]
+    /// Restore the juliaup version that was replaced by the last self-update
+    Rollback {},
     #[cfg(feature = "selfupdate")]
     /// Uninstall this version of juliaup from the system
     Uninstall {},
@@ -161,6 +251,12 @@ pub enum ConfigSubCmd {
         /// New Value
         value: Option<bool>,
     },
+    #[clap(name = "channelshims")]
+    /// Create a separate launcher shim per channel, e.g. `julia-1.10` for `julia +1.10`
+    ChannelShims {
+        /// New Value
+        value: Option<bool>,
+    },
     #[cfg(feature = "selfupdate")]
     #[clap(name = "backgroundselfupdateinterval")]
     /// The time between automatic background updates of Juliaup in minutes, use 0 to disable.
@@ -182,12 +278,52 @@ pub enum ConfigSubCmd {
         /// New value
         value: Option<bool>,
     },
+    /// Remove PR channels whose pull request was merged or closed when running `juliaup update`
+    #[clap(name = "autoprunefinishedprs")]
+    AutoPruneFinishedPrs {
+        /// New value
+        value: Option<bool>,
+    },
     /// The time between automatic updates of the versions database in minutes, use 0 to disable.
     #[clap(name = "versionsdbupdateinterval")]
     VersionsDbUpdateInterval {
         /// New value
         value: Option<i64>,
     },
+    /// Seconds to wait for a connection to a download server, use 0 to wait forever.
+    /// Not supported on Windows.
+    #[clap(name = "connecttimeout")]
+    ConnectTimeout {
+        /// New value
+        value: Option<i64>,
+    },
+    /// Seconds to wait for a download server to respond or send more data, use 0 to wait forever.
+    /// Not supported on Windows.
+    #[clap(name = "readtimeout")]
+    ReadTimeout {
+        /// New value
+        value: Option<i64>,
+    },
+    /// Maximum download rate in bytes per second, e.g. 500K or 2M, use 0 for unlimited.
+    #[clap(name = "maxdownloadrate")]
+    MaxDownloadRate {
+        /// New value
+        #[clap(value_parser = parse_rate)]
+        value: Option<u64>,
+    },
+    /// Maximum download rate for background updates, e.g. 500K or 2M, use 0 for unlimited.
+    #[clap(name = "maxbackgrounddownloadrate")]
+    MaxBackgroundDownloadRate {
+        /// New value
+        #[clap(value_parser = parse_rate)]
+        value: Option<u64>,
+    },
+    /// How often a failed download is retried, with exponential backoff, before giving up.
+    #[clap(name = "downloadretries")]
+    DownloadRetries {
+        /// New value
+        value: Option<i64>,
+    },
     /// Whether to automatically install Julia channels requested from the command line.
     /// When set to true, 'julia +channel' will automatically install missing channels.
     /// When false, users will not be prompted and shown an error.
@@ -197,10 +333,71 @@ pub enum ConfigSubCmd {
         /// New value: true, false, or default
         value: Option<String>,
     },
+    /// Comma separated Julia tools, e.g. juliac, to run from the selected channel like `julia`.
+    /// Use an empty string to remove all.
+    #[clap(name = "launchertools")]
+    LauncherTools {
+        /// New value
+        value: Option<String>,
+    },
+    /// The C library of the Julia builds that channels install by default: glibc or musl.
+    /// x86_64 channels from Julia 1.6 on are also available with a `~glibc` or `~musl` suffix.
+    /// Linux only; other architectures always install glibc builds.
+    #[cfg(target_os = "linux")]
+    #[clap(name = "libc")]
+    Libc {
+        /// New value: glibc, musl, or default to detect it from the system
+        value: Option<String>,
+    },
     /// Enable Julia version selection from manifests
     #[clap(name = "manifestversiondetect")]
     ManifestVersionDetect {
         /// New value
         value: Option<bool>,
     },
+    /// The server to download Julia versions and the versions database from.
+    /// The JULIAUP_SERVER environment variable takes precedence over this setting.
+    #[clap(name = "server")]
+    Server {
+        /// New value: a URL, or default
+        value: Option<String>,
+    },
+    /// The server to download nightly Julia builds from.
+    /// The JULIAUP_NIGHTLY_SERVER environment variable takes precedence over this setting.
+    #[clap(name = "nightlyserver")]
+    NightlyServer {
+        /// New value: a URL, or default
+        value: Option<String>,
+    },
+    /// The server to download Julia pull request builds from.
+    /// The JULIAUP_PR_SERVER environment variable takes precedence over this setting.
+    #[clap(name = "prserver")]
+    PrServer {
+        /// New value: a URL, or default
+        value: Option<String>,
+    },
+    /// A PEM file of additional CA certificates to trust, e.g. for a TLS-inspecting proxy.
+    /// The JULIAUP_CA_BUNDLE environment variable takes precedence over this setting,
+    /// SSL_CERT_FILE is used if neither is set. Not supported on Windows.
+    #[clap(name = "cabundle")]
+    CaBundle {
+        /// New value: a file path, or default
+        value: Option<String>,
+    },
+    /// A PEM client certificate for mirrors that require mutual TLS.
+    /// The JULIAUP_CLIENT_CERT environment variable takes precedence over this setting.
+    /// Not supported on Windows.
+    #[clap(name = "clientcertificate")]
+    ClientCertificate {
+        /// New value: a file path, or default
+        value: Option<String>,
+    },
+    /// The PEM (PKCS#8) private key for the client certificate.
+    /// The JULIAUP_CLIENT_KEY environment variable takes precedence over this setting.
+    /// Not supported on Windows.
+    #[clap(name = "clientkey")]
+    ClientKey {
+        /// New value: a file path, or default
+        value: Option<String>,
+    },
 }
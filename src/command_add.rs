@@ -5,7 +5,8 @@ use crate::global_paths::GlobalPaths;
 #[cfg(not(windows))]
 use crate::operations::create_symlink;
 use crate::operations::{
-    channel_to_name, commit_version_install, download_version_to_temp, install_non_db_version,
+    channel_to_name, commit_version_install, compatible_archs, create_channel_shim,
+    download_version_to_temp, install_non_db_version, is_pr_channel, parse_staged_build_pin,
     update_version_db,
 };
 use crate::utils::{print_juliaup_style, JuliaupMessageType};
@@ -54,9 +55,10 @@ fn commit_downloaded_channel(
 
 pub fn run_command_add(channel: &str, paths: &GlobalPaths) -> Result<()> {
     // This regex is dynamically compiled, but its runtime is negligible compared to downloading Julia
-    if Regex::new(r"^(?:pr\d+|nightly|\d+\.\d+-nightly)(?:~|$)")
-        .unwrap()
-        .is_match(channel)
+    if is_pr_channel(channel)
+        || Regex::new(r"^(?:nightly|\d+\.\d+-nightly)(?:@[^~]+)?(?:~|$)")
+            .unwrap()
+            .is_match(channel)
     {
         return add_non_db(channel, paths);
     }
@@ -69,11 +71,20 @@ pub fn run_command_add(channel: &str, paths: &GlobalPaths) -> Result<()> {
     let required_version = &version_db
         .available_channels
         .get(channel)
-        .ok_or_else(|| {
-            anyhow!(
+        .ok_or_else(|| match channel.split_once('~') {
+            // The versions db lists `~<arch>` variants for every architecture
+            // this system can run, so a known channel with an unknown suffix
+            // asks for a build that would not run here.
+            Some((base, arch)) if version_db.available_channels.contains_key(base) => anyhow!(
+                "'{}' is not available because {} builds of Julia cannot run on this system. Compatible architectures: {}.",
+                channel,
+                arch,
+                compatible_archs().unwrap_or_default().join(", ")
+            ),
+            _ => anyhow!(
                 "'{}' is not a valid Julia version or channel name.",
                 channel
-            )
+            ),
         })?
         .version;
 
@@ -115,6 +126,7 @@ pub fn run_command_add(channel: &str, paths: &GlobalPaths) -> Result<()> {
 
     #[cfg(not(windows))]
     let create_symlinks = config_file.data.settings.create_channel_symlinks;
+    let create_shims = config_file.data.settings.create_channel_shims;
 
     save_config_db(&mut config_file, paths).with_context(|| {
         format!(
@@ -135,6 +147,10 @@ pub fn run_command_add(channel: &str, paths: &GlobalPaths) -> Result<()> {
         )?;
     }
 
+    if create_shims {
+        create_channel_shim(channel)?;
+    }
+
     print_juliaup_style(
         "Add",
         &format!("Installed Julia channel '{}'", channel),
@@ -168,6 +184,13 @@ fn add_non_db(channel: &str, paths: &GlobalPaths) -> Result<()> {
             pr_number
         );
     }
+    if let Some(pin) = parse_staged_build_pin(channel).filter(|pin| pin.pr_number.is_none()) {
+        eprintln!("\nWARNING: Note that CI builds of arbitrary commits may not have been reviewed for security issues etc.");
+        eprintln!(
+            "         Review code at https://github.com/JuliaLang/julia/commit/{}\n",
+            pin.sha
+        );
+    }
 
     // Download and extract the version without holding the configuration lock.
     let name = channel_to_name(channel)?;
@@ -203,6 +226,10 @@ fn add_non_db(channel: &str, paths: &GlobalPaths) -> Result<()> {
         create_symlink(&config_channel, &format!("julia-{}", channel), paths)?;
     }
 
+    if config_file.data.settings.create_channel_shims {
+        create_channel_shim(channel)?;
+    }
+
     print_juliaup_style(
         "Add",
         &format!("Installed Julia channel '{}'", channel),
@@ -0,0 +1,601 @@
+use crate::config_file::{load_mut_config_db, save_config_db, JuliaupConfigChannel};
+use crate::global_paths::GlobalPaths;
+use crate::jsonstructs_versionsdb::JuliaupVersionDB;
+use crate::operations::{
+    commit_version_install, download_version_to_temp, garbage_collect_versions,
+    julia_launcher_binary, update_version_db,
+};
+use crate::utils::{print_juliaup_style, JuliaupMessageType};
+use crate::versions_file::load_versions_db;
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+use semver::{BuildMetadata, Version};
+
+/// Exit code of the test command that marks a version as untestable, the same
+/// convention as `git bisect run`.
+const SKIP_EXIT_CODE: i32 = 125;
+
+/// Prefix of the temporary channels that candidates are installed into.
+const BISECT_CHANNEL_PREFIX: &str = "bisect-";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BisectOutcome {
+    Good,
+    Bad,
+    Skip,
+}
+
+/// A release that takes part in the bisection.
+#[derive(Debug, Clone, PartialEq)]
+struct Candidate {
+    /// The version without build metadata, e.g. `1.10.3`.
+    version: Version,
+    /// The full version from the versions db, e.g. `1.10.3+0.x64.linux.gnu`.
+    full_version: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum BisectResult {
+    /// The first release for which the command fails.
+    FirstBad(Candidate),
+    /// The command succeeds for every release up to and including this one,
+    /// so the change only happened on the nightly side.
+    AfterLastRelease(Candidate),
+    /// Skipped releases sit right before `first_bad` (the nightly build if
+    /// `None`), so any of them could be the first bad one.
+    Ambiguous {
+        skipped: Vec<Candidate>,
+        first_bad: Option<Candidate>,
+    },
+}
+
+/// The upper end of the bisection range.
+#[derive(Debug, PartialEq)]
+enum BadBound {
+    Release(Candidate),
+    /// A nightly channel, optionally restricted to a `major.minor` series.
+    Nightly(Option<(u64, u64)>),
+}
+
+fn without_build(version: &Version) -> Version {
+    Version {
+        build: BuildMetadata::EMPTY,
+        ..version.clone()
+    }
+}
+
+fn resolve_release(version_db: &JuliaupVersionDB, name: &str) -> Result<Candidate> {
+    let full_version = &version_db
+        .available_channels
+        .get(name)
+        .ok_or_else(|| anyhow!("'{}' is not a valid Julia version or channel name.", name))?
+        .version;
+    let version = Version::parse(full_version)
+        .with_context(|| format!("Failed to parse Julia version `{}`.", full_version))?;
+
+    Ok(Candidate {
+        version: without_build(&version),
+        full_version: full_version.clone(),
+    })
+}
+
+fn resolve_bad_bound(version_db: &JuliaupVersionDB, name: &str) -> Result<BadBound> {
+    let caps = Regex::new(r"^(?:nightly|(\d+)\.(\d+)-nightly)$")
+        .unwrap()
+        .captures(name);
+
+    match caps {
+        Some(caps) => Ok(BadBound::Nightly(caps.get(1).zip(caps.get(2)).map(
+            |(major, minor)| {
+                (
+                    major.as_str().parse().unwrap(),
+                    minor.as_str().parse().unwrap(),
+                )
+            },
+        ))),
+        None => Ok(BadBound::Release(resolve_release(version_db, name)?)),
+    }
+}
+
+/// The stable releases strictly between `good` and `bad`, oldest first.
+fn releases_between(
+    version_db: &JuliaupVersionDB,
+    good: &Candidate,
+    bad: &BadBound,
+) -> Vec<Candidate> {
+    let mut releases: Vec<Candidate> = version_db
+        .available_channels
+        .iter()
+        .filter_map(|(name, channel)| {
+            // Only the channels that name one exact version, e.g. `1.10.3`.
+            let version = Version::parse(name).ok()?;
+            Some(Candidate {
+                version,
+                full_version: channel.version.clone(),
+            })
+        })
+        .filter(|candidate| candidate.version.pre.is_empty() && candidate.version > good.version)
+        .filter(|candidate| match bad {
+            BadBound::Release(bad) => candidate.version < bad.version,
+            BadBound::Nightly(Some(series)) => {
+                (candidate.version.major, candidate.version.minor) <= *series
+            }
+            BadBound::Nightly(None) => true,
+        })
+        .collect();
+
+    releases.sort_by(|a, b| a.version.cmp(&b.version));
+    releases
+}
+
+/// Binary search over `candidates`, assuming `good` succeeds and `bad`
+/// fails (or, without a `bad` release, that the nightly build fails).
+fn bisect(
+    good: Candidate,
+    mut candidates: Vec<Candidate>,
+    bad: Option<Candidate>,
+    mut test: impl FnMut(&Candidate, usize) -> Result<BisectOutcome>,
+) -> Result<BisectResult> {
+    // `lo` is the index of the latest known good version, `hi` the index of
+    // the earliest known bad one (`candidates.len()` if that is the nightly).
+    candidates.insert(0, good);
+    let has_bad_release = bad.is_some();
+    candidates.extend(bad);
+
+    let mut lo = 0;
+    let mut hi = if has_bad_release {
+        candidates.len() - 1
+    } else {
+        candidates.len()
+    };
+
+    // Skipped candidates stay in the range, so that they can be reported if
+    // the first bad version ends up next to them.
+    let mut skipped = vec![false; candidates.len()];
+    loop {
+        let untested: Vec<usize> = (lo + 1..hi).filter(|&i| !skipped[i]).collect();
+        // The untested candidate closest to the middle of the range.
+        let middle = lo + (hi - lo) / 2;
+        let mid = match untested.iter().min_by_key(|&&i| i.abs_diff(middle)) {
+            Some(&mid) => mid,
+            None => break,
+        };
+
+        match test(&candidates[mid], untested.len())? {
+            BisectOutcome::Good => lo = mid,
+            BisectOutcome::Bad => hi = mid,
+            BisectOutcome::Skip => skipped[mid] = true,
+        }
+    }
+
+    let first_bad = candidates.get(hi).cloned();
+    Ok(if hi - lo > 1 {
+        BisectResult::Ambiguous {
+            skipped: candidates[lo + 1..hi].to_vec(),
+            first_bad,
+        }
+    } else {
+        match first_bad {
+            Some(candidate) => BisectResult::FirstBad(candidate),
+            None => BisectResult::AfterLastRelease(candidates[lo].clone()),
+        }
+    })
+}
+
+fn bisect_channel_name(candidate: &Candidate) -> String {
+    format!("{}{}", BISECT_CHANNEL_PREFIX, candidate.version)
+}
+
+/// Installs `candidate` (unless it is installed already) and points a
+/// temporary channel at it.
+fn install_candidate(
+    candidate: &Candidate,
+    version_db: &JuliaupVersionDB,
+    paths: &GlobalPaths,
+) -> Result<String> {
+    let channel = bisect_channel_name(candidate);
+
+    let downloaded = {
+        let config_file = crate::config_file::load_config_db(paths, None)
+            .with_context(|| "`bisect` command failed to load configuration data.")?;
+        if config_file
+            .data
+            .installed_versions
+            .contains_key(&candidate.full_version)
+        {
+            None
+        } else {
+            Some(download_version_to_temp(
+                &candidate.full_version,
+                version_db,
+                paths,
+            )?)
+        }
+    };
+
+    let mut config_file = load_mut_config_db(paths)
+        .with_context(|| "`bisect` command failed to load configuration data.")?;
+    if let Some(downloaded) = downloaded {
+        commit_version_install(
+            downloaded,
+            &candidate.full_version,
+            &mut config_file.data,
+            paths,
+        )?;
+    }
+    config_file.data.installed_channels.insert(
+        channel.clone(),
+        JuliaupConfigChannel::SystemChannel {
+            version: candidate.full_version.clone(),
+        },
+    );
+    save_config_db(&mut config_file, paths).with_context(|| {
+        format!(
+            "Failed to save configuration file from `bisect` command at `{}`.",
+            paths.juliaupconfig.display()
+        )
+    })?;
+
+    Ok(channel)
+}
+
+/// Runs the test command with `JULIAUP_CHANNEL` set to `channel`. A leading
+/// `julia` is replaced by the Julia launcher of this juliaup installation, so
+/// that it is not shadowed by some other `julia` on the `PATH`.
+fn run_test_command(command: &[String], channel: &str) -> Result<BisectOutcome> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| anyhow!("No command to run was given."))?;
+
+    let program = if program == "julia" {
+        julia_launcher_binary()?
+    } else {
+        program.into()
+    };
+
+    let status = std::process::Command::new(&program)
+        .args(args)
+        .env("JULIAUP_CHANNEL", channel)
+        .status()
+        .with_context(|| format!("Failed to run `{}`.", program.display()))?;
+
+    match status.code() {
+        Some(0) => Ok(BisectOutcome::Good),
+        Some(SKIP_EXIT_CODE) => Ok(BisectOutcome::Skip),
+        // Like `git bisect run`, a crash is a failure, not an error of the
+        // bisection itself.
+        Some(_) | None => Ok(BisectOutcome::Bad),
+    }
+}
+
+/// The temporary channels of an earlier bisection that was interrupted.
+fn leftover_bisect_channels(paths: &GlobalPaths) -> Result<Vec<String>> {
+    let config_file = crate::config_file::load_config_db(paths, None)
+        .with_context(|| "`bisect` command failed to load configuration data.")?;
+
+    Ok(config_file
+        .data
+        .installed_channels
+        .keys()
+        .filter(|name| name.starts_with(BISECT_CHANNEL_PREFIX))
+        .cloned()
+        .collect())
+}
+
+/// Removes the temporary `channels` and every Julia version that is no
+/// longer used by a channel.
+fn remove_bisect_channels(channels: &[String], paths: &GlobalPaths) -> Result<()> {
+    let mut config_file = load_mut_config_db(paths)
+        .with_context(|| "`bisect` command failed to load configuration data.")?;
+
+    for channel in channels {
+        config_file.data.installed_channels.remove(channel);
+    }
+
+    garbage_collect_versions(false, &mut config_file.data, paths)?;
+
+    save_config_db(&mut config_file, paths).with_context(|| {
+        format!(
+            "Failed to save configuration file from `bisect` command at `{}`.",
+            paths.juliaupconfig.display()
+        )
+    })
+}
+
+pub fn run_command_bisect(
+    good: &str,
+    bad: &str,
+    command: &[String],
+    paths: &GlobalPaths,
+) -> Result<()> {
+    let leftovers = leftover_bisect_channels(paths)?;
+    if !leftovers.is_empty() {
+        print_juliaup_style(
+            "Tidyup",
+            &format!(
+                "Removing channels of an interrupted bisection: {}",
+                leftovers.join(", ")
+            ),
+            JuliaupMessageType::Progress,
+        );
+        remove_bisect_channels(&leftovers, paths)?;
+    }
+
+    update_version_db(&None, paths).with_context(|| "Failed to update versions db.")?;
+    let version_db =
+        load_versions_db(paths).with_context(|| "`bisect` command failed to load versions db.")?;
+
+    let good = resolve_release(&version_db, good)?;
+    let bad_bound = resolve_bad_bound(&version_db, bad)?;
+    if let BadBound::Release(bad) = &bad_bound {
+        if bad.version <= good.version {
+            bail!(
+                "The bad version {} must be newer than the good version {}.",
+                bad.version,
+                good.version
+            );
+        }
+    }
+
+    let candidates = releases_between(&version_db, &good, &bad_bound);
+    let bad_release = match bad_bound {
+        BadBound::Release(bad) => Some(bad),
+        BadBound::Nightly(_) => None,
+    };
+
+    print_juliaup_style(
+        "Bisect",
+        &format!(
+            "{} releases between {} and {}",
+            candidates.len(),
+            good.version,
+            bad
+        ),
+        JuliaupMessageType::Progress,
+    );
+
+    let mut bisect_channels = Vec::new();
+    let result = bisect(good, candidates, bad_release, |candidate, remaining| {
+        print_juliaup_style(
+            "Bisect",
+            &format!(
+                "Testing Julia {} ({} left, about {} steps)",
+                candidate.version,
+                remaining,
+                (remaining + 1).next_power_of_two().trailing_zeros()
+            ),
+            JuliaupMessageType::Progress,
+        );
+
+        let channel = install_candidate(candidate, &version_db, paths)?;
+        bisect_channels.push(channel.clone());
+        let outcome = run_test_command(command, &channel)?;
+
+        print_juliaup_style(
+            "Bisect",
+            &format!(
+                "Julia {} is {}",
+                candidate.version,
+                match outcome {
+                    BisectOutcome::Good => "good",
+                    BisectOutcome::Bad => "bad",
+                    BisectOutcome::Skip => "skipped",
+                }
+            ),
+            JuliaupMessageType::Progress,
+        );
+
+        Ok(outcome)
+    });
+
+    // Clean up whether or not the bisection finished.
+    let cleanup = remove_bisect_channels(&bisect_channels, paths);
+    let result = result?;
+    cleanup?;
+
+    match result {
+        BisectResult::FirstBad(candidate) => print_juliaup_style(
+            "Bisect",
+            &format!("Julia {} is the first bad version.", candidate.version),
+            JuliaupMessageType::Success,
+        ),
+        BisectResult::AfterLastRelease(candidate) => print_juliaup_style(
+            "Bisect",
+            &format!(
+                "Julia {} is the last good release, the change happened after it on `{}`.",
+                candidate.version, bad
+            ),
+            JuliaupMessageType::Success,
+        ),
+        BisectResult::Ambiguous { skipped, first_bad } => {
+            let skipped = skipped
+                .iter()
+                .map(|candidate| candidate.version.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let message = match first_bad {
+                Some(candidate) => format!(
+                    "The first bad version is one of Julia {}, {}. The skipped {} could not be tested.",
+                    skipped, candidate.version, skipped
+                ),
+                None => format!(
+                    "The change happened in one of Julia {} or after them on `{}`. The skipped {} could not be tested.",
+                    skipped, bad, skipped
+                ),
+            };
+            print_juliaup_style("Bisect", &message, JuliaupMessageType::Warning);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonstructs_versionsdb::{JuliaupVersionDBChannel, JuliaupVersionDBVersion};
+    use std::collections::HashMap;
+
+    fn version_db(versions: &[&str]) -> JuliaupVersionDB {
+        let mut db = JuliaupVersionDB {
+            available_versions: HashMap::new(),
+            available_channels: HashMap::new(),
+            version: "1".to_string(),
+        };
+        for version in versions {
+            let full_version = format!("{}+0.x64.linux.gnu", version);
+            db.available_versions.insert(
+                full_version.clone(),
+                JuliaupVersionDBVersion {
+                    url_path: String::new(),
+                },
+            );
+            db.available_channels.insert(
+                version.to_string(),
+                JuliaupVersionDBChannel {
+                    version: full_version.clone(),
+                },
+            );
+            db.available_channels.insert(
+                format!("{}~x64", version),
+                JuliaupVersionDBChannel {
+                    version: full_version,
+                },
+            );
+        }
+        db
+    }
+
+    fn versions(candidates: &[Candidate]) -> Vec<String> {
+        candidates.iter().map(|c| c.version.to_string()).collect()
+    }
+
+    #[test]
+    fn candidates_are_the_releases_in_range() -> Result<()> {
+        let db = version_db(&[
+            "1.9.4",
+            "1.10.0",
+            "1.10.1",
+            "1.11.0-rc1",
+            "1.11.0",
+            "1.11.1",
+            "1.12.0",
+        ]);
+        let good = resolve_release(&db, "1.10.0")?;
+        assert_eq!(good.full_version, "1.10.0+0.x64.linux.gnu");
+
+        let bad = resolve_bad_bound(&db, "1.12.0")?;
+        assert_eq!(
+            versions(&releases_between(&db, &good, &bad)),
+            ["1.10.1", "1.11.0", "1.11.1"]
+        );
+
+        let bad = resolve_bad_bound(&db, "1.11-nightly")?;
+        assert_eq!(bad, BadBound::Nightly(Some((1, 11))));
+        assert_eq!(
+            versions(&releases_between(&db, &good, &bad)),
+            ["1.10.1", "1.11.0", "1.11.1"]
+        );
+
+        let bad = resolve_bad_bound(&db, "nightly")?;
+        assert_eq!(
+            versions(&releases_between(&db, &good, &bad)),
+            ["1.10.1", "1.11.0", "1.11.1", "1.12.0"]
+        );
+
+        assert!(resolve_bad_bound(&db, "0.1.0").is_err());
+        Ok(())
+    }
+
+    fn run_bisect(
+        first_bad: &str,
+        skipped: &[&str],
+        with_bad_release: bool,
+    ) -> Result<(BisectResult, Vec<String>)> {
+        let db = version_db(&[
+            "1.0.0", "1.1.0", "1.2.0", "1.3.0", "1.4.0", "1.5.0", "1.6.0", "1.7.0",
+        ]);
+        let good = resolve_release(&db, "1.0.0")?;
+        let bad = if with_bad_release {
+            resolve_bad_bound(&db, "1.7.0")?
+        } else {
+            resolve_bad_bound(&db, "nightly")?
+        };
+        let candidates = releases_between(&db, &good, &bad);
+        let bad_release = match bad {
+            BadBound::Release(bad) => Some(bad),
+            BadBound::Nightly(_) => None,
+        };
+
+        let first_bad = Version::parse(first_bad)?;
+        let mut tested = Vec::new();
+        let result = bisect(good, candidates, bad_release, |candidate, _| {
+            tested.push(candidate.version.to_string());
+            Ok(
+                if skipped.contains(&candidate.version.to_string().as_str()) {
+                    BisectOutcome::Skip
+                } else if candidate.version >= first_bad {
+                    BisectOutcome::Bad
+                } else {
+                    BisectOutcome::Good
+                },
+            )
+        })?;
+        Ok((result, tested))
+    }
+
+    fn first_bad_version(result: &BisectResult) -> String {
+        match result {
+            BisectResult::FirstBad(candidate) => candidate.version.to_string(),
+            BisectResult::AfterLastRelease(candidate) => format!("after {}", candidate.version),
+            BisectResult::Ambiguous { skipped, first_bad } => format!(
+                "one of {:?} or {}",
+                versions(skipped),
+                first_bad
+                    .as_ref()
+                    .map_or("nightly".to_string(), |c| c.version.to_string())
+            ),
+        }
+    }
+
+    #[test]
+    fn bisect_finds_the_first_bad_release() -> Result<()> {
+        for first_bad in ["1.1.0", "1.3.0", "1.6.0", "1.7.0"] {
+            let (result, tested) = run_bisect(first_bad, &[], true)?;
+            assert_eq!(first_bad_version(&result), first_bad);
+            assert!(tested.len() <= 3);
+            assert!(!tested.contains(&"1.7.0".to_string()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn bisect_reports_skipped_releases_next_to_the_first_bad_one() -> Result<()> {
+        let (result, _) = run_bisect("1.4.0", &["1.4.0"], true)?;
+        assert_eq!(first_bad_version(&result), r#"one of ["1.4.0"] or 1.5.0"#);
+
+        // Skipped releases far from the first bad one do not matter.
+        let (result, tested) = run_bisect("1.5.0", &["1.3.0"], true)?;
+        assert_eq!(first_bad_version(&result), "1.5.0");
+        assert!(tested.contains(&"1.3.0".to_string()));
+
+        let (result, _) = run_bisect("2.0.0", &["1.6.0", "1.7.0"], false)?;
+        assert_eq!(
+            first_bad_version(&result),
+            r#"one of ["1.6.0", "1.7.0"] or nightly"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bisect_against_nightly_can_end_after_the_last_release() -> Result<()> {
+        let (result, tested) = run_bisect("1.5.0", &[], false)?;
+        assert_eq!(first_bad_version(&result), "1.5.0");
+        assert!(tested.len() <= 3);
+
+        let (result, _) = run_bisect("2.0.0", &[], false)?;
+        assert_eq!(first_bad_version(&result), "after 1.7.0");
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::config_file::{load_config_db, load_mut_config_db, save_config_db};
+use crate::global_paths::GlobalPaths;
+use crate::operations::{create_channel_shim, remove_channel_shim};
+use crate::utils::{print_juliaup_style, JuliaupMessageType};
+use anyhow::{Context, Result};
+
+pub fn run_command_config_channelshims(
+    value: Option<bool>,
+    quiet: bool,
+    paths: &GlobalPaths,
+) -> Result<()> {
+    match value {
+        Some(value) => {
+            let mut config_file = load_mut_config_db(paths)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            let mut value_changed = false;
+
+            if value != config_file.data.settings.create_channel_shims {
+                config_file.data.settings.create_channel_shims = value;
+                value_changed = true;
+
+                for (channel_name, _channel) in &config_file.data.installed_channels {
+                    if value {
+                        create_channel_shim(channel_name)?;
+                    } else {
+                        remove_channel_shim(channel_name)?;
+
+                        // Bring back the symlinks the shims were standing in for.
+                        #[cfg(not(windows))]
+                        if config_file.data.settings.create_channel_symlinks {
+                            crate::operations::create_symlink(
+                                _channel,
+                                &format!("julia-{}", channel_name),
+                                paths,
+                            )?;
+                        }
+                    }
+                }
+            }
+
+            save_config_db(&mut config_file, paths).with_context(|| {
+                format!(
+                    "Failed to save configuration file from `config` command at `{}`.",
+                    paths.juliaupconfig.display()
+                )
+            })?;
+
+            if !quiet {
+                if value_changed {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!("Property 'channelshims' set to '{}'", value),
+                        JuliaupMessageType::Success,
+                    );
+                } else {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!("Property 'channelshims' is already set to '{}'", value),
+                        JuliaupMessageType::Success,
+                    );
+                }
+            }
+        }
+        None => {
+            let config_file = load_config_db(paths, None)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            if !quiet {
+                print_juliaup_style(
+                    "Configure",
+                    &format!(
+                        "Property 'channelshims' set to '{}'",
+                        config_file.data.settings.create_channel_shims
+                    ),
+                    JuliaupMessageType::Success,
+                );
+            }
+        }
+    };
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::config_file::JuliaupConfigSettings;
+use crate::config_file::{load_config_db, load_mut_config_db, save_config_db};
+use crate::download_rate::format_rate;
+use crate::utils::{print_juliaup_style, JuliaupMessageType};
+use anyhow::{Context, Result};
+
+/// The download rate limits that can be changed with `juliaup config`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DownloadRateSetting {
+    Foreground,
+    Background,
+}
+
+impl DownloadRateSetting {
+    fn config_property(&self) -> &'static str {
+        match self {
+            DownloadRateSetting::Foreground => "maxdownloadrate",
+            DownloadRateSetting::Background => "maxbackgrounddownloadrate",
+        }
+    }
+
+    fn value_mut<'a>(&self, settings: &'a mut JuliaupConfigSettings) -> &'a mut u64 {
+        match self {
+            DownloadRateSetting::Foreground => &mut settings.max_download_rate,
+            DownloadRateSetting::Background => &mut settings.max_background_download_rate,
+        }
+    }
+
+    fn value(&self, settings: &JuliaupConfigSettings) -> u64 {
+        match self {
+            DownloadRateSetting::Foreground => settings.max_download_rate,
+            DownloadRateSetting::Background => settings.max_background_download_rate,
+        }
+    }
+}
+
+pub fn run_command_config_download_rate(
+    setting: DownloadRateSetting,
+    value: Option<u64>,
+    quiet: bool,
+    paths: &crate::global_paths::GlobalPaths,
+) -> Result<()> {
+    let property = setting.config_property();
+
+    match value {
+        Some(value) => {
+            let mut config_file = load_mut_config_db(paths)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            let mut value_changed = false;
+            let current_value = setting.value_mut(&mut config_file.data.settings);
+
+            if value != *current_value {
+                *current_value = value;
+
+                value_changed = true;
+            }
+
+            save_config_db(&mut config_file, paths).with_context(|| {
+                format!(
+                    "Failed to save configuration file from `config` command at `{}`.",
+                    paths.juliaupconfig.display()
+                )
+            })?;
+
+            if !quiet {
+                if value_changed {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!("Property '{}' set to '{}'", property, format_rate(value)),
+                        JuliaupMessageType::Success,
+                    );
+                } else {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!(
+                            "Property '{}' is already set to '{}'",
+                            property,
+                            format_rate(value)
+                        ),
+                        JuliaupMessageType::Success,
+                    );
+                }
+            }
+        }
+        None => {
+            let config_file = load_config_db(paths, None)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            if !quiet {
+                print_juliaup_style(
+                    "Configure",
+                    &format!(
+                        "Property '{}' set to '{}'",
+                        property,
+                        format_rate(setting.value(&config_file.data.settings))
+                    ),
+                    JuliaupMessageType::Success,
+                );
+            }
+        }
+    };
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+
+use crate::config_file::{load_config_db, load_mut_config_db, save_config_db};
+use crate::global_paths::GlobalPaths;
+use crate::launcher_tools::{create_tool_entry, remove_tool_entry, validate_tool_name};
+use crate::utils::{print_juliaup_style, JuliaupMessageType};
+
+fn parse_tool_list(value: &str) -> Result<Vec<String>> {
+    let mut tools: Vec<String> = Vec::new();
+
+    for tool in value.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        validate_tool_name(tool)?;
+        if !tools.iter().any(|t| t == tool) {
+            tools.push(tool.to_string());
+        }
+    }
+
+    Ok(tools)
+}
+
+pub fn run_command_config_launchertools(
+    value: Option<String>,
+    quiet: bool,
+    paths: &GlobalPaths,
+) -> Result<()> {
+    match value {
+        Some(value) => {
+            let value = parse_tool_list(&value)?;
+
+            let mut config_file = load_mut_config_db(paths)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            let mut value_changed = false;
+
+            if value != config_file.data.settings.launcher_tools {
+                for tool in &config_file.data.settings.launcher_tools {
+                    if !value.contains(tool) {
+                        remove_tool_entry(tool)?;
+                    }
+                }
+                for tool in &value {
+                    if !config_file.data.settings.launcher_tools.contains(tool) {
+                        create_tool_entry(tool)?;
+                    }
+                }
+
+                config_file.data.settings.launcher_tools = value.clone();
+                value_changed = true;
+            }
+
+            save_config_db(&mut config_file, paths).with_context(|| {
+                format!(
+                    "Failed to save configuration file from `config` command at `{}`.",
+                    paths.juliaupconfig.display()
+                )
+            })?;
+
+            if !quiet {
+                if value_changed {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!("Property 'launchertools' set to '{}'", value.join(",")),
+                        JuliaupMessageType::Success,
+                    );
+                } else {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!(
+                            "Property 'launchertools' is already set to '{}'",
+                            value.join(",")
+                        ),
+                        JuliaupMessageType::Success,
+                    );
+                }
+            }
+        }
+        None => {
+            let config_file = load_config_db(paths, None)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            if !quiet {
+                print_juliaup_style(
+                    "Configure",
+                    &format!(
+                        "Property 'launchertools' set to '{}'",
+                        config_file.data.settings.launcher_tools.join(",")
+                    ),
+                    JuliaupMessageType::Success,
+                );
+            }
+        }
+    };
+
+    Ok(())
+}
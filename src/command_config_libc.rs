@@ -0,0 +1,75 @@
+use crate::libc_flavor::{detect_libc, LibcFlavor};
+use anyhow::Result;
+
+pub fn run_command_config_libc(
+    value: Option<String>,
+    quiet: bool,
+    paths: &crate::global_paths::GlobalPaths,
+) -> Result<()> {
+    use crate::config_file::{load_config_db, load_mut_config_db, save_config_db};
+    use crate::utils::{print_juliaup_style, JuliaupMessageType};
+    use anyhow::Context;
+
+    match value {
+        Some(value_str) => {
+            let mut config_file = load_mut_config_db(paths)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            let mut value_changed = false;
+            let new_value = match value_str.to_lowercase().as_str() {
+                "default" => None,
+                _ => Some(LibcFlavor::parse(&value_str)?.name().to_string()),
+            };
+
+            if new_value != config_file.data.settings.libc {
+                config_file.data.settings.libc = new_value.clone();
+                value_changed = true;
+            }
+
+            save_config_db(&mut config_file, paths).with_context(|| {
+                format!(
+                    "Failed to save configuration file from `config` command at `{}`.",
+                    paths.juliaupconfig.display()
+                )
+            })?;
+
+            if !quiet {
+                let display_value = new_value.unwrap_or_else(|| "default (not set)".to_string());
+
+                if value_changed {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!("Property 'libc' set to '{}'", display_value),
+                        JuliaupMessageType::Success,
+                    );
+                } else {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!("Property 'libc' is already set to '{}'", display_value),
+                        JuliaupMessageType::Success,
+                    );
+                }
+            }
+        }
+        None => {
+            let config_file = load_config_db(paths, None)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            if !quiet {
+                print_juliaup_style(
+                    "Configure",
+                    &format!(
+                        "Property 'libc' set to '{}'",
+                        config_file.data.settings.libc.unwrap_or_else(|| format!(
+                            "default (detected: {})",
+                            detect_libc().name()
+                        ))
+                    ),
+                    JuliaupMessageType::Success,
+                );
+            }
+        }
+    };
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use crate::config_file::JuliaupConfigSettings;
+use crate::config_file::{load_config_db, load_mut_config_db, save_config_db};
+use crate::utils::{print_juliaup_style, JuliaupMessageType};
+use anyhow::{bail, Context, Result};
+
+/// The network settings that can be changed with `juliaup config`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NetworkSetting {
+    ConnectTimeout,
+    ReadTimeout,
+    DownloadRetries,
+}
+
+impl NetworkSetting {
+    fn config_property(&self) -> &'static str {
+        match self {
+            NetworkSetting::ConnectTimeout => "connecttimeout",
+            NetworkSetting::ReadTimeout => "readtimeout",
+            NetworkSetting::DownloadRetries => "downloadretries",
+        }
+    }
+
+    fn value_mut<'a>(&self, settings: &'a mut JuliaupConfigSettings) -> &'a mut i64 {
+        match self {
+            NetworkSetting::ConnectTimeout => &mut settings.connect_timeout,
+            NetworkSetting::ReadTimeout => &mut settings.read_timeout,
+            NetworkSetting::DownloadRetries => &mut settings.download_retries,
+        }
+    }
+
+    fn value(&self, settings: &JuliaupConfigSettings) -> i64 {
+        match self {
+            NetworkSetting::ConnectTimeout => settings.connect_timeout,
+            NetworkSetting::ReadTimeout => settings.read_timeout,
+            NetworkSetting::DownloadRetries => settings.download_retries,
+        }
+    }
+}
+
+pub fn run_command_config_network(
+    setting: NetworkSetting,
+    value: Option<i64>,
+    quiet: bool,
+    paths: &crate::global_paths::GlobalPaths,
+) -> Result<()> {
+    let property = setting.config_property();
+
+    match value {
+        Some(value) => {
+            if value < 0 {
+                bail!("Invalid argument.");
+            }
+
+            let mut config_file = load_mut_config_db(paths)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            let mut value_changed = false;
+            let current_value = setting.value_mut(&mut config_file.data.settings);
+
+            if value != *current_value {
+                *current_value = value;
+
+                value_changed = true;
+            }
+
+            save_config_db(&mut config_file, paths).with_context(|| {
+                format!(
+                    "Failed to save configuration file from `config` command at `{}`.",
+                    paths.juliaupconfig.display()
+                )
+            })?;
+
+            if !quiet {
+                if value_changed {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!("Property '{}' set to '{}'", property, value),
+                        JuliaupMessageType::Success,
+                    );
+                } else {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!("Property '{}' is already set to '{}'", property, value),
+                        JuliaupMessageType::Success,
+                    );
+                }
+            }
+        }
+        None => {
+            let config_file = load_config_db(paths, None)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            if !quiet {
+                print_juliaup_style(
+                    "Configure",
+                    &format!(
+                        "Property '{}' set to '{}'",
+                        property,
+                        setting.value(&config_file.data.settings)
+                    ),
+                    JuliaupMessageType::Success,
+                );
+            }
+        }
+    };
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anyhow::{Context, Result};
+
+use crate::config_file::{load_config_db, load_mut_config_db, save_config_db};
+use crate::global_paths::GlobalPaths;
+use crate::utils::{parse_server_url, print_juliaup_style, JuliaupMessageType, JuliaupServer};
+
+fn setting_mut(
+    settings: &mut crate::config_file::JuliaupConfigSettings,
+    server: JuliaupServer,
+) -> &mut Option<String> {
+    match server {
+        JuliaupServer::Release => &mut settings.server,
+        JuliaupServer::Nightly => &mut settings.nightly_server,
+        JuliaupServer::Pr => &mut settings.pr_server,
+    }
+}
+
+fn display_value(value: &Option<String>) -> String {
+    value
+        .clone()
+        .unwrap_or_else(|| "default (not set)".to_string())
+}
+
+pub fn run_command_config_server(
+    server: JuliaupServer,
+    value: Option<String>,
+    quiet: bool,
+    paths: &GlobalPaths,
+) -> Result<()> {
+    let property = server.config_property();
+
+    match value {
+        Some(value_str) => {
+            let new_value = if value_str.to_lowercase() == "default" {
+                None
+            } else {
+                let origin = format!("`juliaup config {}`", property);
+                parse_server_url(&value_str, &origin)?;
+                Some(value_str)
+            };
+
+            let mut config_file = load_mut_config_db(paths)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            let mut value_changed = false;
+            let current_value = setting_mut(&mut config_file.data.settings, server);
+
+            if new_value != *current_value {
+                *current_value = new_value.clone();
+                value_changed = true;
+            }
+
+            save_config_db(&mut config_file, paths).with_context(|| {
+                format!(
+                    "Failed to save configuration file from `config` command at `{}`.",
+                    paths.juliaupconfig.display()
+                )
+            })?;
+
+            if !quiet {
+                if value_changed {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!(
+                            "Property '{}' set to '{}'",
+                            property,
+                            display_value(&new_value)
+                        ),
+                        JuliaupMessageType::Success,
+                    );
+                } else {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!(
+                            "Property '{}' is already set to '{}'",
+                            property,
+                            display_value(&new_value)
+                        ),
+                        JuliaupMessageType::Success,
+                    );
+                }
+
+                if std::env::var(server.env_var()).is_ok() {
+                    print_juliaup_style(
+                        "Warning",
+                        &format!(
+                            "The environment variable {} is set and takes precedence over this setting.",
+                            server.env_var()
+                        ),
+                        JuliaupMessageType::Warning,
+                    );
+                }
+            }
+        }
+        None => {
+            let config_file = load_config_db(paths, None)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            if !quiet {
+                print_juliaup_style(
+                    "Configure",
+                    &format!(
+                        "Property '{}' set to '{}'",
+                        property,
+                        display_value(&server.configured_value(&config_file.data.settings))
+                    ),
+                    JuliaupMessageType::Success,
+                );
+            }
+        }
+    };
+
+    Ok(())
+}
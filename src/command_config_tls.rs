@@ -0,0 +1,113 @@
+use anyhow::{bail, Context, Result};
+
+use crate::config_file::{load_config_db, load_mut_config_db, save_config_db};
+use crate::global_paths::GlobalPaths;
+use crate::tls::TlsSetting;
+use crate::utils::{print_juliaup_style, JuliaupMessageType};
+
+fn display_value(value: &Option<String>) -> String {
+    value
+        .clone()
+        .unwrap_or_else(|| "default (not set)".to_string())
+}
+
+pub fn run_command_config_tls(
+    setting: TlsSetting,
+    value: Option<String>,
+    quiet: bool,
+    paths: &GlobalPaths,
+) -> Result<()> {
+    let property = setting.config_property();
+
+    match value {
+        Some(value_str) => {
+            let new_value = if value_str.to_lowercase() == "default" {
+                None
+            } else {
+                let path = std::path::absolute(&value_str)
+                    .with_context(|| format!("Failed to resolve the path `{}`.", value_str))?;
+                if !path.is_file() {
+                    bail!("The file `{}` does not exist.", path.display());
+                }
+                Some(path.to_string_lossy().into_owned())
+            };
+
+            let mut config_file = load_mut_config_db(paths)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            let settings = &mut config_file.data.settings;
+            let current_value = match setting {
+                TlsSetting::CaBundle => &mut settings.ca_bundle,
+                TlsSetting::ClientCertificate => &mut settings.client_certificate,
+                TlsSetting::ClientKey => &mut settings.client_key,
+            };
+
+            let mut value_changed = false;
+
+            if new_value != *current_value {
+                *current_value = new_value.clone();
+                value_changed = true;
+            }
+
+            save_config_db(&mut config_file, paths).with_context(|| {
+                format!(
+                    "Failed to save configuration file from `config` command at `{}`.",
+                    paths.juliaupconfig.display()
+                )
+            })?;
+
+            if !quiet {
+                if value_changed {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!(
+                            "Property '{}' set to '{}'",
+                            property,
+                            display_value(&new_value)
+                        ),
+                        JuliaupMessageType::Success,
+                    );
+                } else {
+                    print_juliaup_style(
+                        "Configure",
+                        &format!(
+                            "Property '{}' is already set to '{}'",
+                            property,
+                            display_value(&new_value)
+                        ),
+                        JuliaupMessageType::Success,
+                    );
+                }
+
+                if std::env::var_os(setting.env_var()).is_some() {
+                    print_juliaup_style(
+                        "Warning",
+                        &format!(
+                            "The environment variable {} is set and takes precedence over this setting.",
+                            setting.env_var()
+                        ),
+                        JuliaupMessageType::Warning,
+                    );
+                }
+            }
+        }
+        None => {
+            let config_file = load_config_db(paths, None)
+                .with_context(|| "`config` command failed to load configuration data.")?;
+
+            if !quiet {
+                print_juliaup_style(
+                    "Configure",
+                    &format!(
+                        "Property '{}' set to '{}'",
+                        property,
+                        display_value(&setting.configured_value(&config_file.data.settings))
+                    ),
+                    JuliaupMessageType::Success,
+                );
+            }
+        }
+    };
+
+    Ok(())
+}
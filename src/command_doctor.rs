@@ -0,0 +1,571 @@
+use crate::config_file::{is_config_locked, load_config_db, JuliaupConfig, JuliaupConfigChannel};
+use crate::global_paths::GlobalPaths;
+use crate::jsonstructs_versionsdb::JuliaupVersionDB;
+use crate::operations::{is_pr_channel, server_responds};
+use crate::utils::{
+    get_bin_dir, is_valid_julia_path, print_juliaup_style, resolve_server, JuliaupMessageType,
+    JuliaupServer,
+};
+use crate::versions_file::load_versions_db;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// A versions db that has not been refreshed for this long is reported.
+const STALE_VERSION_DB_DAYS: i64 = 7;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug)]
+struct Check {
+    status: CheckStatus,
+    message: String,
+    /// What the user can do about a warning or failure.
+    hint: Option<String>,
+}
+
+impl Check {
+    fn pass(message: impl Into<String>) -> Check {
+        Check {
+            status: CheckStatus::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(message: impl Into<String>, hint: impl Into<String>) -> Check {
+        Check {
+            status: CheckStatus::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(message: impl Into<String>, hint: impl Into<String>) -> Check {
+        Check {
+            status: CheckStatus::Fail,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn print(&self) {
+        let (action, message_type) = match self.status {
+            CheckStatus::Pass => ("Pass", JuliaupMessageType::Success),
+            CheckStatus::Warn => ("Warn", JuliaupMessageType::Warning),
+            CheckStatus::Fail => ("Fail", JuliaupMessageType::Error),
+        };
+        print_juliaup_style(action, &self.message, message_type);
+        if let Some(hint) = &self.hint {
+            print_juliaup_style("", &format!("hint: {}", hint), message_type);
+        }
+    }
+}
+
+/// All files called `name` in the directories of `path_var`, in `PATH` order.
+fn find_on_path(name: &str, path_var: &OsStr) -> Vec<PathBuf> {
+    let file_name = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(&file_name))
+        .filter(|candidate| candidate.is_file())
+        .collect()
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Whether `julia` resolves to the launcher in `bin_dir`, given every `julia`
+/// found on `PATH` in order.
+fn check_launcher_on_path(bin_dir: &Path, julias: &[PathBuf]) -> Vec<Check> {
+    let position = julias
+        .iter()
+        .position(|julia| julia.parent().is_some_and(|dir| same_dir(dir, bin_dir)));
+
+    let mut checks = Vec::new();
+
+    match position {
+        None => checks.push(Check::fail(
+            format!(
+                "The Juliaup bin directory `{}` is not on PATH.",
+                bin_dir.display()
+            ),
+            "Add it to PATH, e.g. with `eval \"$(juliaup shell-init bash)\"` or `juliaup config modifypath true`.",
+        )),
+        Some(0) => checks.push(Check::pass(format!(
+            "`julia` on PATH is the Juliaup launcher in `{}`.",
+            bin_dir.display()
+        ))),
+        Some(_) => {}
+    }
+
+    for (i, julia) in julias.iter().enumerate() {
+        if Some(i) == position {
+            continue;
+        }
+        if position.is_none_or(|position| i < position) {
+            checks.push(Check::fail(
+                format!("`{}` shadows the Juliaup launcher.", julia.display()),
+                format!(
+                    "Remove it, or move `{}` before its directory on PATH.",
+                    bin_dir.display()
+                ),
+            ));
+        } else {
+            checks.push(Check::warn(
+                format!(
+                    "`{}` is also on PATH, after the Juliaup launcher.",
+                    julia.display()
+                ),
+                "It is not used by `julia`, but other tools may pick it up. Consider uninstalling it.",
+            ));
+        }
+    }
+
+    checks
+}
+
+/// Cross-checks the channels, versions, overrides and the default in
+/// `juliaup.json` against each other and against the disk.
+fn check_config(
+    config: &JuliaupConfig,
+    versions_db: &JuliaupVersionDB,
+    juliauphome: &Path,
+) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let mut channels: Vec<_> = config.installed_channels.iter().collect();
+    channels.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (name, channel) in channels {
+        match channel {
+            JuliaupConfigChannel::SystemChannel { version } => {
+                if !config.installed_versions.contains_key(version) {
+                    checks.push(Check::fail(
+                        format!(
+                            "Channel '{}' points at Julia {}, which is not installed.",
+                            name, version
+                        ),
+                        "Run `juliaup repair` to download it again.",
+                    ));
+                }
+            }
+            JuliaupConfigChannel::DirectDownloadChannel { path, .. } => {
+                if !juliauphome.join(path).is_dir() {
+                    checks.push(Check::fail(
+                        format!(
+                            "The installation of channel '{}' at `{}` is missing.",
+                            name,
+                            juliauphome.join(path).display()
+                        ),
+                        "Run `juliaup repair` to download it again.",
+                    ));
+                }
+            }
+            JuliaupConfigChannel::LinkedChannel { command, .. } => {
+                if !is_valid_julia_path(&PathBuf::from(command)) {
+                    checks.push(Check::warn(
+                        format!(
+                            "Linked channel '{}' points at `{}`, which does not exist.",
+                            name, command
+                        ),
+                        "Run `juliaup gc --prune-linked` to remove linked channels whose target is gone.",
+                    ));
+                }
+            }
+            JuliaupConfigChannel::AliasChannel { target, .. } => {
+                if !config.installed_channels.contains_key(target)
+                    && !versions_db.has_channel(target)
+                {
+                    checks.push(Check::fail(
+                        format!(
+                            "Alias '{}' points at '{}', which is neither installed nor a known channel.",
+                            name, target
+                        ),
+                        format!(
+                            "Run `juliaup remove {}` or link it to another channel.",
+                            name
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut versions: Vec<_> = config.installed_versions.iter().collect();
+    versions.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (version, detail) in versions {
+        let path = juliauphome.join(&detail.path);
+        if !path.is_dir() {
+            checks.push(Check::fail(
+                format!(
+                    "The installation of Julia {} at `{}` is missing.",
+                    version,
+                    path.display()
+                ),
+                "Run `juliaup repair` to download it again.",
+            ));
+        }
+    }
+
+    if let Some(default) = &config.default {
+        if !config.installed_channels.contains_key(default) {
+            checks.push(Check::fail(
+                format!("The default channel '{}' is not installed.", default),
+                format!(
+                    "Run `juliaup add {}` or pick another one with `juliaup default`.",
+                    default
+                ),
+            ));
+        }
+    }
+
+    for o in &config.overrides {
+        if !config.installed_channels.contains_key(&o.channel) {
+            checks.push(Check::warn(
+                format!(
+                    "The override for `{}` uses channel '{}', which is not installed.",
+                    o.path, o.channel
+                ),
+                format!(
+                    "Run `juliaup add {}` or `juliaup override unset --path {}`.",
+                    o.channel, o.path
+                ),
+            ));
+        }
+    }
+
+    if checks.is_empty() {
+        checks.push(Check::pass(
+            "All channels and versions in juliaup.json are installed.",
+        ));
+    }
+
+    checks
+}
+
+/// `julia-*` symlinks in the bin directory whose target no longer exists.
+fn check_symlinks(bin_dir: &Path) -> Vec<Check> {
+    let mut broken: Vec<PathBuf> = std::fs::read_dir(bin_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("julia-"))
+                && path.symlink_metadata().is_ok_and(|m| m.is_symlink())
+                && std::fs::metadata(path).is_err()
+        })
+        .collect();
+    broken.sort();
+
+    if broken.is_empty() {
+        return vec![Check::pass("No broken `julia-*` symlinks.")];
+    }
+
+    broken
+        .into_iter()
+        .map(|path| {
+            Check::warn(
+                format!("The symlink `{}` is broken.", path.display()),
+                "Run `juliaup repair` to recreate it, or delete it if its channel was removed.",
+            )
+        })
+        .collect()
+}
+
+fn check_lock(paths: &GlobalPaths) -> Check {
+    match is_config_locked(paths) {
+        Ok(false) => Check::pass("The configuration lock is free."),
+        Ok(true) => Check::warn(
+            format!(
+                "The configuration lock `{}` is held by another process.",
+                paths.lockfile.display()
+            ),
+            "Wait for other juliaup commands to finish. If none are running, the lock may be stuck on a network file system; delete the lock file.",
+        ),
+        Err(e) => Check::fail(
+            format!("Failed to check the configuration lock: {:#}", e),
+            "Make sure the Juliaup home directory is writable.",
+        ),
+    }
+}
+
+fn check_version_db_age(last_update: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Check {
+    match last_update {
+        None => Check::warn(
+            "The versions database has never been updated.",
+            "Run `juliaup update`.",
+        ),
+        Some(last_update) => {
+            let age = now - last_update;
+            if age > chrono::Duration::days(STALE_VERSION_DB_DAYS) {
+                Check::warn(
+                    format!(
+                        "The versions database was last updated {} days ago.",
+                        age.num_days()
+                    ),
+                    "Run `juliaup update`, and check `juliaup config versionsdbupdateinterval`.",
+                )
+            } else {
+                Check::pass(format!(
+                    "The versions database was updated at {}.",
+                    last_update.format("%Y-%m-%d %H:%M UTC")
+                ))
+            }
+        }
+    }
+}
+
+/// Checks that `server` is configured correctly and, if `in_use`, that it is
+/// reachable. The nightly and PR servers only matter once a channel is
+/// installed from them.
+fn check_server(label: &str, server: JuliaupServer, in_use: bool) -> Check {
+    let (url, source) = match resolve_server(server) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            return Check::fail(
+                format!("The {} is misconfigured: {:#}", label, e),
+                format!(
+                    "Fix `{}` or `juliaup config {}`.",
+                    server.env_var(),
+                    server.config_property()
+                ),
+            )
+        }
+    };
+
+    if !in_use {
+        return Check::pass(format!(
+            "The {} {} (from {}) is not used by any installed channel.",
+            label,
+            url,
+            source.describe(server)
+        ));
+    }
+
+    match server_responds(url.as_str()) {
+        Ok(true) => Check::pass(format!(
+            "The {} {} (from {}) is reachable.",
+            label,
+            url,
+            source.describe(server)
+        )),
+        _ => Check::fail(
+            format!(
+                "The {} {} (from {}) is not reachable.",
+                label,
+                url,
+                source.describe(server)
+            ),
+            "Check your network connection and proxy settings, or configure a mirror.",
+        ),
+    }
+}
+
+pub fn run_command_doctor(paths: &GlobalPaths) -> Result<()> {
+    let config_file = load_config_db(paths, None)
+        .with_context(|| "`doctor` command failed to load configuration data.")?;
+    let versions_db =
+        load_versions_db(paths).with_context(|| "`doctor` command failed to load versions db.")?;
+    let bin_dir = get_bin_dir()?;
+
+    let mut checks = check_launcher_on_path(
+        &bin_dir,
+        &find_on_path("julia", &std::env::var_os("PATH").unwrap_or_default()),
+    );
+    checks.extend(check_config(
+        &config_file.data,
+        &versions_db,
+        &paths.juliauphome,
+    ));
+    checks.extend(check_symlinks(&bin_dir));
+    checks.push(check_lock(paths));
+    checks.push(check_version_db_age(
+        config_file.data.last_version_db_update,
+        Utc::now(),
+    ));
+    let direct_download_channels: Vec<&String> = config_file
+        .data
+        .installed_channels
+        .iter()
+        .filter(|(_, channel)| {
+            matches!(channel, JuliaupConfigChannel::DirectDownloadChannel { .. })
+        })
+        .map(|(name, _)| name)
+        .collect();
+    let has_pr_channel = direct_download_channels
+        .iter()
+        .any(|name| is_pr_channel(name));
+    let has_nightly_channel = direct_download_channels
+        .iter()
+        .any(|name| !is_pr_channel(name));
+    for (label, server, in_use) in [
+        ("server", JuliaupServer::Release, true),
+        (
+            "nightly server",
+            JuliaupServer::Nightly,
+            has_nightly_channel,
+        ),
+        ("PR server", JuliaupServer::Pr, has_pr_channel),
+    ] {
+        checks.push(check_server(label, server, in_use));
+    }
+
+    for check in &checks {
+        check.print();
+    }
+
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    let (warnings, failures) = (count(CheckStatus::Warn), count(CheckStatus::Fail));
+
+    if failures > 0 {
+        bail!(
+            "{} check(s) failed and {} produced warnings.",
+            failures,
+            warnings
+        );
+    }
+
+    print_juliaup_style(
+        "Doctor",
+        &format!("All checks passed with {} warning(s).", warnings),
+        JuliaupMessageType::Success,
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::{JuliaupConfigVersion, JuliaupOverride};
+    use std::collections::HashMap;
+
+    fn statuses(checks: &[Check]) -> Vec<CheckStatus> {
+        checks.iter().map(|c| c.status).collect()
+    }
+
+    #[test]
+    fn launcher_shadowing_is_detected() {
+        let bin_dir = PathBuf::from("/opt/juliaup/bin");
+        let launcher = bin_dir.join("julia");
+        let other = PathBuf::from("/usr/bin/julia");
+
+        assert_eq!(
+            statuses(&check_launcher_on_path(
+                &bin_dir,
+                std::slice::from_ref(&launcher)
+            )),
+            vec![CheckStatus::Pass]
+        );
+        assert_eq!(
+            statuses(&check_launcher_on_path(
+                &bin_dir,
+                &[launcher.clone(), other.clone()]
+            )),
+            vec![CheckStatus::Pass, CheckStatus::Warn]
+        );
+        assert_eq!(
+            statuses(&check_launcher_on_path(
+                &bin_dir,
+                &[other.clone(), launcher]
+            )),
+            vec![CheckStatus::Fail]
+        );
+        assert_eq!(
+            statuses(&check_launcher_on_path(&bin_dir, &[other])),
+            vec![CheckStatus::Fail, CheckStatus::Fail]
+        );
+    }
+
+    #[test]
+    fn inconsistent_config_is_reported() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir(home.path().join("julia-1.10.0")).unwrap();
+
+        let versions_db = JuliaupVersionDB {
+            available_versions: HashMap::new(),
+            available_channels: HashMap::new(),
+            version: "1.0.0".to_string(),
+        };
+        let mut config = JuliaupConfig {
+            default: Some("1.10".to_string()),
+            ..Default::default()
+        };
+        config.installed_versions.insert(
+            "1.10.0".to_string(),
+            JuliaupConfigVersion {
+                path: "julia-1.10.0".to_string(),
+                binary_path: None,
+            },
+        );
+        config.installed_channels.insert(
+            "1.10".to_string(),
+            JuliaupConfigChannel::SystemChannel {
+                version: "1.10.0".to_string(),
+            },
+        );
+
+        assert_eq!(
+            statuses(&check_config(&config, &versions_db, home.path())),
+            vec![CheckStatus::Pass]
+        );
+
+        config.installed_channels.insert(
+            "1.11".to_string(),
+            JuliaupConfigChannel::SystemChannel {
+                version: "1.11.0".to_string(),
+            },
+        );
+        config.installed_channels.insert(
+            "dev".to_string(),
+            JuliaupConfigChannel::AliasChannel {
+                target: "gone".to_string(),
+                args: None,
+            },
+        );
+        config.overrides.push(JuliaupOverride {
+            path: "/work".to_string(),
+            channel: "lts".to_string(),
+        });
+        config.default = Some("release".to_string());
+
+        assert_eq!(
+            statuses(&check_config(&config, &versions_db, home.path())),
+            vec![
+                CheckStatus::Fail,
+                CheckStatus::Fail,
+                CheckStatus::Fail,
+                CheckStatus::Warn
+            ]
+        );
+    }
+
+    #[test]
+    fn old_version_db_is_reported() {
+        let now = Utc::now();
+
+        assert_eq!(
+            check_version_db_age(Some(now - chrono::Duration::hours(3)), now).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            check_version_db_age(Some(now - chrono::Duration::days(30)), now).status,
+            CheckStatus::Warn
+        );
+        assert_eq!(check_version_db_age(None, now).status, CheckStatus::Warn);
+    }
+}
@@ -3,7 +3,7 @@ use std::io::BufReader;
 use crate::config_file::load_config_db;
 use crate::jsonstructs_versionsdb::JuliaupVersionDB;
 use crate::operations::download_juliaup_version;
-use crate::utils::get_juliaserver_base_url;
+use crate::utils::{get_juliaserver_base_url, resolve_server, JuliaupServer};
 use crate::{get_bundled_dbversion, global_paths::GlobalPaths};
 use crate::{get_juliaup_target, get_own_version};
 use anyhow::{bail, Context, Result};
@@ -81,5 +81,36 @@ pub fn run_command_info(paths: &GlobalPaths) -> Result<()> {
     println!("Online version db: {}", online_dbversion);
     println!("Local version db: {:?}", local_dbversion);
 
+    for (label, server) in [
+        ("Server", JuliaupServer::Release),
+        ("Nightly server", JuliaupServer::Nightly),
+        ("PR server", JuliaupServer::Pr),
+    ] {
+        let (url, source) = resolve_server(server)?;
+        println!("{}: {} (from {})", label, url, source.describe(server));
+    }
+
+    #[cfg(feature = "selfupdate")]
+    {
+        use crate::operations::active_background_selfupdate_scheduler;
+
+        let scheduler = match active_background_selfupdate_scheduler() {
+            Ok(Some(scheduler)) => scheduler.description().to_string(),
+            Ok(None) => "none".to_string(),
+            Err(e) => format!("unknown ({:#})", e),
+        };
+
+        match config_file.self_data.background_selfupdate_interval {
+            Some(interval) => println!(
+                "Background self-update: every {} minutes via {}",
+                interval, scheduler
+            ),
+            None => println!(
+                "Background self-update: disabled (scheduler: {})",
+                scheduler
+            ),
+        }
+    }
+
     Ok(())
 }
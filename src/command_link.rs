@@ -1,6 +1,7 @@
 use crate::config_file::JuliaupConfigChannel;
 use crate::config_file::{load_mut_config_db, save_config_db};
 use crate::global_paths::GlobalPaths;
+use crate::operations::create_channel_shim;
 #[cfg(not(windows))]
 use crate::operations::create_symlink;
 use crate::operations::is_valid_channel;
@@ -94,6 +95,7 @@ pub fn run_command_link(
 
     #[cfg(not(windows))]
     let create_symlinks = config_file.data.settings.create_channel_symlinks;
+    let create_shims = config_file.data.settings.create_channel_shims;
 
     save_config_db(&mut config_file, paths).with_context(|| {
         format!(
@@ -118,5 +120,10 @@ pub fn run_command_link(
         )?;
     }
 
+    // Shims go through the launcher, so unlike symlinks they work for aliases too.
+    if create_shims {
+        create_channel_shim(channel)?;
+    }
+
     Ok(())
 }
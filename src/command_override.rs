@@ -23,6 +23,17 @@ struct OverrideRow {
     channel: String,
 }
 
+/// The channel of the most specific directory override that applies to
+/// `dir`, which must be canonicalized.
+pub fn find_override_channel(overrides: &[JuliaupOverride], dir: &Path) -> Option<String> {
+    overrides
+        .iter()
+        .filter(|i| dir.starts_with(&i.path))
+        .sorted_by_key(|i| i.path.len())
+        .next_back()
+        .map(|i| i.channel.clone())
+}
+
 pub fn run_command_override_status(paths: &GlobalPaths) -> Result<()> {
     let config_file = load_config_db(paths, None)
         .with_context(|| "`override status` command failed to load configuration file.")?;
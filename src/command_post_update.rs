@@ -22,13 +22,53 @@ pub fn run_command_post_update(paths: &GlobalPaths) -> Result<()> {
     if let Err(e) = restore_symlinks(&bin_path, paths) {
         eprintln!("Warning: failed to restore Julia symlinks: {e}");
     }
+    if let Err(e) = refresh_channel_shims(paths) {
+        eprintln!("Warning: failed to refresh channel shims: {e}");
+    }
+    if let Err(e) = refresh_launcher_tools(paths) {
+        eprintln!("Warning: failed to refresh launcher tool entries: {e}");
+    }
+
+    Ok(())
+}
+
+// Channel shims point at the launcher, which might have moved with the update.
+fn refresh_channel_shims(paths: &GlobalPaths) -> Result<()> {
+    use crate::config_file::load_config_db;
+    use crate::operations::create_channel_shim;
+    use anyhow::Context;
+
+    let config_file = load_config_db(paths, None)
+        .with_context(|| "Failed to load configuration db while refreshing channel shims.")?;
+    if config_file.data.settings.create_channel_shims {
+        for channel_name in config_file.data.installed_channels.keys() {
+            create_channel_shim(channel_name)?;
+        }
+    }
+
+    Ok(())
+}
+
+// On Windows the launcher tool entries are copies of the launcher, which
+// would otherwise keep running the old version.
+fn refresh_launcher_tools(paths: &GlobalPaths) -> Result<()> {
+    use crate::config_file::load_config_db;
+    use crate::launcher_tools::create_tool_entry;
+    use anyhow::Context;
+
+    let config_file = load_config_db(paths, None)
+        .with_context(|| "Failed to load configuration db while refreshing launcher tools.")?;
+    for tool in &config_file.data.settings.launcher_tools {
+        create_tool_entry(tool)?;
+    }
 
     Ok(())
 }
 
-// Self-update replaces the entire bin directory, which removes the `julia`
-// symlink (and any channel symlinks) that are not part of the juliaup tarball.
-// Recreate them here so `julia` keeps working after an update.
+// Older versions of self-update replaced the entire bin directory, which
+// removes the `julia` symlink (and any channel symlinks) that are not part of
+// the juliaup tarball. Recreate them here so `julia` keeps working after an
+// update from such a version.
 #[cfg(not(windows))]
 fn restore_symlinks(bin_path: &std::path::Path, paths: &GlobalPaths) -> Result<()> {
     use crate::config_file::load_config_db;
@@ -4,7 +4,7 @@ use crate::utils::{print_juliaup_style, JuliaupMessageType};
 use crate::{
     config_file::{load_mut_config_db, save_config_db, JuliaupConfigChannel},
     global_paths::GlobalPaths,
-    operations::garbage_collect_versions,
+    operations::{garbage_collect_versions, remove_channel_shim},
 };
 use anyhow::{bail, Context, Result};
 
@@ -74,6 +74,8 @@ pub fn run_command_remove(channel: &str, paths: &GlobalPaths) -> Result<()> {
 
     config_file.data.installed_channels.remove(channel);
 
+    remove_channel_shim(channel)?;
+
     #[cfg(not(windows))]
     remove_symlink(&format!("julia-{channel}"))?;
 
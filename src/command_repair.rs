@@ -0,0 +1,527 @@
+use crate::config_file::{
+    get_read_lock, load_config_db, load_mut_config_db, save_config_db, JuliaupConfig,
+    JuliaupConfigChannel, JuliaupConfigVersion,
+};
+use crate::global_paths::GlobalPaths;
+use crate::jsonstructs_versionsdb::JuliaupVersionDB;
+use crate::operations::{
+    commit_version_install, compute_relative_binary_path, create_channel_shim, create_symlink,
+    download_version_to_temp, install_from_url, is_pr_channel, query_julia_version,
+    remove_channel_shim, remove_symlink, update_version_db,
+};
+use crate::utils::{
+    get_bin_dir, print_juliaup_style, resolve_julia_binary_path, JuliaupMessageType,
+};
+use crate::versions_file::load_versions_db;
+use anyhow::{Context, Result};
+use semver::{BuildMetadata, Version};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn without_build(version: &Version) -> Version {
+    Version {
+        build: BuildMetadata::EMPTY,
+        ..version.clone()
+    }
+}
+
+/// `julia-<version>` directories in `juliauphome` that the configuration does
+/// not know about, with the full version encoded in their name.
+fn find_orphan_versions(config: &JuliaupConfig, juliauphome: &Path) -> Vec<(String, PathBuf)> {
+    let referenced: HashSet<String> = config
+        .installed_versions
+        .values()
+        .map(|version| version.path.as_str())
+        .chain(
+            config
+                .installed_channels
+                .values()
+                .filter_map(|channel| match channel {
+                    JuliaupConfigChannel::DirectDownloadChannel { path, .. } => Some(path.as_str()),
+                    _ => None,
+                }),
+        )
+        .filter_map(|path| Path::new(path).file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+
+    let mut orphans: Vec<(String, PathBuf)> = std::fs::read_dir(juliauphome)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let version = name.strip_prefix("julia-")?;
+            // Database versions always carry build metadata, e.g.
+            // `1.10.4+0.x64.linux.gnu`; channel directories like
+            // `julia-nightly` and temporary downloads never parse.
+            let parsed = Version::parse(version).ok()?;
+            if parsed.build.is_empty()
+                || referenced.contains(&name)
+                || config.installed_versions.contains_key(version)
+            {
+                return None;
+            }
+            Some((version.to_string(), entry.path()))
+        })
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+/// Whether the Julia in `dir` reports `version`. A binary that cannot be run
+/// here, e.g. one for another architecture, is trusted by its directory name.
+fn contains_julia_version(dir: &Path, version: &str) -> bool {
+    let binary = match resolve_julia_binary_path(dir) {
+        Ok(binary) if binary.exists() => binary,
+        _ => return false,
+    };
+    let expected = match Version::parse(version) {
+        Ok(expected) => without_build(&expected),
+        Err(_) => return false,
+    };
+
+    match query_julia_version(&binary) {
+        Ok(output) => {
+            Version::parse(output.trim()).is_ok_and(|reported| without_build(&reported) == expected)
+        }
+        Err(e) => {
+            log::debug!("Failed to run `{}`: {:?}", binary.display(), e);
+            true
+        }
+    }
+}
+
+/// Versions that are registered but missing on disk, or that a channel points
+/// at without them being registered.
+fn find_missing_versions(config: &JuliaupConfig, juliauphome: &Path) -> BTreeSet<String> {
+    let missing_dirs = config
+        .installed_versions
+        .iter()
+        .filter(|(_, detail)| !juliauphome.join(&detail.path).is_dir())
+        .map(|(version, _)| version.clone());
+
+    let unregistered = config
+        .installed_channels
+        .values()
+        .filter_map(|channel| match channel {
+            JuliaupConfigChannel::SystemChannel { version }
+                if !config.installed_versions.contains_key(version) =>
+            {
+                Some(version.clone())
+            }
+            _ => None,
+        });
+
+    missing_dirs.chain(unregistered).collect()
+}
+
+fn remove_channel(config: &mut JuliaupConfig, channel: &str, reason: &str) -> Result<()> {
+    config.installed_channels.remove(channel);
+    remove_channel_shim(channel)?;
+    remove_symlink(&format!("julia-{}", channel))?;
+
+    print_juliaup_style(
+        "Repair",
+        &format!("Removed channel '{}', {}.", channel, reason),
+        JuliaupMessageType::Warning,
+    );
+    Ok(())
+}
+
+/// A repair that has been prepared without holding the configuration lock,
+/// ready to be committed under the exclusive lock.
+enum PreparedRepair {
+    /// An installation on disk that the configuration does not know about.
+    Orphan { version: String, dir: PathBuf },
+    /// A missing version that has been downloaded again.
+    Version {
+        version: String,
+        downloaded: TempDir,
+    },
+    /// A missing version that can no longer be downloaded.
+    Unavailable { version: String },
+    /// A missing direct-download channel that `install_from_url` has already
+    /// put back on disk; only the config entry remains.
+    DirectDownload {
+        channel: String,
+        channel_data: JuliaupConfigChannel,
+    },
+}
+
+/// Phase 1 (no lock held): find what is broken in `config` and download
+/// everything that has to be re-installed.
+fn prepare_repairs(
+    config: &JuliaupConfig,
+    version_db: &JuliaupVersionDB,
+    paths: &GlobalPaths,
+    failures: &mut usize,
+) -> Vec<PreparedRepair> {
+    let mut repairs = Vec::new();
+
+    for (version, dir) in find_orphan_versions(config, &paths.juliauphome) {
+        if contains_julia_version(&dir, &version) {
+            repairs.push(PreparedRepair::Orphan { version, dir });
+        } else {
+            print_juliaup_style(
+                "Repair",
+                &format!(
+                    "Skipped `{}`, it does not contain Julia {}.",
+                    dir.display(),
+                    version
+                ),
+                JuliaupMessageType::Warning,
+            );
+        }
+    }
+
+    for version in find_missing_versions(config, &paths.juliauphome) {
+        if !version_db.available_versions.contains_key(&version) {
+            repairs.push(PreparedRepair::Unavailable { version });
+            continue;
+        }
+
+        match download_version_to_temp(&version, version_db, paths)
+            .with_context(|| format!("Failed to download Julia {}.", version))
+        {
+            Ok(downloaded) => repairs.push(PreparedRepair::Version {
+                version,
+                downloaded,
+            }),
+            Err(e) => {
+                *failures += 1;
+                print_juliaup_style(
+                    "Failed",
+                    &format!("to repair Julia {}: {:#}", version, e),
+                    JuliaupMessageType::Error,
+                );
+            }
+        }
+    }
+
+    for (channel, detail) in &config.installed_channels {
+        let (path, url) = match detail {
+            JuliaupConfigChannel::DirectDownloadChannel { path, url, .. }
+                if !paths.juliauphome.join(path).is_dir() =>
+            {
+                (path, url)
+            }
+            _ => continue,
+        };
+
+        let reinstalled = url::Url::parse(url)
+            .with_context(|| format!("Invalid download url `{}`.", url))
+            .and_then(|url| {
+                install_from_url(&url, &PathBuf::from(path), is_pr_channel(channel), paths)
+            });
+        match reinstalled {
+            Ok((channel_data, _used_dmg)) => repairs.push(PreparedRepair::DirectDownload {
+                channel: channel.clone(),
+                channel_data,
+            }),
+            Err(e) => {
+                *failures += 1;
+                print_juliaup_style(
+                    "Failed",
+                    &format!("to repair channel '{}': {:#}", channel, e),
+                    JuliaupMessageType::Error,
+                );
+            }
+        }
+    }
+
+    repairs
+}
+
+/// Phase 2 (exclusive lock held): apply `repair` to the current
+/// configuration, which another process may have changed in the meantime.
+fn commit_repair(
+    config: &mut JuliaupConfig,
+    repair: PreparedRepair,
+    paths: &GlobalPaths,
+) -> Result<()> {
+    match repair {
+        PreparedRepair::Orphan { version, dir } => {
+            if config.installed_versions.contains_key(&version) {
+                return Ok(());
+            }
+
+            let rel_path = PathBuf::from(".").join(dir.file_name().unwrap());
+            config.installed_versions.insert(
+                version.clone(),
+                JuliaupConfigVersion {
+                    path: rel_path.to_string_lossy().into_owned(),
+                    binary_path: None,
+                },
+            );
+
+            print_juliaup_style(
+                "Repair",
+                &format!("Registered Julia {} found at `{}`.", version, dir.display()),
+                JuliaupMessageType::Success,
+            );
+        }
+        PreparedRepair::Version {
+            version,
+            downloaded,
+        } => {
+            // `commit_version_install` keeps an existing registration, so drop
+            // the stale one unless the version has been repaired meanwhile.
+            if find_missing_versions(config, &paths.juliauphome).contains(&version) {
+                config.installed_versions.remove(&version);
+            }
+            commit_version_install(downloaded, &version, config, paths)?;
+
+            print_juliaup_style(
+                "Repair",
+                &format!("Re-installed Julia {}.", version),
+                JuliaupMessageType::Success,
+            );
+        }
+        PreparedRepair::Unavailable { version } => {
+            if !find_missing_versions(config, &paths.juliauphome).contains(&version) {
+                return Ok(());
+            }
+
+            config.installed_versions.remove(&version);
+
+            let channels: Vec<String> = config
+                .installed_channels
+                .iter()
+                .filter(|(_, channel)| {
+                    matches!(channel, JuliaupConfigChannel::SystemChannel { version: v } if *v == version)
+                })
+                .map(|(name, _)| name.clone())
+                .collect();
+            for channel in channels {
+                remove_channel(
+                    config,
+                    &channel,
+                    &format!("Julia {} is no longer available for download", version),
+                )?;
+            }
+        }
+        PreparedRepair::DirectDownload {
+            channel,
+            channel_data,
+        } => {
+            if !config.installed_channels.contains_key(&channel) {
+                return Ok(());
+            }
+
+            config
+                .installed_channels
+                .insert(channel.clone(), channel_data);
+            print_juliaup_style(
+                "Repair",
+                &format!("Re-installed channel '{}'.", channel),
+                JuliaupMessageType::Success,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Recomputes the `BinaryPath` of every installation that is on disk.
+fn refresh_binary_paths(config: &mut JuliaupConfig, juliauphome: &Path) {
+    for detail in config.installed_versions.values_mut() {
+        let target = juliauphome.join(&detail.path);
+        if target.is_dir() {
+            detail.binary_path =
+                compute_relative_binary_path(&target, Path::new(&detail.path), juliauphome);
+        }
+    }
+
+    for channel in config.installed_channels.values_mut() {
+        if let JuliaupConfigChannel::DirectDownloadChannel {
+            path, binary_path, ..
+        } = channel
+        {
+            let target = juliauphome.join(&*path);
+            if target.is_dir() {
+                *binary_path = compute_relative_binary_path(&target, Path::new(path), juliauphome);
+            }
+        }
+    }
+}
+
+/// Recreates the `julia-<channel>` symlinks and shims that are missing or
+/// broken.
+fn restore_channel_commands(config: &JuliaupConfig, paths: &GlobalPaths) -> Result<()> {
+    let bin_dir = get_bin_dir()?;
+
+    for (name, channel) in &config.installed_channels {
+        let command = bin_dir.join(format!("julia-{}", name));
+        if std::fs::metadata(&command).is_ok() {
+            continue;
+        }
+
+        if config.settings.create_channel_shims {
+            create_channel_shim(name)?;
+        } else if cfg!(not(windows)) && config.settings.create_channel_symlinks {
+            create_symlink(channel, &format!("julia-{}", name), paths)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn run_command_repair(paths: &GlobalPaths) -> Result<()> {
+    // A version is only dropped for being unavailable according to an
+    // up-to-date versions db.
+    update_version_db(&None, paths).with_context(|| "Failed to update versions db.")?;
+
+    let version_db =
+        load_versions_db(paths).with_context(|| "`repair` command failed to load versions db.")?;
+
+    // Phase 1: find and download what is missing from a snapshot of the
+    // configuration, with no lock held so that concurrent julia/juliaup
+    // invocations are not blocked.
+    let config_snapshot = {
+        let file_lock = get_read_lock(paths)?;
+        let config_file = load_config_db(paths, Some(&file_lock))
+            .with_context(|| "`repair` command failed to load configuration data.")?;
+        let snapshot = config_file.data.clone();
+        let (_, res) = file_lock.data_unlock();
+        res.with_context(|| {
+            format!(
+                "Failed to unlock configuration lock file `{}`.",
+                paths.lockfile.display()
+            )
+        })?;
+        snapshot
+    };
+
+    let mut failures = 0;
+    let repairs = prepare_repairs(&config_snapshot, &version_db, paths, &mut failures);
+
+    // Phase 2: re-acquire the exclusive lock only to commit the repairs.
+    let mut config_file = load_mut_config_db(paths)
+        .with_context(|| "`repair` command failed to load configuration data.")?;
+    let config = &mut config_file.data;
+
+    for repair in repairs {
+        if let Err(e) = commit_repair(config, repair, paths) {
+            failures += 1;
+            print_juliaup_style(
+                "Failed",
+                &format!("to repair: {:#}", e),
+                JuliaupMessageType::Error,
+            );
+        }
+    }
+
+    let dangling_aliases: Vec<String> = config
+        .installed_channels
+        .iter()
+        .filter_map(|(name, channel)| match channel {
+            JuliaupConfigChannel::AliasChannel { target, .. }
+                if !config.installed_channels.contains_key(target)
+                    && !version_db.available_channels.contains_key(target) =>
+            {
+                Some(name.clone())
+            }
+            _ => None,
+        })
+        .collect();
+    for alias in dangling_aliases {
+        remove_channel(config, &alias, "its target no longer exists")?;
+    }
+
+    refresh_binary_paths(config, &paths.juliauphome);
+    restore_channel_commands(config, paths)?;
+
+    save_config_db(&mut config_file, paths).with_context(|| {
+        format!(
+            "`repair` command failed to save configuration db at `{}`.",
+            paths.juliaupconfig.display()
+        )
+    })?;
+
+    if failures > 0 {
+        anyhow::bail!(
+            "{} problem(s) could not be repaired, run `juliaup repair` again once they are resolved.",
+            failures
+        );
+    }
+
+    print_juliaup_style(
+        "Repair",
+        "The configuration matches the installed Julia versions.",
+        JuliaupMessageType::Success,
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orphans_and_missing_versions_are_found() {
+        let home = tempfile::tempdir().unwrap();
+        for dir in [
+            "julia-1.10.4+0.x64.linux.gnu",
+            "julia-1.11.0+0.x64.linux.gnu",
+            "julia-nightly",
+            "julia-temp-abc123",
+        ] {
+            std::fs::create_dir(home.path().join(dir)).unwrap();
+        }
+
+        let mut config = JuliaupConfig::default();
+        for version in ["1.10.4+0.x64.linux.gnu", "1.9.0+0.x64.linux.gnu"] {
+            config.installed_versions.insert(
+                version.to_string(),
+                JuliaupConfigVersion {
+                    path: format!("./julia-{}", version),
+                    binary_path: None,
+                },
+            );
+        }
+        config.installed_channels.insert(
+            "1.12".to_string(),
+            JuliaupConfigChannel::SystemChannel {
+                version: "1.12.0+0.x64.linux.gnu".to_string(),
+            },
+        );
+        config.installed_channels.insert(
+            "nightly".to_string(),
+            JuliaupConfigChannel::DirectDownloadChannel {
+                path: "./julia-nightly".to_string(),
+                url: "https://example.com/julia-latest.tar.gz".to_string(),
+                local_etag: String::new(),
+                server_etag: String::new(),
+                version: String::new(),
+                binary_path: None,
+            },
+        );
+
+        let orphans: Vec<String> = find_orphan_versions(&config, home.path())
+            .into_iter()
+            .map(|(version, _)| version)
+            .collect();
+        assert_eq!(orphans, vec!["1.11.0+0.x64.linux.gnu".to_string()]);
+
+        assert_eq!(
+            find_missing_versions(&config, home.path()),
+            BTreeSet::from([
+                "1.12.0+0.x64.linux.gnu".to_string(),
+                "1.9.0+0.x64.linux.gnu".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn orphan_without_binary_is_rejected() {
+        let home = tempfile::tempdir().unwrap();
+        let dir = home.path().join("julia-1.10.4+0.x64.linux.gnu");
+        std::fs::create_dir(&dir).unwrap();
+
+        assert!(!contains_julia_version(&dir, "1.10.4+0.x64.linux.gnu"));
+    }
+}
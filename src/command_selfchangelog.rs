@@ -0,0 +1,55 @@
+use crate::changelog::{download_changelog, entries_between, render_condensed};
+use crate::get_own_version;
+use crate::global_paths::GlobalPaths;
+use anyhow::{Context, Result};
+use semver::Version;
+
+/// The version juliaup was updated from by the last self-update, if known.
+#[cfg(feature = "selfupdate")]
+fn previous_version(paths: &GlobalPaths) -> Result<Option<Version>> {
+    use crate::config_file::load_config_db;
+
+    let config_file = load_config_db(paths, None)
+        .with_context(|| "`self changelog` command failed to load configuration db.")?;
+
+    Ok(config_file
+        .self_data
+        .previous_version
+        .as_deref()
+        .and_then(|v| Version::parse(v).ok()))
+}
+
+#[cfg(not(feature = "selfupdate"))]
+fn previous_version(_paths: &GlobalPaths) -> Result<Option<Version>> {
+    Ok(None)
+}
+
+pub fn run_command_selfchangelog(since: Option<String>, paths: &GlobalPaths) -> Result<()> {
+    let own_version = get_own_version()?;
+
+    let since = match since {
+        Some(since) => Some(
+            Version::parse(since.strip_prefix('v').unwrap_or(&since))
+                .with_context(|| format!("Invalid juliaup version '{}'.", since))?,
+        ),
+        None => previous_version(paths)?,
+    };
+
+    let entries = download_changelog()?;
+
+    let selected = match &since {
+        Some(since) => entries_between(&entries, since, &own_version),
+        None => entries
+            .iter()
+            .filter(|entry| entry.version == own_version)
+            .collect(),
+    };
+
+    if selected.is_empty() {
+        eprintln!("No release notes found for Juliaup {}.", own_version);
+    } else {
+        print!("{}", render_condensed(&selected));
+    }
+
+    Ok(())
+}
@@ -28,6 +28,9 @@ pub fn run_command_selfchannel(
                 .juliaup_channel
                 .expect("juliaup_channel should not be empty.");
             println!("Your juliaup is currently on channel `{}`. Run `juliaup self channel -h` for help on how to set the juliaup channel.", channel_name);
+            if let Some(pinned_version) = &config_file.self_data.pinned_version {
+                println!("Your juliaup is pinned to version {}, so self-updates ignore the channel. Run `juliaup self update --to latest` to remove the pin.", pinned_version);
+            }
         }
     }
 
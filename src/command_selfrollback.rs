@@ -0,0 +1,82 @@
+#[cfg(feature = "selfupdate")]
+use anyhow::Result;
+
+#[cfg(feature = "selfupdate")]
+pub fn run_command_selfrollback(paths: &crate::global_paths::GlobalPaths) -> Result<()> {
+    use crate::config_file::{load_mut_config_db, save_config_db};
+    use crate::get_own_version;
+    use crate::operations::{
+        juliaup_binary_version, previous_binaries_dir, run_post_update_hook, swap_in_binaries,
+    };
+    use anyhow::{anyhow, bail, Context};
+
+    let juliaup_exe = format!("juliaup{}", std::env::consts::EXE_SUFFIX);
+
+    let my_own_path = std::env::current_exe()
+        .with_context(|| "Could not determine the path of the running exe.")?;
+
+    let my_own_folder = my_own_path
+        .parent()
+        .ok_or_else(|| anyhow!("Could not determine parent."))?;
+
+    let previous_dir = previous_binaries_dir(my_own_folder)?;
+
+    let has_previous = std::fs::read_dir(&previous_dir)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if !has_previous {
+        bail!("There is no previous version of juliaup to roll back to.");
+    }
+
+    let previous_version = juliaup_binary_version(&previous_dir.join(&juliaup_exe))
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    let parent = my_own_folder
+        .parent()
+        .ok_or_else(|| anyhow!("Could not determine parent."))?;
+
+    let staging = tempfile::Builder::new()
+        .prefix(".juliaup-rollback-")
+        .tempdir_in(parent)
+        .with_context(|| {
+            format!(
+                "Failed to create a staging directory in `{}`.",
+                parent.display()
+            )
+        })?;
+    let replaced_binaries = staging.path().join("replaced");
+
+    swap_in_binaries(&previous_dir, my_own_folder, &replaced_binaries)?;
+
+    // Keep the version we just replaced, so that running rollback again
+    // undoes the rollback.
+    std::fs::remove_dir_all(&previous_dir)
+        .and_then(|_| std::fs::rename(&replaced_binaries, &previous_dir))
+        .with_context(|| {
+            format!(
+                "Failed to keep the replaced version of juliaup in `{}`.",
+                previous_dir.display()
+            )
+        })?;
+
+    if let Err(e) = run_post_update_hook(&my_own_folder.join(&juliaup_exe)) {
+        eprintln!("Warning: post-update hook failed: {e:#}");
+    }
+
+    eprintln!("Rolled back Juliaup to version {}.", previous_version);
+
+    // The version we rolled back from is now the one to go back to.
+    let mut config_file = load_mut_config_db(paths)
+        .with_context(|| "`self rollback` command failed to load configuration db.")?;
+
+    config_file.self_data.previous_version = Some(get_own_version()?.to_string());
+
+    save_config_db(&mut config_file, paths).with_context(|| {
+        format!(
+            "Failed to save configuration file at `{}`.",
+            paths.juliaupconfig.display()
+        )
+    })?;
+
+    Ok(())
+}
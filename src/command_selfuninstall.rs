@@ -10,6 +10,7 @@ pub fn run_command_selfuninstall(paths: &crate::global_paths::GlobalPaths) -> Re
         command_config_modifypath::run_command_config_modifypath,
         command_config_startupselfupdate::run_command_config_startupselfupdate,
         command_config_symlinks::run_command_config_symlinks,
+        operations::uninstall_background_selfupdate,
         utils::{print_juliaup_style, JuliaupMessageType},
     };
 
@@ -23,7 +24,11 @@ pub fn run_command_selfuninstall(paths: &crate::global_paths::GlobalPaths) -> Re
     }
 
     eprint!("Removing background self update task.");
-    match run_command_config_backgroundselfupdate(Some(0), true, paths) {
+    // The setting is only reset if it changed, so also clean up any task
+    // that is left over in a scheduler.
+    match run_command_config_backgroundselfupdate(Some(0), true, paths)
+        .and_then(|_| uninstall_background_selfupdate())
+    {
         Ok(_) => eprintln!(" Success."),
         Err(e) => eprintln!(" Failed: {e}."),
     };
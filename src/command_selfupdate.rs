@@ -2,16 +2,88 @@ use crate::global_paths::GlobalPaths;
 use crate::operations::update_version_db;
 use anyhow::{Context, Result};
 
+/// The version `juliaup self update --to` asks for.
 #[cfg(feature = "selfupdate")]
-pub fn run_command_selfupdate(paths: &GlobalPaths) -> Result<()> {
+fn parse_selfupdate_target(to: &str) -> Result<Option<semver::Version>> {
+    if to == "latest" {
+        return Ok(None);
+    }
+
+    let version = semver::Version::parse(to.strip_prefix('v').unwrap_or(to)).with_context(|| {
+        format!(
+            "Invalid juliaup version '{}'. Use a version like 1.2.3, or 'latest' to follow the juliaup channel.",
+            to
+        )
+    })?;
+
+    Ok(Some(version))
+}
+
+/// The url of the juliaup release archive of `version` for this platform.
+#[cfg(feature = "selfupdate")]
+fn juliaup_archive_url(version: &semver::Version) -> Result<url::Url> {
+    use crate::get_juliaup_target;
+    use crate::utils::get_juliaserver_base_url;
+
+    let juliaupserver_base =
+        get_juliaserver_base_url().with_context(|| "Failed to get Juliaup server base URL.")?;
+
+    let download_url_path = format!(
+        "juliaup/bin/juliaup-{}-{}.tar.gz",
+        version,
+        get_juliaup_target()
+    );
+
+    juliaupserver_base
+        .join(&download_url_path)
+        .with_context(|| {
+            format!(
+                "Failed to construct a valid url from '{}' and '{}'.",
+                juliaupserver_base, download_url_path
+            )
+        })
+}
+
+/// Signed archives and support for `PinnedVersion` shipped in the same
+/// release, so a version can only be pinned if its archive is signed. Older
+/// releases could not be installed, and would ignore the pin in their own
+/// self-updates.
+#[cfg(feature = "selfupdate")]
+fn ensure_pinnable(version: &semver::Version) -> Result<()> {
+    use crate::operations::ensure_release_is_signed;
+
+    let url = juliaup_archive_url(version)?;
+
+    ensure_release_is_signed(url.as_str()).with_context(|| {
+        format!(
+            "Juliaup {} cannot be pinned: no signature is published for it, so it predates signed updates and does not keep a pinned version during its own self-updates.",
+            version
+        )
+    })
+}
+
+/// Updates juliaup to the latest version on its channel, or to the pinned
+/// version if there is one. `to` pins juliaup to a version, or removes the
+/// pin when it is `latest`.
+#[cfg(feature = "selfupdate")]
+pub fn run_command_selfupdate(to: Option<String>, paths: &GlobalPaths) -> Result<()> {
+    use crate::changelog::print_release_notes;
     use crate::config_file::{get_read_lock, load_config_db, load_mut_config_db, save_config_db};
-    use crate::operations::{download_extract_sans_parent, download_juliaup_version};
+    use crate::download_rate::is_background_process;
+    use crate::get_own_version;
+    use crate::operations::download_juliaup_version;
     use crate::utils::get_juliaserver_base_url;
-    use crate::{get_juliaup_target, get_own_version};
     use anyhow::{anyhow, bail};
+    use is_terminal::IsTerminal;
+
+    let requested_pin = to.as_deref().map(parse_selfupdate_target).transpose()?;
 
     update_version_db(&None, paths).with_context(|| "Failed to update versions db.")?;
 
+    if let Some(Some(pin)) = &requested_pin {
+        ensure_pinnable(pin)?;
+    }
+
     // Read the configured juliaup channel under a short-lived shared lock, then
     // release it before any network operations. Holding the exclusive lock across
     // the downloads below would block concurrent julia/juliaup invocations (which
@@ -27,6 +99,8 @@ pub fn run_command_selfupdate(paths: &GlobalPaths) -> Result<()> {
         None => "release".to_string(),
     };
 
+    let configured_pin = config_file.self_data.pinned_version.clone();
+
     {
         let (_, res) = file_lock.data_unlock();
         res.with_context(|| {
@@ -37,29 +111,47 @@ pub fn run_command_selfupdate(paths: &GlobalPaths) -> Result<()> {
         })?;
     }
 
-    let juliaupserver_base =
-        get_juliaserver_base_url().with_context(|| "Failed to get Juliaup server base URL.")?;
-
-    let version_url_path = match juliaup_channel.as_str() {
-        "release" => "juliaup/RELEASECHANNELVERSION",
-        "releasepreview" => "juliaup/RELEASEPREVIEWCHANNELVERSION",
-        "dev" => "juliaup/DEVCHANNELVERSION",
-        _ => bail!(
-            "Juliaup is configured to a channel named '{}' that does not exist.",
-            juliaup_channel
-        ),
+    let pinned_version = match &requested_pin {
+        Some(pin) => pin.clone(),
+        None => configured_pin
+            .as_deref()
+            .map(semver::Version::parse)
+            .transpose()
+            .with_context(|| "Failed to parse the pinned juliaup version.")?,
     };
 
-    eprintln!("Checking for self-updates");
+    let version = match &pinned_version {
+        Some(pinned_version) => {
+            eprintln!("Juliaup is pinned to version {}", pinned_version);
 
-    let version_url = juliaupserver_base.join(version_url_path).with_context(|| {
-        format!(
-            "Failed to construct a valid url from '{}' and '{}'.",
-            juliaupserver_base, version_url_path
-        )
-    })?;
+            pinned_version.clone()
+        }
+        None => {
+            let juliaupserver_base = get_juliaserver_base_url()
+                .with_context(|| "Failed to get Juliaup server base URL.")?;
+
+            let version_url_path = match juliaup_channel.as_str() {
+                "release" => "juliaup/RELEASECHANNELVERSION",
+                "releasepreview" => "juliaup/RELEASEPREVIEWCHANNELVERSION",
+                "dev" => "juliaup/DEVCHANNELVERSION",
+                _ => bail!(
+                    "Juliaup is configured to a channel named '{}' that does not exist.",
+                    juliaup_channel
+                ),
+            };
+
+            eprintln!("Checking for self-updates");
+
+            let version_url = juliaupserver_base.join(version_url_path).with_context(|| {
+                format!(
+                    "Failed to construct a valid url from '{}' and '{}'.",
+                    juliaupserver_base, version_url_path
+                )
+            })?;
 
-    let version = download_juliaup_version(version_url.as_ref())?;
+            download_juliaup_version(version_url.as_ref())?
+        }
+    };
 
     // Re-acquire the exclusive lock only briefly to record the self-update
     // timestamp, so the lock is never held across the network operations above.
@@ -77,28 +169,19 @@ pub fn run_command_selfupdate(paths: &GlobalPaths) -> Result<()> {
         })?;
     }
 
-    if version == get_own_version().unwrap() {
-        eprintln!(
-            "Juliaup unchanged on channel '{}' - {}",
-            juliaup_channel, version
-        );
-    } else {
-        let juliaup_target = get_juliaup_target();
+    let own_version = get_own_version().unwrap();
 
-        let juliaupserver_base =
-            get_juliaserver_base_url().with_context(|| "Failed to get Juliaup server base URL.")?;
-
-        let download_url_path =
-            format!("juliaup/bin/juliaup-{}-{}.tar.gz", version, juliaup_target);
-
-        let new_juliaup_url = juliaupserver_base
-            .join(&download_url_path)
-            .with_context(|| {
-                format!(
-                    "Failed to construct a valid url from '{}' and '{}'.",
-                    juliaupserver_base, download_url_path
-                )
-            })?;
+    if version == own_version {
+        if pinned_version.is_some() {
+            eprintln!("Juliaup unchanged - {} (pinned)", version);
+        } else {
+            eprintln!(
+                "Juliaup unchanged on channel '{}' - {}",
+                juliaup_channel, version
+            );
+        }
+    } else {
+        let new_juliaup_url = juliaup_archive_url(&version)?;
 
         let my_own_path = std::env::current_exe()
             .with_context(|| "Could not determine the path of the running exe.")?;
@@ -107,23 +190,165 @@ pub fn run_command_selfupdate(paths: &GlobalPaths) -> Result<()> {
             .parent()
             .ok_or_else(|| anyhow!("Could not determine parent."))?;
 
-        eprintln!(
-            "Found new version {} on channel {}.",
-            version, juliaup_channel
+        if pinned_version.is_some() {
+            eprintln!("Installing pinned version {}.", version);
+        } else {
+            eprintln!(
+                "Found new version {} on channel {}.",
+                version, juliaup_channel
+            );
+        }
+
+        // Versions older than this one may not know the post-update hook, so
+        // only treat its failure as fatal when moving forward.
+        let strict_post_update = version > own_version;
+
+        install_update(
+            new_juliaup_url.as_ref(),
+            my_own_folder,
+            &version,
+            strict_post_update,
+        )
+        .with_context(|| format!("Failed to install juliaup {}.", version))?;
+
+        eprintln!("Updated Juliaup to version {}.", version);
+
+        let mut config_file = load_mut_config_db(paths)
+            .with_context(|| "`self update` command failed to load configuration db.")?;
+
+        config_file.self_data.previous_version = Some(own_version.to_string());
+
+        save_config_db(&mut config_file, paths).with_context(|| {
+            format!(
+                "Failed to save configuration file at `{}`.",
+                paths.juliaupconfig.display()
+            )
+        })?;
+
+        // Nobody reads the notes of a background update or of a redirected
+        // run, so don't download them there.
+        if !is_background_process() && std::io::stderr().is_terminal() {
+            print_release_notes(&own_version, &version);
+        }
+    }
+
+    // Only persist a new pin once the pinned version is actually installed,
+    // so a typo doesn't leave background updates stuck on a missing version.
+    if let Some(pin) = requested_pin {
+        let mut config_file = load_mut_config_db(paths)
+            .with_context(|| "`self update` command failed to load configuration db.")?;
+
+        config_file.self_data.pinned_version = pin.as_ref().map(|v| v.to_string());
+
+        save_config_db(&mut config_file, paths).with_context(|| {
+            format!(
+                "Failed to save configuration file at `{}`.",
+                paths.juliaupconfig.display()
+            )
+        })?;
+
+        match pin {
+            Some(pin) => eprintln!(
+                "Pinned Juliaup to version {}. Background and startup self-updates will keep this version until you run `juliaup self update --to latest`.",
+                pin
+            ),
+            None => {
+                if configured_pin.is_some() {
+                    eprintln!(
+                        "Juliaup is no longer pinned and follows channel '{}'.",
+                        juliaup_channel
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Stages the new binaries in a directory next to `install_dir`, checks that
+/// they run, and then swaps them in. The replaced binaries are kept for
+/// `juliaup self rollback`, and are restored right away if the new version's
+/// post-update hook fails and `strict_post_update` is set.
+#[cfg(feature = "selfupdate")]
+fn install_update(
+    url: &str,
+    install_dir: &std::path::Path,
+    version: &semver::Version,
+    strict_post_update: bool,
+) -> Result<()> {
+    use crate::operations::{
+        download_verify_extract_sans_parent, juliaup_binary_version, previous_binaries_dir,
+        run_post_update_hook, swap_in_binaries,
+    };
+    use anyhow::{anyhow, bail};
+
+    let juliaup_exe = format!("juliaup{}", std::env::consts::EXE_SUFFIX);
+
+    let parent = install_dir
+        .parent()
+        .ok_or_else(|| anyhow!("Could not determine parent."))?;
+
+    let staging = tempfile::Builder::new()
+        .prefix(".juliaup-update-")
+        .tempdir_in(parent)
+        .with_context(|| {
+            format!(
+                "Failed to create a staging directory in `{}`.",
+                parent.display()
+            )
+        })?;
+
+    let new_binaries = staging.path().join("new");
+    let replaced_binaries = staging.path().join("replaced");
+
+    download_verify_extract_sans_parent(url, &new_binaries, 0)?;
+
+    let staged_version = juliaup_binary_version(&new_binaries.join(&juliaup_exe))
+        .with_context(|| "The downloaded version of juliaup failed to run.")?;
+    if staged_version != version.to_string() {
+        bail!(
+            "The downloaded juliaup reports version {} instead of {}.",
+            staged_version,
+            version
         );
+    }
 
-        download_extract_sans_parent(new_juliaup_url.as_ref(), my_own_folder, 0)?;
+    swap_in_binaries(&new_binaries, install_dir, &replaced_binaries)?;
 
-        let new_juliaup = my_own_folder.join(format!("juliaup{}", std::env::consts::EXE_SUFFIX));
-        if let Err(e) = std::process::Command::new(&new_juliaup)
-            .arg("_post-update")
-            .status()
-        {
-            eprintln!("Warning: post-update hook failed: {e}");
+    match run_post_update_hook(&install_dir.join(&juliaup_exe)) {
+        Ok(()) => {}
+        Err(e) if !strict_post_update => {
+            eprintln!("Warning: post-update hook failed: {e:#}");
         }
+        Err(e) => {
+            swap_in_binaries(
+                &replaced_binaries,
+                install_dir,
+                &staging.path().join("failed"),
+            )
+            .with_context(|| "Failed to restore the previous version of juliaup.")?;
 
-        eprintln!("Updated Juliaup to version {}.", version);
+            return Err(e).with_context(|| {
+                format!(
+                "Juliaup {} failed its post-update checks; the previous version has been restored.",
+                version
+            )
+            });
+        }
+    }
+
+    let previous_dir = previous_binaries_dir(install_dir)?;
+    if previous_dir.exists() {
+        std::fs::remove_dir_all(&previous_dir)
+            .with_context(|| format!("Failed to remove `{}`.", previous_dir.display()))?;
     }
+    std::fs::rename(&replaced_binaries, &previous_dir).with_context(|| {
+        format!(
+            "Failed to keep the previous version of juliaup in `{}`.",
+            previous_dir.display()
+        )
+    })?;
 
     Ok(())
 }
@@ -187,3 +412,26 @@ pub fn run_command_selfupdate(paths: &GlobalPaths) -> Result<()> {
     update_version_db(&None, paths).with_context(|| "Failed to update versions db.")?;
     Ok(())
 }
+
+#[cfg(all(test, feature = "selfupdate"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selfupdate_target_parses_versions_and_latest() {
+        assert_eq!(
+            parse_selfupdate_target("1.23.0").unwrap(),
+            Some(semver::Version::new(1, 23, 0))
+        );
+        assert_eq!(
+            parse_selfupdate_target("v1.24.1").unwrap(),
+            Some(semver::Version::new(1, 24, 1))
+        );
+        assert_eq!(parse_selfupdate_target("latest").unwrap(), None);
+        assert!(parse_selfupdate_target("1.23").is_err());
+        assert_eq!(
+            parse_selfupdate_target("1.21.0").unwrap(),
+            Some(semver::Version::new(1, 21, 0))
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use crate::cli::{CompletionShell, InitShell, Juliaup};
+use crate::command_completions::generate_completion_for_command;
+use crate::command_override::find_override_channel;
+use crate::config_file::load_config_db_lockfree;
+use crate::global_paths::GlobalPaths;
+use anyhow::{anyhow, Context, Result};
+use indoc::formatdoc;
+use std::io::Write;
+
+/// Quotes `value` as a single-quoted POSIX shell word.
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Quotes `value` as a single-quoted fish word.
+fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn path_setup(shell: InitShell, bin_dir: &str) -> String {
+    match shell {
+        InitShell::Bash | InitShell::Zsh => formatdoc!(
+            r#"
+                case ":$PATH:" in
+                    *:{bin}:*)
+                        ;;
+                    *)
+                        export PATH={bin}"${{PATH:+:$PATH}}"
+                        ;;
+                esac
+            "#,
+            bin = sh_quote(bin_dir)
+        ),
+        InitShell::Fish => formatdoc!(
+            "
+                if not contains -- {bin} $PATH
+                    set -gx PATH {bin} $PATH
+                end
+            ",
+            bin = fish_quote(bin_dir)
+        ),
+    }
+}
+
+/// A hook that exports `JULIAUP_CHANNEL` for the directory override that
+/// applies to the working directory. A `JULIAUP_CHANNEL` the user set
+/// themselves is left alone.
+fn override_hook(shell: InitShell, juliaup_exe: &str) -> String {
+    let function = |exe: &str| {
+        formatdoc!(
+            r#"
+                _juliaup_override_hook() {{
+                    local channel
+                    channel="$({exe} _override-channel 2>/dev/null)"
+                    if [ -n "$channel" ]; then
+                        if [ -z "${{JULIAUP_CHANNEL-}}" ] || [ "$JULIAUP_CHANNEL" = "${{_JULIAUP_HOOK_CHANNEL-}}" ]; then
+                            export JULIAUP_CHANNEL="$channel"
+                            _JULIAUP_HOOK_CHANNEL="$channel"
+                        fi
+                    elif [ -n "${{_JULIAUP_HOOK_CHANNEL-}}" ]; then
+                        if [ "${{JULIAUP_CHANNEL-}}" = "$_JULIAUP_HOOK_CHANNEL" ]; then
+                            unset JULIAUP_CHANNEL
+                        fi
+                        unset _JULIAUP_HOOK_CHANNEL
+                    fi
+                }}
+            "#
+        )
+    };
+
+    match shell {
+        InitShell::Bash => {
+            let mut content = function(&sh_quote(juliaup_exe));
+            content.push_str(&formatdoc!(
+                r#"
+                    _juliaup_override_prompt_hook() {{
+                        if [ "$PWD" != "${{_JULIAUP_HOOK_PWD-}}" ]; then
+                            _JULIAUP_HOOK_PWD="$PWD"
+                            _juliaup_override_hook
+                        fi
+                    }}
+                    case ";${{PROMPT_COMMAND-}};" in
+                        *";_juliaup_override_prompt_hook;"*)
+                            ;;
+                        *)
+                            PROMPT_COMMAND="_juliaup_override_prompt_hook${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}"
+                            ;;
+                    esac
+                    _juliaup_override_prompt_hook
+                "#
+            ));
+            content
+        }
+        InitShell::Zsh => {
+            let mut content = function(&sh_quote(juliaup_exe));
+            content.push_str(&formatdoc!(
+                "
+                    autoload -Uz add-zsh-hook
+                    add-zsh-hook chpwd _juliaup_override_hook
+                    _juliaup_override_hook
+                "
+            ));
+            content
+        }
+        InitShell::Fish => formatdoc!(
+            r#"
+                function __juliaup_override_hook --on-variable PWD
+                    set -l channel ({exe} _override-channel 2>/dev/null)
+                    if test -n "$channel"
+                        if not set -q JULIAUP_CHANNEL; or test "$JULIAUP_CHANNEL" = "$__juliaup_hook_channel"
+                            set -gx JULIAUP_CHANNEL $channel
+                            set -g __juliaup_hook_channel $channel
+                        end
+                    else if set -q __juliaup_hook_channel
+                        if test "$JULIAUP_CHANNEL" = "$__juliaup_hook_channel"
+                            set -e JULIAUP_CHANNEL
+                        end
+                        set -e __juliaup_hook_channel
+                    end
+                end
+                __juliaup_override_hook
+            "#,
+            exe = fish_quote(juliaup_exe)
+        ),
+    }
+}
+
+/// The shell code printed by `juliaup shell-init`, without the completions.
+fn shell_init_script(shell: InitShell, bin_dir: &str, juliaup_exe: &str, hook: bool) -> String {
+    let mut script = path_setup(shell, bin_dir);
+
+    if hook {
+        script.push_str(&override_hook(shell, juliaup_exe));
+    }
+
+    script
+}
+
+pub fn run_command_shell_init(shell: InitShell, hook: bool) -> Result<()> {
+    let juliaup_exe = std::env::current_exe()
+        .with_context(|| "Could not determine the path of the running exe.")?;
+
+    let bin_dir = juliaup_exe
+        .parent()
+        .ok_or_else(|| anyhow!("Could not determine parent."))?;
+
+    let bin_dir = bin_dir.to_str().ok_or_else(|| {
+        anyhow!("Only valid UTF-8 paths are supported for the Juliaup bin directory.")
+    })?;
+    let juliaup_exe = juliaup_exe.to_str().ok_or_else(|| {
+        anyhow!("Only valid UTF-8 paths are supported for the Juliaup executable.")
+    })?;
+
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(shell_init_script(shell, bin_dir, juliaup_exe, hook).as_bytes())?;
+    stdout.flush()?;
+    drop(stdout);
+
+    let completion_shell = match shell {
+        InitShell::Bash => CompletionShell::Bash,
+        InitShell::Fish => CompletionShell::Fish,
+        InitShell::Zsh => CompletionShell::Zsh,
+    };
+
+    generate_completion_for_command::<Juliaup>(completion_shell, "juliaup")
+}
+
+pub fn run_command_override_channel(paths: &GlobalPaths) -> Result<()> {
+    // Silently print nothing on error — this runs on every directory change
+    // of a shell with the `shell-init` hook.
+    let Ok(config_file) = load_config_db_lockfree(paths) else {
+        return Ok(());
+    };
+
+    let Ok(curr_dir) = std::env::current_dir().and_then(|dir| dir.canonicalize()) else {
+        return Ok(());
+    };
+
+    if let Some(channel) = find_override_channel(&config_file.data.overrides, &curr_dir) {
+        println!("{}", channel);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_paths_for_each_shell() {
+        assert_eq!(sh_quote("/home/o'neil/bin"), r"'/home/o'\''neil/bin'");
+        assert_eq!(fish_quote(r"C:\o'neil"), r"'C:\\o\'neil'");
+    }
+
+    #[test]
+    fn hook_is_only_added_when_requested() {
+        for shell in [InitShell::Bash, InitShell::Fish, InitShell::Zsh] {
+            let without =
+                shell_init_script(shell, "/opt/juliaup/bin", "/opt/juliaup/bin/juliaup", false);
+            assert!(without.contains("'/opt/juliaup/bin'"));
+            assert!(!without.contains("_override-channel"));
+
+            let with =
+                shell_init_script(shell, "/opt/juliaup/bin", "/opt/juliaup/bin/juliaup", true);
+            assert!(with.contains("'/opt/juliaup/bin/juliaup' _override-channel"));
+        }
+
+        let zsh = shell_init_script(InitShell::Zsh, "/bin", "/bin/juliaup", true);
+        assert!(zsh.contains("add-zsh-hook chpwd _juliaup_override_hook"));
+
+        let fish = shell_init_script(InitShell::Fish, "/bin", "/bin/juliaup", true);
+        assert!(fish.contains("--on-variable PWD"));
+    }
+}
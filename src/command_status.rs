@@ -1,5 +1,6 @@
 use crate::config_file::load_config_db;
 use crate::config_file::{JuliaupConfigChannel, JuliaupReadonlyConfigFile};
+use crate::disk_usage::{compute_disk_usage, format_size, DiskUsage};
 use crate::global_paths::GlobalPaths;
 use crate::jsonstructs_versionsdb::JuliaupVersionDB;
 use crate::versions_file::load_versions_db;
@@ -144,7 +145,7 @@ struct ChannelRow {
     update: String,
 }
 
-pub fn run_command_status(paths: &GlobalPaths) -> Result<()> {
+pub fn run_command_status(size: bool, paths: &GlobalPaths) -> Result<()> {
     let config_file = load_config_db(paths, None)
         .with_context(|| "`status` command failed to load configuration file.")?;
 
@@ -180,11 +181,63 @@ pub fn run_command_status(paths: &GlobalPaths) -> Result<()> {
 
     print_stdout(styled_table(build_rows(compact)))?;
 
+    if size {
+        let usage = compute_disk_usage(&config_file.data, &paths.juliauphome);
+
+        println!();
+        print_stdout(with_table_style(size_rows(&usage).with_title()))?;
+        println!("Total: {}", format_size(usage.total()));
+    }
+
     Ok(())
 }
 
+#[derive(Table)]
+struct SizeRow {
+    #[table(title = "Size", justify = "Justify::Right")]
+    size: String,
+    #[table(title = "Installation")]
+    installation: String,
+    #[table(title = "Used by")]
+    used_by: String,
+}
+
+fn size_rows(usage: &DiskUsage) -> Vec<SizeRow> {
+    let versions = usage.versions.iter().map(|version| SizeRow {
+        size: format_size(version.size),
+        installation: format!("Julia {}", version.version),
+        used_by: match version.channels.len() {
+            0 => "no channel, see `juliaup gc`".to_string(),
+            1 => version.channels[0].clone(),
+            _ => format!("{} (shared)", version.channels.join(", ")),
+        },
+    });
+
+    let channels = usage.channels.iter().map(|channel| SizeRow {
+        size: format_size(channel.size),
+        installation: channel.path.trim_start_matches("./").to_string(),
+        used_by: channel.name.clone(),
+    });
+
+    let temp_dirs = usage.temp_dirs.iter().map(|temp_dir| SizeRow {
+        size: format_size(temp_dir.size),
+        installation: temp_dir
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        used_by: "interrupted download".to_string(),
+    });
+
+    versions.chain(channels).chain(temp_dirs).collect()
+}
+
 fn styled_table(rows: Vec<ChannelRow>) -> TableStruct {
-    rows.with_title()
+    with_table_style(rows.with_title())
+}
+
+fn with_table_style(table: TableStruct) -> TableStruct {
+    table
         .color_choice(ColorChoice::Never)
         .border(Border::builder().build())
         .separator(
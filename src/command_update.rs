@@ -8,7 +8,7 @@ use crate::jsonstructs_versionsdb::JuliaupVersionDB;
 use crate::operations::create_symlink;
 use crate::operations::{
     commit_version_install, download_version_to_temp, garbage_collect_versions, install_from_url,
-    is_pr_channel, update_version_db,
+    is_pr_channel, prune_finished_pr_channels, update_version_db,
 };
 use crate::utils::{print_juliaup_style, JuliaupMessageType};
 use crate::versions_file::load_versions_db;
@@ -230,9 +230,35 @@ fn commit_channel_update(
     Ok(())
 }
 
+/// Removes finished PR channels before a full update when
+/// `autoprunefinishedprs` is enabled, so that they are not updated first.
+fn auto_prune_finished_prs(paths: &GlobalPaths) -> Result<()> {
+    let mut config_file = load_mut_config_db(paths)
+        .with_context(|| "`update` command failed to load configuration data.")?;
+
+    if !config_file.data.settings.auto_prune_finished_prs
+        || config_file.data.finished_pr_channels.is_empty()
+    {
+        return Ok(());
+    }
+
+    prune_finished_pr_channels(&mut config_file.data, paths)?;
+
+    save_config_db(&mut config_file, paths).with_context(|| {
+        format!(
+            "`update` command failed to save configuration db at `{}`.",
+            paths.juliaupconfig.display()
+        )
+    })
+}
+
 pub fn run_command_update(channel: &Option<String>, paths: &GlobalPaths) -> Result<()> {
     update_version_db(channel, paths).with_context(|| "Failed to update versions db.")?;
 
+    if channel.is_none() {
+        auto_prune_finished_prs(paths)?;
+    }
+
     let version_db =
         load_versions_db(paths).with_context(|| "`update` command failed to load versions db.")?;
 
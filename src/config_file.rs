@@ -10,6 +10,7 @@ use std::mem;
 use tempfile::NamedTempFile;
 
 use crate::global_paths::GlobalPaths;
+use crate::operations::PrState;
 
 fn is_default<T: Default + PartialEq>(t: &T) -> bool {
     t == &T::default()
@@ -23,6 +24,38 @@ fn is_default_versionsdb_update_interval(i: &i64) -> bool {
     *i == default_versionsdb_update_interval()
 }
 
+pub fn default_connect_timeout() -> i64 {
+    30
+}
+
+fn is_default_connect_timeout(i: &i64) -> bool {
+    *i == default_connect_timeout()
+}
+
+pub fn default_read_timeout() -> i64 {
+    60
+}
+
+fn is_default_read_timeout(i: &i64) -> bool {
+    *i == default_read_timeout()
+}
+
+pub fn default_download_retries() -> i64 {
+    3
+}
+
+fn is_default_download_retries(i: &i64) -> bool {
+    *i == default_download_retries()
+}
+
+pub fn default_max_background_download_rate() -> u64 {
+    1024 * 1024
+}
+
+fn is_default_max_background_download_rate(i: &u64) -> bool {
+    *i == default_max_background_download_rate()
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct JuliaupConfigVersion {
     #[serde(rename = "Path")]
@@ -78,6 +111,13 @@ pub struct JuliaupConfigSettings {
         skip_serializing_if = "is_default"
     )]
     pub create_channel_symlinks: bool,
+    /// Create a `julia-<channel>` launcher shim for every installed channel.
+    #[serde(
+        rename = "CreateChannelShims",
+        default,
+        skip_serializing_if = "is_default"
+    )]
+    pub create_channel_shims: bool,
     #[serde(
         rename = "VersionsDbUpdateInterval",
         default = "default_versionsdb_update_interval",
@@ -96,15 +136,113 @@ pub struct JuliaupConfigSettings {
         skip_serializing_if = "is_default"
     )]
     pub manifest_version_detect: bool,
+    /// Remove PR channels whose pull request was merged or closed during
+    /// `juliaup update`.
+    #[serde(
+        rename = "AutoPruneFinishedPrs",
+        default,
+        skip_serializing_if = "is_default"
+    )]
+    pub auto_prune_finished_prs: bool,
+    /// `glibc` or `musl`, the flavor of Julia builds that plain channels
+    /// install on Linux. Detected from the running system if unset.
+    #[serde(rename = "Libc", default, skip_serializing_if = "Option::is_none")]
+    pub libc: Option<String>,
+    /// Mirror for Julia release downloads and the versions database. The
+    /// `JULIAUP_SERVER` environment variable takes precedence.
+    #[serde(rename = "Server", default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// Mirror for nightly builds. `JULIAUP_NIGHTLY_SERVER` takes precedence.
+    #[serde(
+        rename = "NightlyServer",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub nightly_server: Option<String>,
+    /// Mirror for pull request builds. `JULIAUP_PR_SERVER` takes precedence.
+    #[serde(rename = "PrServer", default, skip_serializing_if = "Option::is_none")]
+    pub pr_server: Option<String>,
+    /// PEM bundle of additional CA certificates to trust.
+    #[serde(rename = "CaBundle", default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<String>,
+    /// PEM client certificate for mirrors that require mutual TLS.
+    #[serde(
+        rename = "ClientCertificate",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub client_certificate: Option<String>,
+    /// PEM (PKCS#8) private key belonging to `client_certificate`.
+    #[serde(rename = "ClientKey", default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
+    /// Seconds to wait for a connection to be established, 0 to wait forever.
+    #[serde(
+        rename = "ConnectTimeout",
+        default = "default_connect_timeout",
+        skip_serializing_if = "is_default_connect_timeout"
+    )]
+    pub connect_timeout: i64,
+    /// Seconds to wait for a response or for more data, 0 to wait forever.
+    #[serde(
+        rename = "ReadTimeout",
+        default = "default_read_timeout",
+        skip_serializing_if = "is_default_read_timeout"
+    )]
+    pub read_timeout: i64,
+    /// How often a failed request is retried before giving up.
+    #[serde(
+        rename = "DownloadRetries",
+        default = "default_download_retries",
+        skip_serializing_if = "is_default_download_retries"
+    )]
+    pub download_retries: i64,
+    /// Download rate limit in bytes per second, 0 for unlimited.
+    #[serde(
+        rename = "MaxDownloadRate",
+        default,
+        skip_serializing_if = "is_default"
+    )]
+    pub max_download_rate: u64,
+    /// Download rate limit in bytes per second for background updates, 0 for
+    /// unlimited.
+    #[serde(
+        rename = "MaxBackgroundDownloadRate",
+        default = "default_max_background_download_rate",
+        skip_serializing_if = "is_default_max_background_download_rate"
+    )]
+    pub max_background_download_rate: u64,
+    /// Julia tools, e.g. `juliac`, that get a launcher entry in the bin
+    /// directory.
+    #[serde(
+        rename = "LauncherTools",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub launcher_tools: Vec<String>,
 }
 
 impl Default for JuliaupConfigSettings {
     fn default() -> Self {
         JuliaupConfigSettings {
             create_channel_symlinks: false,
+            create_channel_shims: false,
             versionsdb_update_interval: default_versionsdb_update_interval(),
             auto_install_channels: None,
             manifest_version_detect: false,
+            auto_prune_finished_prs: false,
+            libc: None,
+            server: None,
+            nightly_server: None,
+            pr_server: None,
+            ca_bundle: None,
+            client_certificate: None,
+            client_key: None,
+            connect_timeout: default_connect_timeout(),
+            read_timeout: default_read_timeout(),
+            download_retries: default_download_retries(),
+            max_download_rate: 0,
+            max_background_download_rate: default_max_background_download_rate(),
+            launcher_tools: Vec::new(),
         }
     }
 }
@@ -134,6 +272,14 @@ pub struct JuliaupConfig {
         skip_serializing_if = "Option::is_none"
     )]
     pub last_version_db_update: Option<DateTime<Utc>>,
+    /// PR channels whose pull request was merged or closed, as last seen when
+    /// checking for updates.
+    #[serde(
+        rename = "FinishedPrChannels",
+        default,
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub finished_pr_channels: HashMap<String, PrState>,
 }
 
 #[cfg(feature = "selfupdate")]
@@ -155,6 +301,20 @@ pub struct JuliaupSelfConfig {
     pub juliaup_channel: Option<String>,
     #[serde(rename = "LastSelfUpdate", skip_serializing_if = "Option::is_none")]
     pub last_selfupdate: Option<DateTime<Utc>>,
+    /// Juliaup version that self-updates install instead of the latest one.
+    #[serde(
+        rename = "PinnedVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pinned_version: Option<String>,
+    /// Juliaup version that was replaced by the last self-update.
+    #[serde(
+        rename = "PreviousVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub previous_version: Option<String>,
 }
 
 pub struct JuliaupConfigFile {
@@ -253,6 +413,27 @@ pub fn get_read_lock(paths: &GlobalPaths) -> Result<FlockLock<File>> {
     Ok(file_lock)
 }
 
+/// Whether another process currently holds the configuration lock.
+pub fn is_config_locked(paths: &GlobalPaths) -> Result<bool> {
+    let lock_file = match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&paths.lockfile)
+    {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(e) => {
+            return Err(anyhow!(
+                "Could not open lockfile `{}`: {}.",
+                paths.lockfile.display(),
+                e
+            ));
+        }
+    };
+
+    Ok(ExclusiveFlock::try_lock(lock_file).is_err())
+}
+
 /// Reads the configuration from disk without any locking.
 fn read_config_db(paths: &GlobalPaths) -> Result<JuliaupReadonlyConfigFile> {
     let v = match std::fs::OpenOptions::new()
@@ -0,0 +1,399 @@
+//! Per-host credentials for authenticated mirrors.
+//!
+//! Credentials are read from `credentials.toml` in the juliaup home folder and
+//! from the user's `.netrc` (or the file named by `NETRC`), and are attached
+//! to every HTTP request juliaup makes to a matching host. They are never
+//! written to `juliaup.json` and never logged; only host names are.
+//!
+//! ```toml
+//! [hosts."artifacts.example.com"]
+//! token = "..."       # sent as `Authorization: Bearer ...`
+//!
+//! [hosts."mirror.example.com:8443"]
+//! username = "me"     # sent as HTTP basic auth
+//! password = "..."
+//!
+//! [hosts."mirror.example.com:8443".headers]
+//! X-Api-Key = "..."
+//! ```
+//!
+//! Entries in `credentials.toml` take precedence over `.netrc`. Only
+//! `machine` entries of `.netrc` are used; a `default` entry is ignored so that
+//! credentials are never sent to the public Julia servers by accident.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use url::Url;
+
+use crate::global_paths::get_paths;
+
+#[derive(Deserialize, Clone, Default)]
+pub struct HostCredentials {
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Default)]
+struct CredentialsFile {
+    #[serde(default)]
+    hosts: HashMap<String, HostCredentials>,
+}
+
+#[derive(Default)]
+struct Credentials {
+    hosts: HashMap<String, HostCredentials>,
+    netrc: HashMap<String, HostCredentials>,
+}
+
+static CREDENTIALS: OnceLock<std::result::Result<Credentials, String>> = OnceLock::new();
+
+impl HostCredentials {
+    /// The headers to send with a request, including `Authorization` for a
+    /// token or username/password.
+    pub fn headers(&self) -> Vec<(String, String)> {
+        let mut headers: Vec<(String, String)> = self
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        if let Some(token) = &self.token {
+            headers.push(("Authorization".to_string(), format!("Bearer {}", token)));
+        } else if let Some(username) = &self.username {
+            let user_pass = format!("{}:{}", username, self.password.as_deref().unwrap_or(""));
+            headers.push((
+                "Authorization".to_string(),
+                format!("Basic {}", base64_encode(user_pass.as_bytes())),
+            ));
+        }
+
+        headers
+    }
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+
+        output.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        output.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        output.push(if chunk.len() > 1 {
+            ALPHABET[(n >> 6) as usize & 63] as char
+        } else {
+            '='
+        });
+        output.push(if chunk.len() > 2 {
+            ALPHABET[n as usize & 63] as char
+        } else {
+            '='
+        });
+    }
+
+    output
+}
+
+/// Parses the `machine` entries of a `.netrc` file into basic auth
+/// credentials keyed by host.
+fn parse_netrc(content: &str) -> HashMap<String, HostCredentials> {
+    let mut result = HashMap::new();
+    let mut tokens = content.split_whitespace();
+    let mut current: Option<(String, HostCredentials)> = None;
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" | "default" => {
+                if let Some((host, creds)) = current.take() {
+                    result.insert(host, creds);
+                }
+                if token == "machine" {
+                    if let Some(host) = tokens.next() {
+                        current = Some((host.to_string(), HostCredentials::default()));
+                    }
+                }
+            }
+            "login" => {
+                let value = tokens.next().map(str::to_string);
+                if let Some((_, creds)) = current.as_mut() {
+                    creds.username = value;
+                }
+            }
+            "password" => {
+                let value = tokens.next().map(str::to_string);
+                if let Some((_, creds)) = current.as_mut() {
+                    creds.password = value;
+                }
+            }
+            "account" => {
+                tokens.next();
+            }
+            "macdef" => {
+                // Macro definitions run until an empty line, which
+                // whitespace tokenizing cannot see; stop parsing instead of
+                // misreading the macro body as entries.
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((host, creds)) = current.take() {
+        result.insert(host, creds);
+    }
+
+    result.retain(|_, creds| creds.username.is_some());
+
+    result
+}
+
+fn netrc_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("NETRC") {
+        return Some(PathBuf::from(path));
+    }
+
+    let file_name = if cfg!(windows) { "_netrc" } else { ".netrc" };
+
+    dirs::home_dir().map(|home| home.join(file_name))
+}
+
+fn read_credentials_file(path: &Path) -> Result<HashMap<String, HostCredentials>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("Failed to read credentials file `{}`.", path.display()))
+        }
+    };
+
+    // Deliberately do not include the toml error: its message quotes the
+    // offending line, which may contain a secret.
+    let parsed: CredentialsFile = match toml::from_str(&content) {
+        Ok(parsed) => parsed,
+        Err(_) => bail!("Failed to parse credentials file `{}`.", path.display()),
+    };
+
+    for (host, creds) in &parsed.hosts {
+        if creds.token.is_some() && creds.username.is_some() {
+            bail!(
+                "The credentials for host `{}` in `{}` set both a token and a username; use only one.",
+                host,
+                path.display()
+            );
+        }
+    }
+
+    Ok(parsed.hosts)
+}
+
+fn load_credentials() -> Result<Credentials> {
+    let paths = get_paths()?;
+
+    let hosts = read_credentials_file(&paths.juliauphome.join("credentials.toml"))?;
+
+    let netrc = match netrc_path() {
+        Some(path) => match std::fs::read_to_string(&path) {
+            Ok(content) => parse_netrc(&content),
+            Err(e) => {
+                log::debug!("Not using netrc file `{}`: {}", path.display(), e);
+                HashMap::new()
+            }
+        },
+        None => HashMap::new(),
+    };
+
+    Ok(Credentials { hosts, netrc })
+}
+
+impl Credentials {
+    fn lookup(&self, url: &Url) -> Option<HostCredentials> {
+        let host = url.host_str()?;
+        let host_port = url.port().map(|port| format!("{}:{}", host, port));
+
+        host_port
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(host))
+            .find_map(|key| self.hosts.get(key).or_else(|| self.netrc.get(key)))
+            .cloned()
+    }
+}
+
+/// Returns the credentials configured for the host of `url`, if any.
+pub fn credentials_for(url: &str) -> Result<Option<HostCredentials>> {
+    let credentials = CREDENTIALS
+        .get_or_init(|| load_credentials().map_err(|e| format!("{:#}", e)))
+        .as_ref()
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let url = match Url::parse(url) {
+        Ok(url) => url,
+        Err(_) => return Ok(None),
+    };
+
+    let creds = credentials.lookup(&url);
+
+    if creds.is_some() {
+        log::debug!(
+            "Using configured credentials for host `{}`.",
+            url.host_str().unwrap_or_default()
+        );
+    }
+
+    Ok(creds)
+}
+
+/// Whether the configured credentials for `url` include an `Authorization`
+/// header.
+pub fn sends_authorization(url: &str) -> bool {
+    matches!(credentials_for(url), Ok(Some(creds)) if creds
+        .headers()
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("authorization")))
+}
+
+/// Adds the configured credentials for `url` to a request.
+#[cfg(not(windows))]
+pub fn apply_credentials(
+    mut request: reqwest::blocking::RequestBuilder,
+    url: &str,
+) -> Result<reqwest::blocking::RequestBuilder> {
+    if let Some(creds) = credentials_for(url)? {
+        for (name, value) in creds.headers() {
+            let mut value = reqwest::header::HeaderValue::from_str(&value)
+                .with_context(|| format!("Invalid value for credential header `{}`.", name))?;
+            value.set_sensitive(true);
+            request = request.header(name, value);
+        }
+    }
+
+    Ok(request)
+}
+
+/// Adds the configured credentials for `url` to a request header collection.
+#[cfg(windows)]
+pub fn apply_credentials(
+    headers: &windows::Web::Http::Headers::HttpRequestHeaderCollection,
+    url: &str,
+) -> Result<()> {
+    use windows::core::HSTRING;
+
+    if let Some(creds) = credentials_for(url)? {
+        for (name, value) in creds.headers() {
+            headers
+                .TryAppendWithoutValidation(&HSTRING::from(&name), &HSTRING::from(&value))
+                .with_context(|| format!("Failed to set credential header `{}`.", name))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_matches_rfc4648_vectors() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn netrc_machine_entries_are_parsed_and_default_is_ignored() {
+        let netrc = parse_netrc(
+            "machine mirror.example.com login alice password s3cret\n\
+             machine other.example.com\n  login bob\n  account x\n  password pw\n\
+             default login anonymous password guest\n",
+        );
+
+        assert_eq!(netrc.len(), 2);
+        let alice = &netrc["mirror.example.com"];
+        assert_eq!(alice.username.as_deref(), Some("alice"));
+        assert_eq!(alice.password.as_deref(), Some("s3cret"));
+        assert_eq!(netrc["other.example.com"].password.as_deref(), Some("pw"));
+    }
+
+    #[test]
+    fn lookup_prefers_host_port_and_credentials_file() {
+        let mut credentials = Credentials::default();
+        credentials.hosts.insert(
+            "mirror.example.com".to_string(),
+            HostCredentials {
+                token: Some("file-token".to_string()),
+                ..Default::default()
+            },
+        );
+        credentials.hosts.insert(
+            "mirror.example.com:8443".to_string(),
+            HostCredentials {
+                token: Some("port-token".to_string()),
+                ..Default::default()
+            },
+        );
+        credentials.netrc = parse_netrc("machine mirror.example.com login a password b");
+
+        let lookup = |url: &str| {
+            credentials
+                .lookup(&Url::parse(url).unwrap())
+                .map(|c| c.headers())
+        };
+
+        assert_eq!(
+            lookup("https://mirror.example.com/bin/x.tar.gz"),
+            Some(vec![(
+                "Authorization".to_string(),
+                "Bearer file-token".to_string()
+            )])
+        );
+        assert_eq!(
+            lookup("https://mirror.example.com:8443/x"),
+            Some(vec![(
+                "Authorization".to_string(),
+                "Bearer port-token".to_string()
+            )])
+        );
+        assert_eq!(lookup("https://julialang-s3.julialang.org/x"), None);
+    }
+
+    #[test]
+    fn basic_auth_and_custom_headers() {
+        let creds = HostCredentials {
+            username: Some("alice".to_string()),
+            password: Some("s3cret".to_string()),
+            headers: HashMap::from([("X-Api-Key".to_string(), "key".to_string())]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            creds.headers(),
+            vec![
+                ("X-Api-Key".to_string(), "key".to_string()),
+                (
+                    "Authorization".to_string(),
+                    "Basic YWxpY2U6czNjcmV0".to_string()
+                ),
+            ]
+        );
+    }
+}
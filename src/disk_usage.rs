@@ -0,0 +1,221 @@
+//! On-disk size of the Julia installations that Juliaup manages.
+
+use crate::config_file::{JuliaupConfig, JuliaupConfigChannel};
+use itertools::Itertools;
+use numeric_sort::cmp;
+use std::path::{Path, PathBuf};
+
+pub struct VersionUsage {
+    pub version: String,
+    pub size: u64,
+    /// The channels that point at this version, sorted.
+    pub channels: Vec<String>,
+}
+
+impl VersionUsage {
+    /// Removing one of the channels would not free the space.
+    pub fn is_shared(&self) -> bool {
+        self.channels.len() > 1
+    }
+}
+
+pub struct ChannelUsage {
+    pub name: String,
+    /// The installation directory, relative to `juliauphome`.
+    pub path: String,
+    pub size: u64,
+}
+
+pub struct TempDirUsage {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+pub struct DiskUsage {
+    pub versions: Vec<VersionUsage>,
+    /// Nightly and pull request channels, which own their installation.
+    pub channels: Vec<ChannelUsage>,
+    /// `julia-temp-*` leftovers of interrupted downloads.
+    pub temp_dirs: Vec<TempDirUsage>,
+}
+
+impl DiskUsage {
+    pub fn total(&self) -> u64 {
+        self.versions.iter().map(|v| v.size).sum::<u64>()
+            + self.channels.iter().map(|c| c.size).sum::<u64>()
+            + self.temp_dirs.iter().map(|t| t.size).sum::<u64>()
+    }
+}
+
+/// Total size of the files below `path`. Symlinks are counted, but not
+/// followed, so links out of an installation do not inflate its size.
+pub fn dir_size(path: &Path) -> u64 {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return 0,
+    };
+
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    std::fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| dir_size(&entry.path()))
+        .sum()
+}
+
+/// `julia-temp-*` directories in `juliauphome`.
+pub fn find_temp_dirs(juliauphome: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(juliauphome)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("julia-temp-")
+                && entry.path().is_dir()
+        })
+        .map(|entry| entry.path())
+        .sorted()
+        .collect()
+}
+
+pub fn compute_disk_usage(config: &JuliaupConfig, juliauphome: &Path) -> DiskUsage {
+    let versions = config
+        .installed_versions
+        .iter()
+        .sorted_by(|(a, _), (b, _)| cmp(a, b))
+        .map(|(version, detail)| VersionUsage {
+            version: version.clone(),
+            size: dir_size(&juliauphome.join(&detail.path)),
+            channels: config
+                .installed_channels
+                .iter()
+                .filter(|(_, channel)| {
+                    matches!(channel, JuliaupConfigChannel::SystemChannel { version: v } if v == version)
+                })
+                .map(|(name, _)| name.clone())
+                .sorted_by(|a, b| cmp(a, b))
+                .collect(),
+        })
+        .collect();
+
+    let channels = config
+        .installed_channels
+        .iter()
+        .sorted_by(|(a, _), (b, _)| cmp(a, b))
+        .filter_map(|(name, channel)| match channel {
+            JuliaupConfigChannel::DirectDownloadChannel { path, .. } => Some(ChannelUsage {
+                name: name.clone(),
+                path: path.clone(),
+                size: dir_size(&juliauphome.join(path)),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    let temp_dirs = find_temp_dirs(juliauphome)
+        .into_iter()
+        .map(|path| TempDirUsage {
+            size: dir_size(&path),
+            path,
+        })
+        .collect();
+
+    DiskUsage {
+        versions,
+        channels,
+        temp_dirs,
+    }
+}
+
+/// `1536` becomes `1.5 KiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::JuliaupConfigVersion;
+
+    #[test]
+    fn format_size_picks_a_unit() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(300 * 1024 * 1024), "300.0 MiB");
+    }
+
+    #[test]
+    fn disk_usage_covers_versions_channels_and_temp_dirs() {
+        let home = tempfile::tempdir().unwrap();
+        let write = |rel: &str, len: usize| {
+            let path = home.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, vec![0u8; len]).unwrap();
+        };
+        write("julia-1.10.4+0.x64.linux.gnu/bin/julia", 100);
+        write("julia-1.10.4+0.x64.linux.gnu/lib/libjulia.so", 50);
+        write("julia-nightly/bin/julia", 20);
+        write("julia-temp-abc123/partial", 7);
+
+        let mut config = JuliaupConfig::default();
+        config.installed_versions.insert(
+            "1.10.4+0.x64.linux.gnu".to_string(),
+            JuliaupConfigVersion {
+                path: "./julia-1.10.4+0.x64.linux.gnu".to_string(),
+                binary_path: None,
+            },
+        );
+        for name in ["release", "lts"] {
+            config.installed_channels.insert(
+                name.to_string(),
+                JuliaupConfigChannel::SystemChannel {
+                    version: "1.10.4+0.x64.linux.gnu".to_string(),
+                },
+            );
+        }
+        config.installed_channels.insert(
+            "nightly".to_string(),
+            JuliaupConfigChannel::DirectDownloadChannel {
+                path: "./julia-nightly".to_string(),
+                url: "https://example.com/julia-latest.tar.gz".to_string(),
+                local_etag: String::new(),
+                server_etag: String::new(),
+                version: String::new(),
+                binary_path: None,
+            },
+        );
+
+        let usage = compute_disk_usage(&config, home.path());
+
+        assert_eq!(usage.versions.len(), 1);
+        assert_eq!(usage.versions[0].size, 150);
+        assert_eq!(usage.versions[0].channels, vec!["lts", "release"]);
+        assert!(usage.versions[0].is_shared());
+        assert_eq!(usage.channels[0].name, "nightly");
+        assert_eq!(usage.channels[0].size, 20);
+        assert_eq!(usage.temp_dirs.len(), 1);
+        assert_eq!(usage.temp_dirs[0].size, 7);
+        assert_eq!(usage.total(), 177);
+    }
+}
@@ -0,0 +1,227 @@
+//! Bandwidth limiting for downloads.
+//!
+//! The effective limit is, in order of precedence, the `--limit-rate` flag of
+//! the running command, or the `maxdownloadrate` setting. Downloads started in
+//! the background (version db updates and self-updates triggered by the
+//! launcher or the scheduler) are additionally capped by the
+//! `maxbackgrounddownloadrate` setting so they don't saturate a slow link.
+
+use anyhow::{bail, Result};
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::config_file::JuliaupConfigSettings;
+use crate::utils::configured_settings;
+
+static RATE_LIMIT_OVERRIDE: OnceLock<u64> = OnceLock::new();
+static BACKGROUND_DOWNLOADS: AtomicBool = AtomicBool::new(false);
+
+/// Sets the rate limit from a command line flag for the rest of the process.
+pub fn set_download_rate_limit(bytes_per_second: u64) {
+    let _ = RATE_LIMIT_OVERRIDE.set(bytes_per_second);
+}
+
+/// Marks all downloads of this process as background downloads.
+pub fn mark_background_downloads() {
+    BACKGROUND_DOWNLOADS.store(true, Ordering::Relaxed);
+}
+
+/// Whether this process was started in the background by the launcher or the
+/// scheduler, see [`mark_background_downloads`].
+pub fn is_background_process() -> bool {
+    BACKGROUND_DOWNLOADS.load(Ordering::Relaxed)
+}
+
+/// Parses a rate such as `800`, `500K`, `1.5M` or `1G` (bytes per second, with
+/// binary multiples). `0` means unlimited.
+pub fn parse_rate(value: &str) -> Result<u64> {
+    let value = value.trim();
+    let (number, multiplier) = match value.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => {
+            let multiplier: u64 = match c.to_ascii_uppercase() {
+                'K' => 1 << 10,
+                'M' => 1 << 20,
+                'G' => 1 << 30,
+                _ => bail!(
+                    "Invalid rate '{}'. Use a number of bytes per second, optionally followed by K, M or G.",
+                    value
+                ),
+            };
+            (&value[..i], multiplier)
+        }
+        _ => (value, 1),
+    };
+
+    match number.parse::<f64>() {
+        Ok(n) if n.is_finite() && n >= 0.0 => Ok((n * multiplier as f64) as u64),
+        _ => bail!(
+            "Invalid rate '{}'. Use a number of bytes per second, optionally followed by K, M or G.",
+            value
+        ),
+    }
+}
+
+/// Formats a rate the way [`parse_rate`] accepts it, using the largest unit
+/// that represents it exactly.
+pub fn format_rate(bytes_per_second: u64) -> String {
+    if bytes_per_second == 0 {
+        return "0 (unlimited)".to_string();
+    }
+
+    for (suffix, multiplier) in [("G", 1u64 << 30), ("M", 1 << 20), ("K", 1 << 10)] {
+        if bytes_per_second.is_multiple_of(multiplier) {
+            return format!("{}{}", bytes_per_second / multiplier, suffix);
+        }
+    }
+
+    bytes_per_second.to_string()
+}
+
+fn effective_rate_limit_for(
+    settings: &JuliaupConfigSettings,
+    flag: Option<u64>,
+    background: bool,
+) -> Option<u64> {
+    let foreground = flag.unwrap_or(settings.max_download_rate);
+
+    let limit = if background {
+        [foreground, settings.max_background_download_rate]
+            .into_iter()
+            .filter(|rate| *rate > 0)
+            .min()
+            .unwrap_or(0)
+    } else {
+        foreground
+    };
+
+    (limit > 0).then_some(limit)
+}
+
+/// The rate limit in bytes per second for downloads of this process, or
+/// `None` if downloads are unlimited.
+pub fn effective_rate_limit() -> Option<u64> {
+    let flag = RATE_LIMIT_OVERRIDE.get().copied();
+    let background = is_background_process();
+
+    match configured_settings() {
+        Some(settings) => effective_rate_limit_for(settings, flag, background),
+        None => effective_rate_limit_for(&JuliaupConfigSettings::default(), flag, background),
+    }
+}
+
+/// A reader that sleeps as needed to keep the average throughput of `inner`
+/// at or below `bytes_per_second`.
+pub struct ThrottledReader<R> {
+    inner: R,
+    bytes_per_second: Option<u64>,
+    start: Instant,
+    bytes_read: u64,
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let Some(rate) = self.bytes_per_second else {
+            return self.inner.read(buf);
+        };
+
+        // Read in slices of roughly a tenth of a second so that throttling
+        // stays smooth instead of bursting a large buffer and then stalling.
+        let max_chunk = usize::try_from((rate / 10).max(1)).unwrap_or(usize::MAX);
+        let len = buf.len().min(max_chunk);
+        let n = self.inner.read(&mut buf[..len])?;
+        self.bytes_read += n as u64;
+
+        let expected = Duration::from_secs_f64(self.bytes_read as f64 / rate as f64);
+        let elapsed = self.start.elapsed();
+        if expected > elapsed {
+            std::thread::sleep(expected - elapsed);
+        }
+
+        Ok(n)
+    }
+}
+
+/// Wraps `inner` so that it is read no faster than the effective rate limit.
+pub fn throttle<R: Read>(inner: R) -> ThrottledReader<R> {
+    let bytes_per_second = effective_rate_limit();
+
+    if let Some(rate) = bytes_per_second {
+        log::debug!("Limiting download rate to {} bytes/s.", rate);
+    }
+
+    ThrottledReader {
+        inner,
+        bytes_per_second,
+        start: Instant::now(),
+        bytes_read: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rate_accepts_suffixes() {
+        assert_eq!(parse_rate("800").unwrap(), 800);
+        assert_eq!(parse_rate("500K").unwrap(), 500 * 1024);
+        assert_eq!(parse_rate("1.5m").unwrap(), 3 * 512 * 1024);
+        assert_eq!(parse_rate("1G").unwrap(), 1 << 30);
+        assert_eq!(parse_rate("0").unwrap(), 0);
+        assert!(parse_rate("fast").is_err());
+        assert!(parse_rate("-1K").is_err());
+        assert!(parse_rate("10X").is_err());
+    }
+
+    #[test]
+    fn format_rate_round_trips() {
+        for rate in [800, 500 * 1024, 2 << 20, 1 << 30] {
+            assert_eq!(parse_rate(&format_rate(rate)).unwrap(), rate);
+        }
+    }
+
+    #[test]
+    fn background_downloads_use_the_lower_limit() {
+        let mut settings = JuliaupConfigSettings {
+            max_download_rate: 0,
+            max_background_download_rate: 1 << 20,
+            ..Default::default()
+        };
+
+        assert_eq!(effective_rate_limit_for(&settings, None, false), None);
+        assert_eq!(
+            effective_rate_limit_for(&settings, None, true),
+            Some(1 << 20)
+        );
+        assert_eq!(
+            effective_rate_limit_for(&settings, Some(512 << 10), true),
+            Some(512 << 10)
+        );
+
+        settings.max_background_download_rate = 0;
+        assert_eq!(
+            effective_rate_limit_for(&settings, Some(2 << 20), false),
+            Some(2 << 20)
+        );
+        assert_eq!(effective_rate_limit_for(&settings, None, true), None);
+    }
+
+    #[test]
+    fn throttled_reader_limits_throughput() {
+        let data = vec![0u8; 4096];
+        let mut reader = ThrottledReader {
+            inner: &data[..],
+            bytes_per_second: Some(16 * 1024),
+            start: Instant::now(),
+            bytes_read: 0,
+        };
+
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+
+        assert_eq!(out.len(), data.len());
+        assert!(reader.start.elapsed() >= Duration::from_millis(200));
+    }
+}
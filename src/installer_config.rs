@@ -0,0 +1,309 @@
+//! Settings for an unattended `juliainstaller` run. They can come from a TOML
+//! file passed with `--config` and from `JULIAINSTALLER_*` environment
+//! variables, so that provisioning tools can configure everything at install
+//! time instead of running `juliaup config` afterwards.
+
+use crate::utils::{parse_server_url, JuliaupServer};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct InstallerConfig {
+    pub path: Option<String>,
+    pub default_channel: Option<String>,
+    pub juliaup_channel: Option<String>,
+    pub add_to_path: Option<bool>,
+    pub background_selfupdate: Option<i64>,
+    pub startup_selfupdate: Option<i64>,
+    pub channel_symlinks: Option<bool>,
+    /// Channels to install in addition to the default channel.
+    pub channels: Option<Vec<String>>,
+    pub versionsdb_update_interval: Option<i64>,
+    pub auto_install_channels: Option<bool>,
+    pub manifest_version_detect: Option<bool>,
+    pub server: Option<String>,
+    pub nightly_server: Option<String>,
+    pub pr_server: Option<String>,
+}
+
+fn parse_env_bool(name: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "yes" | "y" | "true" | "on" => Ok(true),
+        "0" | "no" | "n" | "false" | "off" => Ok(false),
+        _ => bail!(
+            "Invalid value '{}' for {}. Valid values are: yes, no, true, false, 1, 0.",
+            value,
+            name
+        ),
+    }
+}
+
+fn parse_env_i64(name: &str, value: &str) -> Result<i64> {
+    value
+        .parse::<i64>()
+        .with_context(|| format!("Invalid value '{}' for {}. Expected a number.", value, name))
+}
+
+impl InstallerConfig {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| {
+            format!(
+                "Failed to read installer configuration file `{}`.",
+                path.display()
+            )
+        })?;
+
+        toml::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse installer configuration file `{}`.",
+                path.display()
+            )
+        })
+    }
+
+    /// Reads the `JULIAINSTALLER_*` variables through `lookup`. Empty values
+    /// count as unset.
+    pub fn from_env_vars(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| lookup(name).filter(|value| !value.is_empty());
+        let bool_var = |name: &str| {
+            var(name)
+                .map(|value| parse_env_bool(name, &value))
+                .transpose()
+        };
+        let i64_var = |name: &str| {
+            var(name)
+                .map(|value| parse_env_i64(name, &value))
+                .transpose()
+        };
+
+        Ok(InstallerConfig {
+            path: var("JULIAINSTALLER_PATH"),
+            default_channel: var("JULIAINSTALLER_DEFAULT_CHANNEL"),
+            juliaup_channel: var("JULIAINSTALLER_JULIAUP_CHANNEL"),
+            add_to_path: bool_var("JULIAINSTALLER_ADD_TO_PATH")?,
+            background_selfupdate: i64_var("JULIAINSTALLER_BACKGROUND_SELFUPDATE")?,
+            startup_selfupdate: i64_var("JULIAINSTALLER_STARTUP_SELFUPDATE")?,
+            channel_symlinks: bool_var("JULIAINSTALLER_CHANNEL_SYMLINKS")?,
+            channels: var("JULIAINSTALLER_CHANNELS").map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|channel| !channel.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }),
+            versionsdb_update_interval: i64_var("JULIAINSTALLER_VERSIONSDB_UPDATE_INTERVAL")?,
+            auto_install_channels: bool_var("JULIAINSTALLER_AUTO_INSTALL_CHANNELS")?,
+            manifest_version_detect: bool_var("JULIAINSTALLER_MANIFEST_VERSION_DETECT")?,
+            server: var("JULIAINSTALLER_SERVER"),
+            nightly_server: var("JULIAINSTALLER_NIGHTLY_SERVER"),
+            pr_server: var("JULIAINSTALLER_PR_SERVER"),
+        })
+    }
+
+    /// Fills every setting that is not set in `self` from `fallback`.
+    pub fn or(self, fallback: InstallerConfig) -> Self {
+        InstallerConfig {
+            path: self.path.or(fallback.path),
+            default_channel: self.default_channel.or(fallback.default_channel),
+            juliaup_channel: self.juliaup_channel.or(fallback.juliaup_channel),
+            add_to_path: self.add_to_path.or(fallback.add_to_path),
+            background_selfupdate: self
+                .background_selfupdate
+                .or(fallback.background_selfupdate),
+            startup_selfupdate: self.startup_selfupdate.or(fallback.startup_selfupdate),
+            channel_symlinks: self.channel_symlinks.or(fallback.channel_symlinks),
+            channels: self.channels.or(fallback.channels),
+            versionsdb_update_interval: self
+                .versionsdb_update_interval
+                .or(fallback.versionsdb_update_interval),
+            auto_install_channels: self
+                .auto_install_channels
+                .or(fallback.auto_install_channels),
+            manifest_version_detect: self
+                .manifest_version_detect
+                .or(fallback.manifest_version_detect),
+            server: self.server.or(fallback.server),
+            nightly_server: self.nightly_server.or(fallback.nightly_server),
+            pr_server: self.pr_server.or(fallback.pr_server),
+        }
+    }
+
+    /// The servers to configure, next to the server they are for.
+    pub fn servers(&self) -> Vec<(JuliaupServer, &str)> {
+        [
+            (JuliaupServer::Release, &self.server),
+            (JuliaupServer::Nightly, &self.nightly_server),
+            (JuliaupServer::Pr, &self.pr_server),
+        ]
+        .into_iter()
+        .filter_map(|(server, value)| Some((server, value.as_deref()?)))
+        .collect()
+    }
+
+    /// Makes every download of this process use the configured servers.
+    ///
+    /// The installer downloads Juliaup before `juliaup.json` exists, and the
+    /// settings from that file are read only once per process, so the servers
+    /// are passed on through the `JULIAUP_*SERVER` variables instead. Variables
+    /// that are already set win, as they do over `juliaup.json`.
+    pub fn apply_servers_to_environment(&self) {
+        for (server, value) in self.servers() {
+            if std::env::var_os(server.env_var()).is_none() {
+                std::env::set_var(server.env_var(), value);
+            }
+        }
+    }
+
+    /// Rejects values that the interactive wizard would not accept either.
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("background-selfupdate", self.background_selfupdate),
+            ("startup-selfupdate", self.startup_selfupdate),
+            (
+                "versionsdb-update-interval",
+                self.versionsdb_update_interval,
+            ),
+        ] {
+            if value.is_some_and(|value| value < 0) {
+                bail!("The installer setting `{}` must not be negative.", name);
+            }
+        }
+
+        for (server, value) in self.servers() {
+            parse_server_url(
+                value,
+                &format!("the installer setting for `{}`", server.config_property()),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn config_file_covers_all_settings() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("install.toml");
+        std::fs::write(
+            &file,
+            r#"
+path = "/opt/juliaup"
+default-channel = "lts"
+juliaup-channel = "releasepreview"
+add-to-path = false
+background-selfupdate = 60
+startup-selfupdate = 0
+channel-symlinks = true
+channels = ["1.10", "nightly"]
+versionsdb-update-interval = 120
+auto-install-channels = true
+manifest-version-detect = false
+server = "https://mirror.example.com/julia/"
+nightly-server = "https://mirror.example.com/nightly/"
+pr-server = "https://mirror.example.com/pr/"
+"#,
+        )?;
+
+        let config = InstallerConfig::from_file(&file)?;
+        assert_eq!(config.path.as_deref(), Some("/opt/juliaup"));
+        assert_eq!(config.juliaup_channel.as_deref(), Some("releasepreview"));
+        assert_eq!(config.add_to_path, Some(false));
+        assert_eq!(config.channel_symlinks, Some(true));
+        assert_eq!(
+            config.channels,
+            Some(vec!["1.10".to_string(), "nightly".to_string()])
+        );
+        assert_eq!(config.versionsdb_update_interval, Some(120));
+        assert_eq!(config.manifest_version_detect, Some(false));
+        assert_eq!(
+            config.pr_server.as_deref(),
+            Some("https://mirror.example.com/pr/")
+        );
+
+        std::fs::write(&file, "add-to-pth = true\n")?;
+        assert!(InstallerConfig::from_file(&file).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn env_vars_are_parsed() -> Result<()> {
+        let vars = HashMap::from([
+            ("JULIAINSTALLER_ADD_TO_PATH", "no"),
+            ("JULIAINSTALLER_CHANNELS", "1.10, lts,"),
+            ("JULIAINSTALLER_STARTUP_SELFUPDATE", "30"),
+            ("JULIAINSTALLER_SERVER", ""),
+        ]);
+        let config = InstallerConfig::from_env_vars(|name| vars.get(name).map(|v| v.to_string()))?;
+
+        assert_eq!(config.add_to_path, Some(false));
+        assert_eq!(
+            config.channels,
+            Some(vec!["1.10".to_string(), "lts".to_string()])
+        );
+        assert_eq!(config.startup_selfupdate, Some(30));
+        assert_eq!(config.server, None);
+
+        let invalid = HashMap::from([("JULIAINSTALLER_CHANNEL_SYMLINKS", "maybe")]);
+        assert!(
+            InstallerConfig::from_env_vars(|name| invalid.get(name).map(|v| v.to_string()))
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn earlier_sources_take_precedence() {
+        let env = InstallerConfig {
+            default_channel: Some("lts".to_string()),
+            ..Default::default()
+        };
+        let file = InstallerConfig {
+            default_channel: Some("release".to_string()),
+            channel_symlinks: Some(true),
+            ..Default::default()
+        };
+
+        let merged = env.or(file);
+        assert_eq!(merged.default_channel.as_deref(), Some("lts"));
+        assert_eq!(merged.channel_symlinks, Some(true));
+
+        let negative = InstallerConfig {
+            background_selfupdate: Some(-1),
+            ..Default::default()
+        };
+        assert!(negative.validate().is_err());
+
+        let insecure = InstallerConfig {
+            server: Some("http://mirror.example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(insecure.validate().is_err());
+    }
+
+    #[test]
+    fn configured_server_is_used_for_downloads() {
+        let _guard = crate::utils::tests::ENV_LOCK.lock().unwrap();
+
+        // As in the installer, the settings cache is already filled before
+        // `juliaup.json` is written.
+        let _ = crate::utils::configured_settings();
+
+        let config = InstallerConfig {
+            server: Some("https://mirror.example.com/julia".to_string()),
+            ..Default::default()
+        };
+        config.apply_servers_to_environment();
+        let url = crate::utils::get_juliaserver_base_url();
+        std::env::remove_var("JULIAUP_SERVER");
+
+        assert_eq!(url.unwrap().as_str(), "https://mirror.example.com/julia/");
+    }
+}
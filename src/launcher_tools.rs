@@ -0,0 +1,282 @@
+//! Julia ships tools besides `julia` itself, e.g. `juliac`. For every tool
+//! name configured with `juliaup config launchertools`, juliaup puts an entry
+//! for the Julia launcher with that name into the bin directory. When the
+//! launcher is started through such an entry, it selects a channel exactly as
+//! it does for `julia` and then runs the tool of that channel instead.
+
+use crate::operations::julia_launcher_binary;
+use crate::utils::{get_bin_dir, print_juliaup_style, JuliaupMessageType};
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// How to run a tool of a Julia installation.
+#[derive(Debug, PartialEq)]
+pub enum ToolCommand {
+    /// An executable in the `bin` directory of the installation.
+    Executable(PathBuf),
+    /// A script in `share/julia` that is run with the channel's `julia`.
+    Script(PathBuf),
+}
+
+/// Checks that `name` can be used as a command name for a launcher tool.
+pub fn validate_tool_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        || name.starts_with('.')
+    {
+        bail!("`{}` is not a valid tool name.", name);
+    }
+
+    if ["julia", "julialauncher", "juliaup", "juliaupgui"].contains(&name)
+        || name.starts_with("julia-")
+    {
+        bail!("`{}` is reserved and can't be used as a tool name.", name);
+    }
+
+    Ok(())
+}
+
+/// The tool the launcher was started as, judging by the name it was invoked
+/// with, if that is one of the configured `tools`.
+pub fn tool_name_from_argv0<'a>(argv0: &str, tools: &'a [String]) -> Option<&'a str> {
+    let file_name = Path::new(argv0).file_name()?.to_str()?;
+
+    tools
+        .iter()
+        .find(|tool| {
+            if cfg!(windows) {
+                // Started as `juliac` or `juliac.exe`, in any case.
+                tool.eq_ignore_ascii_case(file_name)
+                    || format!("{}.exe", tool).eq_ignore_ascii_case(file_name)
+            } else {
+                *tool == file_name
+            }
+        })
+        .map(String::as_str)
+}
+
+/// Finds `tool` in the Julia installation that `julia_path` belongs to.
+pub fn resolve_tool(julia_path: &Path, tool: &str) -> Result<ToolCommand> {
+    let bin_dir = julia_path.parent().with_context(|| {
+        format!(
+            "Could not determine the bin directory of `{}`.",
+            julia_path.display()
+        )
+    })?;
+
+    let executable = bin_dir.join(format!("{}{}", tool, std::env::consts::EXE_SUFFIX));
+    if executable.is_file() {
+        return Ok(ToolCommand::Executable(executable));
+    }
+
+    if let Some(share_dir) = bin_dir.parent().map(|p| p.join("share").join("julia")) {
+        for script in [
+            share_dir.join(tool).join(format!("{}.jl", tool)),
+            share_dir.join(format!("{}.jl", tool)),
+        ] {
+            if script.is_file() {
+                return Ok(ToolCommand::Script(script));
+            }
+        }
+    }
+
+    bail!(
+        "The Julia installation at `{}` does not provide `{}`.",
+        bin_dir.display(),
+        tool
+    );
+}
+
+fn tool_entry_path(bin_dir: &Path, tool: &str) -> PathBuf {
+    bin_dir.join(format!("{}{}", tool, std::env::consts::EXE_SUFFIX))
+}
+
+/// Whether `entry_path` is an entry that juliaup created: a symlink to the
+/// launcher or, on Windows, a copy of the current launcher or of the one that
+/// the last self-update replaced. The bin directory can be shared, e.g.
+/// `~/.local/bin`, so anything else belongs to the user.
+fn is_launcher_entry(entry_path: &Path, launcher: &Path) -> bool {
+    #[cfg(not(windows))]
+    {
+        let is_symlink = entry_path
+            .symlink_metadata()
+            .is_ok_and(|metadata| metadata.file_type().is_symlink());
+        is_symlink
+            && matches!(
+                (entry_path.canonicalize(), launcher.canonicalize()),
+                (Ok(target), Ok(launcher)) if target == launcher
+            )
+    }
+
+    #[cfg(windows)]
+    {
+        use crate::operations::previous_binaries_dir;
+
+        let entry = match std::fs::read(entry_path) {
+            Ok(entry) => entry,
+            Err(_) => return false,
+        };
+        let previous = launcher
+            .parent()
+            .zip(launcher.file_name())
+            .and_then(|(dir, name)| Some(previous_binaries_dir(dir).ok()?.join(name)));
+
+        std::iter::once(launcher.to_path_buf())
+            .chain(previous)
+            .any(|candidate| std::fs::read(candidate).is_ok_and(|content| content == entry))
+    }
+}
+
+/// Puts an entry for the Julia launcher named `tool` into the bin directory.
+pub fn create_tool_entry(tool: &str) -> Result<()> {
+    validate_tool_name(tool)?;
+
+    let bin_dir = get_bin_dir()
+        .with_context(|| "Failed to retrieve binary directory while trying to add a tool.")?;
+
+    std::fs::create_dir_all(&bin_dir).with_context(|| {
+        format!(
+            "Failed to create the binary directory `{}`.",
+            bin_dir.display()
+        )
+    })?;
+
+    let entry_path = tool_entry_path(&bin_dir, tool);
+    let launcher = julia_launcher_binary()?;
+
+    if entry_path.symlink_metadata().is_ok() {
+        if !is_launcher_entry(&entry_path, &launcher) {
+            bail!(
+                "`{}` already exists and was not created by Juliaup. Remove it or configure a different tool name.",
+                entry_path.display()
+            );
+        }
+
+        std::fs::remove_file(&entry_path)
+            .with_context(|| format!("Failed to remove `{}`.", entry_path.display()))?;
+    }
+
+    print_juliaup_style(
+        "Creating",
+        &format!("launcher entry {}", entry_path.display()),
+        JuliaupMessageType::Progress,
+    );
+
+    // The launcher looks at the name it was started with, so on Windows,
+    // where symlinks need special privileges, it gets a copy of its own.
+    #[cfg(not(windows))]
+    std::os::unix::fs::symlink(&launcher, &entry_path)
+        .with_context(|| format!("Failed to create symlink `{}`.", entry_path.display()))?;
+
+    #[cfg(windows)]
+    std::fs::copy(&launcher, &entry_path)
+        .with_context(|| format!("Failed to create `{}`.", entry_path.display()))?;
+
+    Ok(())
+}
+
+/// Removes the launcher entry named `tool` from the bin directory.
+pub fn remove_tool_entry(tool: &str) -> Result<()> {
+    let bin_dir = get_bin_dir()
+        .with_context(|| "Failed to retrieve binary directory while trying to remove a tool.")?;
+
+    let entry_path = tool_entry_path(&bin_dir, tool);
+
+    if entry_path.symlink_metadata().is_ok() {
+        if !is_launcher_entry(&entry_path, &julia_launcher_binary()?) {
+            bail!(
+                "`{}` was not created by Juliaup and is left in place. Remove it yourself if it is no longer needed.",
+                entry_path.display()
+            );
+        }
+
+        print_juliaup_style(
+            "Deleting",
+            &format!("launcher entry {}", entry_path.display()),
+            JuliaupMessageType::Progress,
+        );
+
+        std::fs::remove_file(&entry_path)
+            .with_context(|| format!("Failed to remove `{}`.", entry_path.display()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_names_are_validated() {
+        assert!(validate_tool_name("juliac").is_ok());
+        assert!(validate_tool_name("julia-config.jl").is_err());
+        assert!(validate_tool_name("julia").is_err());
+        assert!(validate_tool_name("../juliac").is_err());
+        assert!(validate_tool_name("").is_err());
+    }
+
+    #[test]
+    fn only_configured_tools_are_recognized() {
+        let tools = vec!["juliac".to_string()];
+        let exe = |name: &str| format!("/opt/bin/{}{}", name, std::env::consts::EXE_SUFFIX);
+
+        assert_eq!(tool_name_from_argv0(&exe("juliac"), &tools), Some("juliac"));
+        assert_eq!(tool_name_from_argv0(&exe("julia"), &tools), None);
+        assert_eq!(tool_name_from_argv0(&exe("julialauncher"), &tools), None);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn only_symlinks_to_the_launcher_are_launcher_entries() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let launcher = dir.path().join("julialauncher");
+        let other = dir.path().join("other");
+        std::fs::write(&launcher, "launcher")?;
+        std::fs::write(&other, "other")?;
+
+        let entry = dir.path().join("juliac");
+        std::os::unix::fs::symlink(&launcher, &entry)?;
+        assert!(is_launcher_entry(&entry, &launcher));
+
+        let foreign_link = dir.path().join("juliac-link");
+        std::os::unix::fs::symlink(&other, &foreign_link)?;
+        assert!(!is_launcher_entry(&foreign_link, &launcher));
+
+        // A user's own binary, even with the same content.
+        let copy = dir.path().join("juliac-copy");
+        std::fs::write(&copy, "launcher")?;
+        assert!(!is_launcher_entry(&copy, &launcher));
+        Ok(())
+    }
+
+    #[test]
+    fn tools_are_found_in_bin_and_share() -> Result<()> {
+        let install = tempfile::tempdir()?;
+        let bin = install.path().join("bin");
+        let share = install.path().join("share").join("julia");
+        std::fs::create_dir_all(&bin)?;
+        std::fs::create_dir_all(share.join("juliac"))?;
+
+        let julia = bin.join(format!("julia{}", std::env::consts::EXE_SUFFIX));
+        let script = share.join("juliac").join("juliac.jl");
+        std::fs::write(&script, "")?;
+        assert_eq!(
+            resolve_tool(&julia, "juliac")?,
+            ToolCommand::Script(script.clone())
+        );
+
+        // An executable takes precedence over a script.
+        let executable = bin.join(format!("juliac{}", std::env::consts::EXE_SUFFIX));
+        std::fs::write(&executable, "")?;
+        assert_eq!(
+            resolve_tool(&julia, "juliac")?,
+            ToolCommand::Executable(executable)
+        );
+
+        assert!(resolve_tool(&julia, "missingtool").is_err());
+        Ok(())
+    }
+}
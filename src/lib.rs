@@ -1,18 +1,29 @@
 use anyhow::Context;
 
+pub mod changelog;
 pub mod cli;
 pub mod cli_styles;
 pub mod command_add;
 pub mod command_api;
+pub mod command_bisect;
 pub mod command_completions;
 pub mod command_config_autoinstall;
+pub mod command_config_autoprunefinishedprs;
 pub mod command_config_backgroundselfupdate;
+pub mod command_config_channelshims;
+pub mod command_config_download_rate;
+pub mod command_config_launchertools;
+pub mod command_config_libc;
 pub mod command_config_manifestversiondetect;
 pub mod command_config_modifypath;
+pub mod command_config_network;
+pub mod command_config_server;
 pub mod command_config_startupselfupdate;
 pub mod command_config_symlinks;
+pub mod command_config_tls;
 pub mod command_config_versionsdbupdate;
 pub mod command_default;
+pub mod command_doctor;
 pub mod command_gc;
 pub mod command_info;
 pub mod command_initial_setup_from_launcher;
@@ -22,16 +33,29 @@ pub mod command_list_channels;
 pub mod command_override;
 pub mod command_post_update;
 pub mod command_remove;
+pub mod command_repair;
+pub mod command_selfchangelog;
 pub mod command_selfchannel;
+pub mod command_selfrollback;
 pub mod command_selfuninstall;
 pub mod command_selfupdate;
+pub mod command_shell_init;
 pub mod command_status;
 pub mod command_update;
 pub mod command_update_version_db;
 pub mod config_file;
+pub mod credentials;
+pub mod disk_usage;
+pub mod download_rate;
 pub mod global_paths;
+pub mod installer_config;
 pub mod jsonstructs_versionsdb;
+pub mod launcher_tools;
+pub mod libc_flavor;
 pub mod operations;
+pub mod selfupdate_signature;
+pub mod temp_dirs;
+pub mod tls;
 pub mod utils;
 pub mod version_selection;
 pub mod versions_file;
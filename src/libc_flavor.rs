@@ -0,0 +1,372 @@
+//! Selection between glibc and musl builds of Julia on Linux.
+//!
+//! The versions db only lists glibc builds, but musl builds are published
+//! next to them for x86_64 from Julia 1.6 on. When the db is loaded, every
+//! such x86_64 channel gets a `~glibc` and a `~musl` variant, and plain channels point at the flavor that
+//! matches the running system, or the `libc` setting if one is configured.
+
+use crate::jsonstructs_versionsdb::{
+    JuliaupVersionDB, JuliaupVersionDBChannel, JuliaupVersionDBVersion,
+};
+use anyhow::{bail, Result};
+use semver::Version;
+
+const GLIBC_VERSION_SUFFIX: &str = ".x64.linux.gnu";
+const MUSL_VERSION_SUFFIX: &str = ".x64.linux.musl";
+/// The first minor release with musl builds, prereleases included. There are
+/// no aarch64 musl builds, so only x86_64 gets musl variants.
+const FIRST_MUSL_RELEASE: (u64, u64) = (1, 6);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LibcFlavor {
+    Glibc,
+    Musl,
+}
+
+impl LibcFlavor {
+    pub fn parse(value: &str) -> Result<LibcFlavor> {
+        match value.to_lowercase().as_str() {
+            "glibc" | "gnu" => Ok(LibcFlavor::Glibc),
+            "musl" => Ok(LibcFlavor::Musl),
+            _ => bail!(
+                "Invalid value '{}'. Valid values are: glibc, musl, default (to unset the property)",
+                value
+            ),
+        }
+    }
+
+    /// The channel suffix, and the value of the `libc` setting.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LibcFlavor::Glibc => "glibc",
+            LibcFlavor::Musl => "musl",
+        }
+    }
+}
+
+/// Detects the C library of the running system from the dynamic loader that
+/// `/bin/sh` asks for. Which loaders are installed is only a fallback: glibc
+/// systems can have the musl loader installed too, e.g. by Debian's `musl`
+/// package.
+pub fn detect_libc() -> LibcFlavor {
+    let interpreter = std::fs::read("/bin/sh")
+        .ok()
+        .and_then(|elf| elf_interpreter(&elf));
+
+    match interpreter {
+        Some(interpreter) if interpreter.contains("ld-musl-") => LibcFlavor::Musl,
+        Some(_) => LibcFlavor::Glibc,
+        None => {
+            let loaders: Vec<String> = ["/lib", "/lib64"]
+                .iter()
+                .filter_map(|dir| std::fs::read_dir(dir).ok())
+                .flatten()
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect();
+            libc_from_loaders(&loaders)
+        }
+    }
+}
+
+/// Picks glibc whenever its loader `ld-linux-*` is installed, and musl only if
+/// its loader `ld-musl-*` is the only one.
+fn libc_from_loaders(file_names: &[String]) -> LibcFlavor {
+    let has = |prefix: &str| file_names.iter().any(|name| name.starts_with(prefix));
+
+    if !has("ld-linux") && has("ld-musl-") {
+        LibcFlavor::Musl
+    } else {
+        LibcFlavor::Glibc
+    }
+}
+
+/// The `PT_INTERP` entry of an ELF file, i.e. the dynamic loader it runs with.
+fn elf_interpreter(elf: &[u8]) -> Option<String> {
+    const PT_INTERP: u32 = 3;
+
+    if elf.get(..4)? != b"\x7fELF" {
+        return None;
+    }
+    let is_64_bit = match elf.get(4)? {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let little_endian = match elf.get(5)? {
+        1 => true,
+        2 => false,
+        _ => return None,
+    };
+
+    let read = |offset: usize, len: usize| -> Option<u64> {
+        let bytes = elf.get(offset..offset.checked_add(len)?)?;
+        let mut value = 0u64;
+        for i in 0..len {
+            let byte = if little_endian {
+                bytes[len - 1 - i]
+            } else {
+                bytes[i]
+            };
+            value = (value << 8) | u64::from(byte);
+        }
+        Some(value)
+    };
+    let word = if is_64_bit { 8 } else { 4 };
+
+    let (phoff, phentsize, phnum) = if is_64_bit {
+        (read(0x20, 8)?, read(0x36, 2)?, read(0x38, 2)?)
+    } else {
+        (read(0x1c, 4)?, read(0x2a, 2)?, read(0x2c, 2)?)
+    };
+
+    (0..phnum).find_map(|i| {
+        let header = usize::try_from(phoff + i * phentsize).ok()?;
+        if read(header, 4)? != u64::from(PT_INTERP) {
+            return None;
+        }
+        let (offset, size) = if is_64_bit {
+            (read(header + 8, word)?, read(header + 0x20, word)?)
+        } else {
+            (read(header + 4, word)?, read(header + 0x10, word)?)
+        };
+        let start = usize::try_from(offset).ok()?;
+        let bytes = elf.get(start..start.checked_add(usize::try_from(size).ok()?)?)?;
+        let interpreter = bytes.split(|&b| b == 0).next()?;
+        Some(String::from_utf8_lossy(interpreter).into_owned())
+    })
+}
+
+/// The flavor that plain channels install: the `libc` setting if it is set,
+/// otherwise whatever the running system uses.
+pub fn default_libc(setting: Option<&str>) -> LibcFlavor {
+    match setting.map(LibcFlavor::parse) {
+        Some(Ok(flavor)) => flavor,
+        Some(Err(e)) => {
+            log::debug!("Ignoring the `libc` setting: {}", e);
+            detect_libc()
+        }
+        None => detect_libc(),
+    }
+}
+
+/// `bin/linux/x64/1.10/julia-1.10.0-linux-x86_64.tar.gz` becomes
+/// `bin/musl/x64/1.10/julia-1.10.0-musl-x86_64.tar.gz`.
+fn musl_url_path(glibc_url_path: &str) -> Option<String> {
+    let rest = glibc_url_path.strip_prefix("bin/linux/x64/")?;
+    let (folder, file) = rest.rsplit_once('/')?;
+    let file = file.replacen("-linux-x86_64", "-musl-x86_64", 1);
+    Some(format!("bin/musl/x64/{}/{}", folder, file))
+}
+
+fn musl_version(glibc_version: &str) -> Option<String> {
+    let base = glibc_version.strip_suffix(GLIBC_VERSION_SUFFIX)?;
+    let version = Version::parse(glibc_version).ok()?;
+    if (version.major, version.minor) < FIRST_MUSL_RELEASE {
+        return None;
+    }
+    Some(format!("{}{}", base, MUSL_VERSION_SUFFIX))
+}
+
+/// Adds the musl builds of the x86_64 glibc versions that have one to `db`, a `~glibc` and
+/// `~musl` variant for every channel without an architecture suffix, and
+/// points the x86_64 channels at the `default` flavor.
+pub fn add_libc_variants(db: &mut JuliaupVersionDB, default: LibcFlavor) {
+    let musl_versions: Vec<(String, String)> = db
+        .available_versions
+        .iter()
+        .filter_map(|(version, info)| {
+            Some((musl_version(version)?, musl_url_path(&info.url_path)?))
+        })
+        .collect();
+    for (version, url_path) in musl_versions {
+        db.available_versions
+            .insert(version, JuliaupVersionDBVersion { url_path });
+    }
+
+    let channels: Vec<(String, String)> = db
+        .available_channels
+        .iter()
+        .map(|(name, channel)| (name.clone(), channel.version.clone()))
+        .collect();
+    for (name, glibc_version) in channels {
+        let musl_version = match musl_version(&glibc_version) {
+            Some(musl_version) if db.available_versions.contains_key(&musl_version) => musl_version,
+            _ => continue,
+        };
+
+        if !name.contains('~') {
+            for (flavor, version) in [
+                (LibcFlavor::Glibc, &glibc_version),
+                (LibcFlavor::Musl, &musl_version),
+            ] {
+                db.available_channels.insert(
+                    format!("{}~{}", name, flavor.name()),
+                    JuliaupVersionDBChannel {
+                        version: version.clone(),
+                    },
+                );
+            }
+        }
+
+        if default == LibcFlavor::Musl {
+            db.available_channels.insert(
+                name,
+                JuliaupVersionDBChannel {
+                    version: musl_version,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn test_db() -> JuliaupVersionDB {
+        let mut db = JuliaupVersionDB {
+            available_versions: HashMap::new(),
+            available_channels: HashMap::new(),
+            version: "1.0.0".to_string(),
+        };
+        for (version, url_path) in [
+            (
+                "1.10.0+0.x64.linux.gnu",
+                "bin/linux/x64/1.10/julia-1.10.0-linux-x86_64.tar.gz",
+            ),
+            (
+                "1.10.0+0.x86.linux.gnu",
+                "bin/linux/x86/1.10/julia-1.10.0-linux-i686.tar.gz",
+            ),
+            (
+                "1.5.4+0.x64.linux.gnu",
+                "bin/linux/x64/1.5/julia-1.5.4-linux-x86_64.tar.gz",
+            ),
+            (
+                "1.6.0-rc1+0.x64.linux.gnu",
+                "bin/linux/x64/1.6/julia-1.6.0-rc1-linux-x86_64.tar.gz",
+            ),
+        ] {
+            db.available_versions.insert(
+                version.to_string(),
+                JuliaupVersionDBVersion {
+                    url_path: url_path.to_string(),
+                },
+            );
+        }
+        for (name, version) in [
+            ("1.10", "1.10.0+0.x64.linux.gnu"),
+            ("1.10~x86", "1.10.0+0.x86.linux.gnu"),
+            ("1.5", "1.5.4+0.x64.linux.gnu"),
+        ] {
+            db.available_channels.insert(
+                name.to_string(),
+                JuliaupVersionDBChannel {
+                    version: version.to_string(),
+                },
+            );
+        }
+        db
+    }
+
+    fn channel_version<'a>(db: &'a JuliaupVersionDB, name: &str) -> Option<&'a str> {
+        db.available_channels
+            .get(name)
+            .map(|channel| channel.version.as_str())
+    }
+
+    #[test]
+    fn musl_variants_are_added() {
+        let mut db = test_db();
+        add_libc_variants(&mut db, LibcFlavor::Glibc);
+
+        assert_eq!(
+            db.available_versions["1.10.0+0.x64.linux.musl"].url_path,
+            "bin/musl/x64/1.10/julia-1.10.0-musl-x86_64.tar.gz"
+        );
+        assert_eq!(channel_version(&db, "1.10"), Some("1.10.0+0.x64.linux.gnu"));
+        assert_eq!(
+            channel_version(&db, "1.10~musl"),
+            Some("1.10.0+0.x64.linux.musl")
+        );
+        assert_eq!(
+            channel_version(&db, "1.10~glibc"),
+            Some("1.10.0+0.x64.linux.gnu")
+        );
+        // There are no 32-bit musl builds.
+        assert_eq!(channel_version(&db, "1.10~x86~musl"), None);
+        // Nor any before Julia 1.6, but its prereleases have them.
+        assert!(!db.available_versions.contains_key("1.5.4+0.x64.linux.musl"));
+        assert_eq!(channel_version(&db, "1.5~musl"), None);
+        assert!(db
+            .available_versions
+            .contains_key("1.6.0-rc1+0.x64.linux.musl"));
+    }
+
+    #[test]
+    fn musl_default_switches_plain_channels() {
+        let mut db = test_db();
+        add_libc_variants(&mut db, LibcFlavor::Musl);
+
+        assert_eq!(
+            channel_version(&db, "1.10"),
+            Some("1.10.0+0.x64.linux.musl")
+        );
+        assert_eq!(
+            channel_version(&db, "1.10~glibc"),
+            Some("1.10.0+0.x64.linux.gnu")
+        );
+        assert_eq!(
+            channel_version(&db, "1.10~x86"),
+            Some("1.10.0+0.x86.linux.gnu")
+        );
+        assert_eq!(channel_version(&db, "1.5"), Some("1.5.4+0.x64.linux.gnu"));
+        assert!(LibcFlavor::parse("uclibc").is_err());
+    }
+
+    #[test]
+    fn glibc_wins_when_both_loaders_are_installed() {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            libc_from_loaders(&names(&[
+                "ld-linux-x86-64.so.2",
+                "ld-musl-x86_64.so.1",
+                "libc.so.6"
+            ])),
+            LibcFlavor::Glibc
+        );
+        assert_eq!(
+            libc_from_loaders(&names(&["ld-musl-x86_64.so.1"])),
+            LibcFlavor::Musl
+        );
+        assert_eq!(libc_from_loaders(&[]), LibcFlavor::Glibc);
+    }
+
+    #[test]
+    fn elf_interpreter_is_read_from_program_headers() {
+        let interpreter = b"/lib/ld-musl-x86_64.so.1\0";
+
+        // A 64-bit little-endian ELF header, followed by one PT_INTERP
+        // program header and the interpreter path.
+        let mut elf = vec![0u8; 0x40 + 0x38];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes());
+        elf[0x36..0x38].copy_from_slice(&0x38u16.to_le_bytes());
+        elf[0x38..0x3a].copy_from_slice(&1u16.to_le_bytes());
+        elf[0x40..0x44].copy_from_slice(&3u32.to_le_bytes());
+        elf[0x48..0x50].copy_from_slice(&0x78u64.to_le_bytes());
+        elf[0x60..0x68].copy_from_slice(&(interpreter.len() as u64).to_le_bytes());
+        elf.extend_from_slice(interpreter);
+
+        assert_eq!(
+            elf_interpreter(&elf).as_deref(),
+            Some("/lib/ld-musl-x86_64.so.1")
+        );
+        assert_eq!(elf_interpreter(b"#!/bin/sh\n"), None);
+    }
+}
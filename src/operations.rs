@@ -6,13 +6,27 @@ use crate::config_file::load_mut_config_db;
 use crate::config_file::save_config_db;
 use crate::config_file::JuliaupConfig;
 use crate::config_file::JuliaupConfigChannel;
+use crate::config_file::JuliaupConfigSettings;
 use crate::config_file::JuliaupConfigVersion;
+#[cfg(not(windows))]
+use crate::credentials::credentials_for;
+use crate::credentials::{apply_credentials, sends_authorization};
+use crate::download_rate::throttle;
 use crate::get_bundled_dbversion;
 use crate::get_bundled_julia_version;
 use crate::get_juliaup_target;
 use crate::global_paths::GlobalPaths;
 use crate::jsonstructs_versionsdb::JuliaupVersionDB;
+use crate::selfupdate_signature::{verify_release_archive, SIGNATURE_SUFFIX};
+use crate::temp_dirs::{
+    create_download_temp_dir, release_download_temp_dir, remove_leftover_temp_dirs,
+};
+#[cfg(not(windows))]
+use crate::tls::configure_tls;
+#[cfg(windows)]
+use crate::tls::warn_if_tls_configured;
 use crate::utils::check_server_supports_nightlies;
+use crate::utils::configured_settings;
 use crate::utils::get_bin_dir;
 use crate::utils::get_julianightlies_base_url;
 use crate::utils::get_juliaprs_base_url;
@@ -29,7 +43,12 @@ use flate2::read::GzDecoder;
 use indicatif::{ProgressBar, ProgressStyle};
 use indoc::formatdoc;
 use regex::Regex;
+use retry::{
+    delay::{jitter, Exponential},
+    retry, OperationResult,
+};
 use semver::Version;
+use serde::{Deserialize, Serialize};
 #[cfg(not(windows))]
 use std::os::unix::fs::PermissionsExt;
 
@@ -39,6 +58,7 @@ use std::{
 };
 #[cfg(not(target_os = "freebsd"))]
 use tar::Archive;
+#[cfg(target_os = "macos")]
 use tempfile::Builder;
 use tempfile::TempDir;
 use tempfile::TempPath;
@@ -47,13 +67,183 @@ use url::Url;
 // Progress bar prefix with proper indentation to match other messages (12 characters wide, right-aligned)
 const DOWNLOADING_PREFIX: &str = " Downloading";
 
-/// Creates an HTTP client with a proper User-Agent header.
+/// Initial delay before retrying a failed request; doubles on every retry.
+const RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// Reads a network setting, falling back to its default when the
+/// configuration cannot be loaded.
+fn network_setting(get: impl Fn(&JuliaupConfigSettings) -> i64) -> i64 {
+    match configured_settings() {
+        Some(settings) => get(settings),
+        None => get(&JuliaupConfigSettings::default()),
+    }
+}
+
+/// Converts a timeout setting in seconds to a `Duration`; 0 disables it.
+#[cfg(not(windows))]
+fn timeout_from_setting(seconds: i64) -> Option<std::time::Duration> {
+    u64::try_from(seconds)
+        .ok()
+        .filter(|s| *s > 0)
+        .map(std::time::Duration::from_secs)
+}
+
+/// Runs a network operation, retrying it with exponential backoff up to the
+/// configured `downloadretries` times. `operation` receives whether this is
+/// the last attempt, so that it can hand back e.g. an HTTP error response
+/// instead of retrying it.
+fn with_network_retries<T>(
+    url: &str,
+    mut operation: impl FnMut(bool) -> OperationResult<T, Error>,
+) -> Result<T> {
+    let retries = usize::try_from(network_setting(|s| s.download_retries)).unwrap_or(0);
+    let mut attempt = 0;
+
+    retry(
+        Exponential::from_millis(RETRY_BASE_DELAY_MS)
+            .map(jitter)
+            .take(retries),
+        || {
+            attempt += 1;
+            let result = operation(attempt > retries);
+            if let OperationResult::Retry(e) = &result {
+                log::debug!(
+                    "Request to `{}` failed (attempt {} of {}), retrying: {:#}",
+                    url,
+                    attempt,
+                    retries + 1,
+                    e
+                );
+            }
+            result
+        },
+    )
+    .map_err(|e| e.error)
+}
+
+/// Win32 error codes of the Windows HTTP stack that may go away on a retry:
+/// timeouts, name resolution and connection failures.
+#[cfg(windows)]
+const TRANSIENT_WININET_ERRORS: [u32; 6] = [
+    12002, // ERROR_INTERNET_TIMEOUT
+    12007, // ERROR_INTERNET_NAME_NOT_RESOLVED
+    12029, // ERROR_INTERNET_CANNOT_CONNECT
+    12030, // ERROR_INTERNET_CONNECTION_ABORTED
+    12031, // ERROR_INTERNET_CONNECTION_RESET
+    12152, // ERROR_HTTP_INVALID_SERVER_RESPONSE
+];
+
+/// Decides whether a request sent with the Windows HTTP stack is retried, with
+/// the same rules as `send_with_retries`. `GetAsync` and `SendRequestAsync`
+/// succeed for any status code, so HTTP 429 and server errors are detected
+/// here; of the errors only transient transport failures are retried, not
+/// e.g. TLS or URI errors.
+#[cfg(windows)]
+fn windows_retry_decision(
+    result: windows::core::Result<windows::Web::Http::HttpResponseMessage>,
+    last_attempt: bool,
+) -> OperationResult<windows::Web::Http::HttpResponseMessage, Error> {
+    match result {
+        Ok(response) => {
+            let status = match response.StatusCode() {
+                Ok(status) => status.0,
+                Err(e) => return OperationResult::Err(anyhow!("{:?}", e)),
+            };
+
+            if !last_attempt && ((500..600).contains(&status) || status == 429) {
+                OperationResult::Retry(anyhow!("HTTP {}", status))
+            } else {
+                OperationResult::Ok(response)
+            }
+        }
+        Err(e) => {
+            let code = e.code().0 as u32;
+            let transient = code & 0xFFFF_0000 == 0x8007_0000
+                && TRANSIENT_WININET_ERRORS.contains(&(code & 0xFFFF));
+
+            if transient {
+                OperationResult::Retry(anyhow!("{:?}", e))
+            } else {
+                OperationResult::Err(anyhow!("{:?}", e))
+            }
+        }
+    }
+}
+
+/// Sends the request built by `build`, retrying connection failures,
+/// timeouts, HTTP 429 and server errors. Only the request and the response
+/// headers are covered; the caller reads the body.
+#[cfg(not(windows))]
+fn send_with_retries(
+    url: &str,
+    build: impl Fn() -> Result<reqwest::blocking::RequestBuilder>,
+) -> Result<reqwest::blocking::Response> {
+    with_network_retries(url, |last_attempt| {
+        let request = match build() {
+            Ok(request) => request,
+            Err(e) => return OperationResult::Err(e),
+        };
+
+        match request.send() {
+            Ok(response)
+                if !last_attempt
+                    && (response.status().is_server_error()
+                        || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS) =>
+            {
+                OperationResult::Retry(anyhow!("HTTP {}", response.status()))
+            }
+            Ok(response) => OperationResult::Ok(response),
+            Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => {
+                OperationResult::Retry(e.into())
+            }
+            Err(e) => OperationResult::Err(e.into()),
+        }
+    })
+}
+
+/// Follows redirects like reqwest's default policy, but refuses to leave the
+/// host of a request that carries configured credentials. reqwest only strips
+/// its own authentication headers on such redirects, so custom headers from
+/// `credentials.toml` would reach the redirect target.
+#[cfg(not(windows))]
+fn credential_safe_redirect(attempt: reqwest::redirect::Attempt) -> reqwest::redirect::Action {
+    const MAX_REDIRECTS: usize = 10;
+
+    if attempt.previous().len() > MAX_REDIRECTS {
+        return attempt.error("too many redirects");
+    }
+
+    let origin = attempt.previous()[0].clone();
+    let has_credentials = matches!(credentials_for(origin.as_str()), Ok(Some(_)));
+    if has_credentials && attempt.url().origin() != origin.origin() {
+        let message = format!(
+            "Refusing to follow the redirect from `{}` to `{}`, which would send the credentials configured for `{}` to another host.",
+            origin,
+            attempt.url(),
+            origin.host_str().unwrap_or_default()
+        );
+        return attempt.error(message);
+    }
+
+    attempt.follow()
+}
+
+/// Creates an HTTP client with a proper User-Agent header, the configured
+/// timeouts, and the configured CA bundle and client certificate.
 /// Some CDNs (like CloudFront) block requests without User-Agent.
 #[cfg(not(windows))]
-fn http_client() -> Result<reqwest::blocking::Client> {
+pub(crate) fn http_client() -> Result<reqwest::blocking::Client> {
     let user_agent = format!("juliaup/{}", env!("CARGO_PKG_VERSION"));
-    reqwest::blocking::Client::builder()
+    // In the blocking client `timeout` bounds waiting for the response and
+    // every individual read of the body, so it acts as a read timeout and
+    // does not limit the total duration of large downloads.
+    let builder = reqwest::blocking::Client::builder()
         .user_agent(user_agent)
+        .connect_timeout(timeout_from_setting(network_setting(|s| s.connect_timeout)))
+        .timeout(timeout_from_setting(network_setting(|s| s.read_timeout)))
+        .redirect(reqwest::redirect::Policy::custom(credential_safe_redirect));
+
+    configure_tls(builder)?
         .build()
         .with_context(|| "Failed to create HTTP client")
 }
@@ -61,9 +251,11 @@ fn http_client() -> Result<reqwest::blocking::Client> {
 /// Creates a Windows HTTP client with a proper User-Agent header.
 /// Some CDNs (like CloudFront) block requests without User-Agent.
 #[cfg(windows)]
-fn http_client() -> Result<windows::Web::Http::HttpClient> {
+pub(crate) fn http_client() -> Result<windows::Web::Http::HttpClient> {
     use windows::core::HSTRING;
 
+    warn_if_tls_configured();
+
     let http_client =
         windows::Web::Http::HttpClient::new().with_context(|| "Failed to create HttpClient.")?;
 
@@ -293,9 +485,8 @@ pub fn download_extract_dmg(url: &str, target_path: &Path) -> Result<String> {
     }
 
     log::debug!("Downloading DMG from url `{}`.", url);
-    let response = http_client()?
-        .get(url)
-        .send()
+    let client = http_client()?;
+    let response = send_with_retries(url, || apply_credentials(client.get(url), url))
         .with_context(|| format!("Failed to download from url `{}`.", url))?;
 
     if !response.status().is_success() {
@@ -320,7 +511,7 @@ pub fn download_extract_dmg(url: &str, target_path: &Path) -> Result<String> {
     let temp_dmg = Builder::new().prefix("julia-").suffix(".dmg").tempfile()?;
 
     let mut dmg_file = File::create(temp_dmg.path())?;
-    std::io::copy(&mut pb.wrap_read(response), &mut dmg_file)?;
+    std::io::copy(&mut pb.wrap_read(throttle(response)), &mut dmg_file)?;
     dmg_file.flush()?;
     drop(dmg_file);
 
@@ -443,9 +634,8 @@ pub fn download_extract_sans_parent(
     levels_to_skip: usize,
 ) -> Result<String> {
     log::debug!("Downloading from url `{}`.", url);
-    let response = http_client()?
-        .get(url)
-        .send()
+    let client = http_client()?;
+    let response = send_with_retries(url, || apply_credentials(client.get(url), url))
         .with_context(|| format!("Failed to download from url `{}`.", url))?;
 
     let content_length = response.content_length();
@@ -466,7 +656,7 @@ pub fn download_extract_sans_parent(
         .map(|etag| etag.to_str().unwrap_or("").to_string())
         .unwrap_or_default();
 
-    let response_with_pb = pb.wrap_read(response);
+    let response_with_pb = pb.wrap_read(throttle(response));
 
     unpack_sans_parent(response_with_pb, target_path, levels_to_skip)
         .with_context(|| format!("Failed to extract downloaded file from url `{}`.", url))?;
@@ -503,15 +693,18 @@ pub fn download_extract_sans_parent(
     use windows::core::HSTRING;
 
     let http_client = http_client()?;
+    apply_credentials(&http_client.DefaultRequestHeaders()?, url)?;
 
     let request_uri = windows::Foundation::Uri::CreateUri(&HSTRING::from(url))
         .with_context(|| "Failed to convert url string to Uri.")?;
 
-    let http_response = http_client
-        .GetAsync(&request_uri)
-        .with_context(|| "Failed to initiate download.")?
-        .join()
-        .with_context(|| "Failed to complete async download operation.")?;
+    let http_response = with_network_retries(url, |last_attempt| {
+        windows_retry_decision(
+            http_client.GetAsync(&request_uri).and_then(|op| op.join()),
+            last_attempt,
+        )
+    })
+    .with_context(|| "Failed to complete async download operation.")?;
 
     http_response
         .EnsureSuccessStatusCode()
@@ -553,7 +746,7 @@ pub fn download_extract_sans_parent(
     pb.set_prefix(DOWNLOADING_PREFIX);
     pb.set_style(bar_style());
 
-    let response_with_pb = pb.wrap_read(DataReaderWrap(reader));
+    let response_with_pb = pb.wrap_read(throttle(DataReaderWrap(reader)));
 
     unpack_sans_parent(response_with_pb, target_path, levels_to_skip)
         .with_context(|| format!("Failed to extract downloaded file from url `{}`.", url))?;
@@ -561,11 +754,142 @@ pub fn download_extract_sans_parent(
     Ok(last_modified)
 }
 
+#[cfg(not(windows))]
+fn download_bytes(url: &str, show_progress: bool) -> Result<Vec<u8>> {
+    log::debug!("Downloading from url `{}`.", url);
+    let client = http_client()?;
+    let response = send_with_retries(url, || apply_credentials(client.get(url), url))
+        .with_context(|| format!("Failed to download from url `{}`.", url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to download from url `{}`.", url))?;
+
+    let pb = match (show_progress, response.content_length()) {
+        (false, _) => ProgressBar::hidden(),
+        (true, Some(content_length)) => ProgressBar::new(content_length),
+        (true, None) => ProgressBar::new_spinner(),
+    };
+
+    pb.set_prefix(DOWNLOADING_PREFIX);
+    pb.set_style(bar_style());
+
+    let mut buf: Vec<u8> = vec![];
+    pb.wrap_read(throttle(response))
+        .read_to_end(&mut buf)
+        .with_context(|| format!("Failed to download from url `{}`.", url))?;
+
+    Ok(buf)
+}
+
+#[cfg(windows)]
+fn download_bytes(url: &str, show_progress: bool) -> Result<Vec<u8>> {
+    use windows::core::HSTRING;
+
+    let http_client = http_client()?;
+    apply_credentials(&http_client.DefaultRequestHeaders()?, url)?;
+
+    let request_uri = windows::Foundation::Uri::CreateUri(&HSTRING::from(url))
+        .with_context(|| "Failed to convert url string to Uri.")?;
+
+    let http_response = with_network_retries(url, |last_attempt| {
+        windows_retry_decision(
+            http_client.GetAsync(&request_uri).and_then(|op| op.join()),
+            last_attempt,
+        )
+    })
+    .with_context(|| "Failed to complete async download operation.")?;
+
+    http_response
+        .EnsureSuccessStatusCode()
+        .with_context(|| format!("Failed to download from `{}`.", url))?;
+
+    let http_response_content = http_response
+        .Content()
+        .with_context(|| "Failed to obtain content from http response.")?;
+
+    let response_stream = http_response_content
+        .ReadAsInputStreamAsync()
+        .with_context(|| "Failed to initiate get input stream from response")?
+        .join()
+        .with_context(|| "Failed to obtain input stream from http response")?;
+
+    let reader = windows::Storage::Streams::DataReader::CreateDataReader(&response_stream)
+        .with_context(|| "Failed to create DataReader.")?;
+
+    reader
+        .SetInputStreamOptions(windows::Storage::Streams::InputStreamOptions::ReadAhead)
+        .with_context(|| "Failed to set input stream options.")?;
+
+    let mut content_length: u64 = 0;
+    let pb = if !show_progress {
+        ProgressBar::hidden()
+    } else if http_response_content.TryComputeLength(&mut content_length)? {
+        ProgressBar::new(content_length)
+    } else {
+        ProgressBar::new_spinner()
+    };
+
+    pb.set_prefix(DOWNLOADING_PREFIX);
+    pb.set_style(bar_style());
+
+    let mut buf: Vec<u8> = vec![];
+    pb.wrap_read(throttle(DataReaderWrap(reader)))
+        .read_to_end(&mut buf)
+        .with_context(|| format!("Failed to download from url `{}`.", url))?;
+
+    Ok(buf)
+}
+
+/// Downloads a small text file, such as release notes.
+pub fn download_text(url: &str) -> Result<String> {
+    let content = download_bytes(url, false)?;
+
+    String::from_utf8(content).map_err(|_| anyhow!("The content of `{}` is not valid text.", url))
+}
+
+/// Fails unless a signature is published for the juliaup release archive at
+/// `url`, without downloading the archive itself.
+pub fn ensure_release_is_signed(url: &str) -> Result<()> {
+    download_bytes(&format!("{}{}", url, SIGNATURE_SUFFIX), false)?;
+
+    Ok(())
+}
+
+/// Downloads a juliaup release archive together with its detached signature
+/// and extracts it into `target_path` only if the signature is valid. Nothing
+/// is written to disk before the signature has been checked.
+pub fn download_verify_extract_sans_parent(
+    url: &str,
+    target_path: &Path,
+    levels_to_skip: usize,
+) -> Result<()> {
+    let signature_url = format!("{}{}", url, SIGNATURE_SUFFIX);
+
+    // Fetch the small signature first so that a release without one fails
+    // before the archive is downloaded.
+    let signature = download_bytes(&signature_url, false)
+        .with_context(|| "Failed to download the signature of the update.")?;
+    let signature = String::from_utf8(signature)
+        .map_err(|_| anyhow!("The signature at `{}` is not valid text.", signature_url))?;
+
+    let archive = download_bytes(url, true)?;
+
+    verify_release_archive(&archive, &signature).with_context(|| {
+        format!(
+            "Refusing to install `{}` because its signature could not be verified.",
+            url
+        )
+    })?;
+
+    unpack_sans_parent(std::io::Cursor::new(archive), target_path, levels_to_skip)
+        .with_context(|| format!("Failed to extract downloaded file from url `{}`.", url))?;
+
+    Ok(())
+}
+
 #[cfg(not(windows))]
 pub fn download_juliaup_version(url: &str) -> Result<Version> {
-    let response = http_client()?
-        .get(url)
-        .send()
+    let client = http_client()?;
+    let response = send_with_retries(url, || apply_credentials(client.get(url), url))
         .with_context(|| format!("Failed to download from url `{}`.", url))?;
 
     let status = response.status();
@@ -599,9 +923,8 @@ pub fn download_juliaup_version(url: &str) -> Result<Version> {
 
 #[cfg(not(windows))]
 pub fn download_versiondb(url: &str, path: &Path) -> Result<()> {
-    let mut response = http_client()?
-        .get(url)
-        .send()
+    let client = http_client()?;
+    let mut response = send_with_retries(url, || apply_credentials(client.get(url), url))
         .with_context(|| format!("Failed to download from url `{}`.", url))?;
 
     let status = response.status();
@@ -645,19 +968,7 @@ pub fn download_versiondb(url: &str, path: &Path) -> Result<()> {
 
 #[cfg(windows)]
 pub fn download_juliaup_version(url: &str) -> Result<Version> {
-    let http_client = http_client()?;
-
-    let request_uri = windows::Foundation::Uri::CreateUri(&windows::core::HSTRING::from(url))
-        .with_context(|| "Failed to convert url string to Uri.")?;
-
-    let async_op = http_client
-        .GetStringAsync(&request_uri)
-        .with_context(|| "Failed on http_client.GetStringAsync")?;
-
-    let response = async_op
-        .join()
-        .with_context(|| "Failed on http_client.GetStringAsync.get")?
-        .to_string();
+    let response = download_text(url)?;
 
     let trimmed_response = response.trim();
 
@@ -673,17 +984,7 @@ pub fn download_juliaup_version(url: &str) -> Result<Version> {
 
 #[cfg(windows)]
 pub fn download_versiondb(url: &str, path: &Path) -> Result<()> {
-    let http_client = http_client()?;
-
-    let request_uri = windows::Foundation::Uri::CreateUri(&windows::core::HSTRING::from(url))
-        .with_context(|| "Failed to convert url string to Uri.")?;
-
-    let response = http_client
-        .GetStringAsync(&request_uri)
-        .with_context(|| "Failed to download version db step 1.")?
-        .join()
-        .with_context(|| "Failed to download version db step 2.")?
-        .to_string();
+    let response = download_text(url).with_context(|| "Failed to download version db.")?;
 
     let mut file = std::fs::OpenOptions::new()
         .write(true)
@@ -712,7 +1013,7 @@ pub fn download_versiondb(url: &str, path: &Path) -> Result<()> {
 /// Resolves the Julia binary within `target_path` (handling .app bundles on macOS),
 /// then returns it as a relative path anchored at `rel_prefix` (which is typically
 /// `./julia-{version}` or `./julia-{channel}`).
-fn compute_relative_binary_path(
+pub fn compute_relative_binary_path(
     target_path: &Path,
     rel_prefix: &Path,
     juliauphome: &Path,
@@ -756,10 +1057,7 @@ pub fn download_version_to_temp(
         )
     })?;
 
-    let temp_dir = Builder::new()
-        .prefix("julia-temp-")
-        .tempdir_in(&paths.juliauphome)
-        .with_context(|| "Failed to create temporary directory for download.")?;
+    let temp_dir = create_download_temp_dir(&paths.juliauphome)?;
 
     // TODO At some point we could put this behind a conditional compile, we know
     // that we don't ship a bundled version for some platforms.
@@ -855,6 +1153,9 @@ pub fn commit_version_install(
     // Another process may have installed this exact version while we were
     // downloading. In that case discard our download and reuse the existing one.
     if config_data.installed_versions.contains_key(fullversion) {
+        let temp_path = downloaded.path().to_path_buf();
+        drop(downloaded);
+        release_download_temp_dir(&temp_path);
         return Ok(());
     }
 
@@ -882,7 +1183,9 @@ pub fn commit_version_install(
     }
 
     // keep() consumes the TempDir and returns the path without cleanup
-    retry_rename(&downloaded.keep(), &target_path)?;
+    let temp_path = downloaded.keep();
+    retry_rename(&temp_path, &target_path)?;
+    release_download_temp_dir(&temp_path);
 
     let mut rel_path = PathBuf::new();
     rel_path.push(".");
@@ -966,8 +1269,43 @@ pub fn is_valid_channel(versions_db: &JuliaupVersionDB, channel: &String) -> Res
     Ok(regular || nightly)
 }
 
+/// Whether `channel` installs a build staged by the CI of a pull request,
+/// i.e. `pr<number>`, `pr<number>@<sha>` or `commit-<sha>`.
 pub fn is_pr_channel(channel: &str) -> bool {
-    Regex::new(r"^(pr\d+)(~|$)").unwrap().is_match(channel)
+    Regex::new(r"^(pr\d+(@[0-9a-fA-F]{7,40})?|commit-[0-9a-fA-F]{7,40})(~|$)")
+        .unwrap()
+        .is_match(channel)
+}
+
+/// The staged CI build that a `commit-<sha>` or `pr<number>@<sha>` channel
+/// refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedBuildPin {
+    /// The pull request the commit was pushed to, if any. Commits that were
+    /// force-pushed over are no longer listed by GitHub, so a commit that is
+    /// not found in the pull request only causes a warning.
+    pub pr_number: Option<u64>,
+    /// A full or abbreviated commit sha.
+    pub sha: String,
+}
+
+/// Parses a `commit-<sha>` or `pr<number>@<sha>` channel, optionally followed
+/// by `~<arch>`. Returns `None` for other channels.
+pub fn parse_staged_build_pin(channel: &str) -> Option<StagedBuildPin> {
+    let caps = Regex::new(r"^(?:commit-|pr(\d+)@)([0-9a-fA-F]{7,40})(?:~|$)")
+        .unwrap()
+        .captures(channel)?;
+
+    Some(StagedBuildPin {
+        pr_number: caps.get(1).and_then(|number| number.as_str().parse().ok()),
+        sha: caps[2].to_lowercase(),
+    })
+}
+
+/// Whether `channel` is pinned to one specific build, so that it never
+/// receives updates.
+pub fn is_pinned_channel(channel: &str) -> bool {
+    is_pinned_nightly_channel(channel) || parse_staged_build_pin(channel).is_some()
 }
 
 fn parse_nightly_channel_or_id(channel: &str) -> Option<String> {
@@ -982,23 +1320,71 @@ fn parse_nightly_channel_or_id(channel: &str) -> Option<String> {
     }
 }
 
+/// The build of a nightly channel that a `nightly@<pin>` channel refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NightlyPin {
+    /// A full or abbreviated commit sha.
+    Commit(String),
+    /// The last build made on or before the end of this day (UTC).
+    Date(chrono::NaiveDate),
+}
+
+/// Parses the pin of a `nightly@<sha>` or `x.y-nightly@<yyyy-mm-dd>` channel,
+/// optionally followed by `~<arch>`. Returns `None` for other channels.
+pub fn parse_nightly_pin(channel: &str) -> Result<Option<NightlyPin>> {
+    let caps = match Regex::new(r"^(?:nightly|\d+\.\d+-nightly)@([^~]*)(?:~|$)")
+        .unwrap()
+        .captures(channel)
+    {
+        Some(caps) => caps,
+        None => return Ok(None),
+    };
+    let pin = &caps[1];
+
+    if Regex::new(r"^[0-9a-fA-F]{7,40}$").unwrap().is_match(pin) {
+        Ok(Some(NightlyPin::Commit(pin.to_lowercase())))
+    } else if let Ok(date) = chrono::NaiveDate::parse_from_str(pin, "%Y-%m-%d") {
+        Ok(Some(NightlyPin::Date(date)))
+    } else {
+        bail!(
+            "`{}` is neither a commit sha nor a date of the form YYYY-MM-DD.",
+            pin
+        )
+    }
+}
+
+/// Whether `channel` is a nightly channel pinned to a specific build, which
+/// never receives updates.
+pub fn is_pinned_nightly_channel(channel: &str) -> bool {
+    matches!(parse_nightly_pin(channel), Ok(Some(_)))
+}
+
 // Identify the unversioned name of a nightly (e.g., `latest-macos-x86_64`) for a channel
 pub fn channel_to_name(channel: &str) -> Result<String> {
-    let mut parts = channel.splitn(2, '~');
-
-    let channel = parts.next().expect("Failed to parse channel name.");
+    let channel_without_arch = channel
+        .split('~')
+        .next()
+        .expect("Failed to parse channel name.");
 
-    let version = if let Some(version_prefix) = parse_nightly_channel_or_id(channel) {
+    let version = if let Some(version_prefix) = parse_nightly_channel_or_id(channel_without_arch) {
         if version_prefix.is_empty() {
             "latest".to_string()
         } else {
             format!("{}-latest", version_prefix)
         }
     } else {
-        channel.to_string()
+        channel_without_arch.to_string()
     };
-    let arch = match parts.next() {
-        Some(arch) => arch.to_string(),
+
+    Ok(version + "-" + &channel_to_arch_suffix(channel)?)
+}
+
+/// The `<os>-<arch>` part of the name of a nightly or PR build (e.g.
+/// `macos-x86_64`) for the `~<arch>` suffix of a channel, or for the default
+/// arch if the channel has none.
+fn channel_to_arch_suffix(channel: &str) -> Result<String> {
+    let arch = match channel.split_once('~') {
+        Some((_, arch)) => arch.to_string(),
         None => default_arch()?,
     };
 
@@ -1040,10 +1426,10 @@ pub fn channel_to_name(channel: &str) -> Result<String> {
         }
     };
 
-    Ok(version.to_string() + "-" + os_arch_suffix)
+    Ok(os_arch_suffix.to_string())
 }
 
-fn query_julia_version(julia_path: &Path) -> Result<String> {
+pub fn query_julia_version(julia_path: &Path) -> Result<String> {
     let output = std::process::Command::new(julia_path)
         .arg("--startup-file=no")
         .arg("-e")
@@ -1078,10 +1464,7 @@ pub fn install_from_url(
     }
 
     // Download and extract into a temporary directory
-    let temp_dir = Builder::new()
-        .prefix("julia-temp-")
-        .tempdir_in(&paths.juliauphome)
-        .expect("Failed to create temporary directory");
+    let temp_dir = create_download_temp_dir(&paths.juliauphome)?;
 
     #[cfg(target_os = "macos")]
     let (server_etag, used_dmg) = try_download_dmg_with_fallback(url, temp_dir.path())?;
@@ -1093,6 +1476,7 @@ pub fn install_from_url(
             Ok(last_updated) => (last_updated, false),
             Err(e) => {
                 std::fs::remove_dir_all(temp_dir.path())?;
+                release_download_temp_dir(temp_dir.path());
                 bail!("Failed to download and extract pr or nightly: {}", e);
             }
         }
@@ -1134,7 +1518,9 @@ pub fn install_from_url(
 
     // keep() consumes the TempDir and returns the path without cleanup
     // For macOS DMG installs, this preserves the .app bundle structure
-    retry_rename(&temp_dir.keep(), &target_path)?;
+    let temp_path = temp_dir.keep();
+    retry_rename(&temp_path, &target_path)?;
+    release_download_temp_dir(&temp_path);
 
     let binary_path = compute_relative_binary_path(&target_path, path, &paths.juliauphome);
 
@@ -1160,7 +1546,7 @@ fn github_api_token() -> Option<String> {
 }
 
 /// The lifecycle state of a pull request, as reported by the GitHub API.
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum PrState {
     Open,
     Merged,
@@ -1233,18 +1619,15 @@ fn pr_title_from_api_response(body: &str) -> Result<String> {
     Ok(title.to_string())
 }
 
-/// Fetches the GitHub API response for a Julia pull request.
+/// Sends a GET request to the GitHub API and returns the response body.
+/// `not_found` is the error message for an HTTP 404 response.
 #[cfg(not(windows))]
-fn github_pr_api_response(pr_number: u64) -> Result<String> {
-    let url = format!(
-        "https://api.github.com/repos/JuliaLang/julia/pulls/{}",
-        pr_number
-    );
-
-    let mut request = http_client()?
-        .get(&url)
+fn github_api_get(url: &str, not_found: &str) -> Result<String> {
+    let mut request = apply_credentials(http_client()?.get(url), url)?
         .header("Accept", "application/vnd.github+json");
-    if let Some(token) = github_api_token() {
+    // Configured credentials for the GitHub API win over the token, a request
+    // with two `Authorization` headers is rejected.
+    if let Some(token) = github_api_token().filter(|_| !sends_authorization(url)) {
         request = request.header("Authorization", format!("Bearer {}", token));
     }
 
@@ -1253,10 +1636,7 @@ fn github_pr_api_response(pr_number: u64) -> Result<String> {
         .with_context(|| format!("Failed to query the GitHub API at `{}`.", url))?;
 
     match response.status().as_u16() {
-        404 => bail!(
-            "https://github.com/JuliaLang/julia/pull/{} does not exist.",
-            pr_number
-        ),
+        404 => bail!("{}", not_found),
         403 | 429 => bail!(
             "The GitHub API request to `{}` was rejected (HTTP {}), most likely due to rate \
              limiting. Set the GITHUB_TOKEN (or GH_TOKEN) environment variable to make an \
@@ -1275,20 +1655,16 @@ fn github_pr_api_response(pr_number: u64) -> Result<String> {
     Ok(response.text()?)
 }
 
-/// Fetches the GitHub API response for a Julia pull request.
+/// Sends a GET request to the GitHub API and returns the response body.
+/// `not_found` is the error message for an HTTP 404 response.
 #[cfg(windows)]
-fn github_pr_api_response(pr_number: u64) -> Result<String> {
+fn github_api_get(url: &str, not_found: &str) -> Result<String> {
     use windows::core::HSTRING;
     use windows::Foundation::Uri;
     use windows::Web::Http::HttpMethod;
     use windows::Web::Http::HttpRequestMessage;
 
-    let url = format!(
-        "https://api.github.com/repos/JuliaLang/julia/pulls/{}",
-        pr_number
-    );
-
-    let request_uri = Uri::CreateUri(&HSTRING::from(&url))
+    let request_uri = Uri::CreateUri(&HSTRING::from(url))
         .with_context(|| format!("Failed to create URI from {}", url))?;
 
     let request = HttpRequestMessage::Create(&HttpMethod::Get()?, &request_uri)
@@ -1297,13 +1673,16 @@ fn github_pr_api_response(pr_number: u64) -> Result<String> {
     let headers = request
         .Headers()
         .with_context(|| "Failed to get request headers.")?;
+    apply_credentials(&headers, url)?;
     headers
         .TryAppendWithoutValidation(
             &HSTRING::from("Accept"),
             &HSTRING::from("application/vnd.github+json"),
         )
         .with_context(|| "Failed to set the Accept header.")?;
-    if let Some(token) = github_api_token() {
+    // Configured credentials for the GitHub API win over the token, a request
+    // with two `Authorization` headers is rejected.
+    if let Some(token) = github_api_token().filter(|_| !sends_authorization(url)) {
         headers
             .TryAppendWithoutValidation(
                 &HSTRING::from("Authorization"),
@@ -1319,10 +1698,7 @@ fn github_pr_api_response(pr_number: u64) -> Result<String> {
         .with_context(|| format!("Failed to query the GitHub API at `{}`.", url))?;
 
     match response.StatusCode().map(|status| status.0) {
-        Ok(404) => bail!(
-            "https://github.com/JuliaLang/julia/pull/{} does not exist.",
-            pr_number
-        ),
+        Ok(404) => bail!("{}", not_found),
         Ok(status @ (403 | 429)) => bail!(
             "The GitHub API request to `{}` was rejected (HTTP {}), most likely due to rate \
              limiting. Set the GITHUB_TOKEN (or GH_TOKEN) environment variable to make an \
@@ -1350,6 +1726,20 @@ fn github_pr_api_response(pr_number: u64) -> Result<String> {
     Ok(body)
 }
 
+/// Fetches the GitHub API response for a Julia pull request.
+fn github_pr_api_response(pr_number: u64) -> Result<String> {
+    github_api_get(
+        &format!(
+            "https://api.github.com/repos/JuliaLang/julia/pulls/{}",
+            pr_number
+        ),
+        &format!(
+            "https://github.com/JuliaLang/julia/pull/{} does not exist.",
+            pr_number
+        ),
+    )
+}
+
 /// Resolves a Julia pull request number to the current head commit sha of the
 /// PR branch and its lifecycle state via the GitHub API.
 fn resolve_pr_info(pr_number: u64) -> Result<PrInfo> {
@@ -1366,7 +1756,7 @@ pub fn get_julia_pr_title(pr_number: u64) -> Result<String> {
 /// alternative locations.
 #[cfg(not(windows))]
 fn url_exists(url: &str) -> Result<bool> {
-    match http_client()?.head(url).send() {
+    match apply_credentials(http_client()?.head(url), url)?.send() {
         Ok(response) => Ok(response.status().is_success()),
         Err(e) => {
             log::debug!("HEAD request to `{}` failed: {}", url, e);
@@ -1390,6 +1780,7 @@ fn url_exists(url: &str) -> Result<bool> {
 
     let request = HttpRequestMessage::Create(&HttpMethod::Head()?, &request_uri)
         .with_context(|| "Failed to create HttpRequestMessage.")?;
+    apply_credentials(&request.Headers()?, url)?;
 
     match http_client()?
         .SendRequestAsync(&request)
@@ -1403,10 +1794,51 @@ fn url_exists(url: &str) -> Result<bool> {
     }
 }
 
-/// Number of leading characters of the head commit sha used in the file names
-/// of staged PR builds (`SHORT_COMMIT_LENGTH` in julia-buildkite's
-/// `utilities/build_envs.sh`).
-const PR_SHORT_SHA_LENGTH: usize = 10;
+/// Whether the server behind `url` answers an HTTP HEAD request at all, with
+/// any status code.
+#[cfg(not(windows))]
+pub fn server_responds(url: &str) -> Result<bool> {
+    match apply_credentials(http_client()?.head(url), url)?.send() {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            log::debug!("HEAD request to `{}` failed: {}", url, e);
+            Ok(false)
+        }
+    }
+}
+
+/// Whether the server behind `url` answers an HTTP HEAD request at all, with
+/// any status code.
+#[cfg(windows)]
+pub fn server_responds(url: &str) -> Result<bool> {
+    use windows::core::HSTRING;
+    use windows::Foundation::Uri;
+    use windows::Web::Http::HttpMethod;
+    use windows::Web::Http::HttpRequestMessage;
+
+    let request_uri = Uri::CreateUri(&HSTRING::from(url))
+        .with_context(|| format!("Failed to create URI from {}", url))?;
+
+    let request = HttpRequestMessage::Create(&HttpMethod::Head()?, &request_uri)
+        .with_context(|| "Failed to create HttpRequestMessage.")?;
+    apply_credentials(&request.Headers()?, url)?;
+
+    match http_client()?
+        .SendRequestAsync(&request)
+        .and_then(|async_op| async_op.join())
+    {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            log::debug!("HEAD request to `{}` failed: {:?}", url, e);
+            Ok(false)
+        }
+    }
+}
+
+/// Number of leading characters of the commit sha used in the file names of
+/// staged PR builds and per-commit nightlies (`SHORT_COMMIT_LENGTH` in
+/// julia-buildkite's `utilities/build_envs.sh`).
+const SHORT_SHA_LENGTH: usize = 10;
 
 /// Relative path of a PR build staged in the ephemeral PR bucket:
 /// `bin/<full head sha>/julia-<short sha>-<os>-<arch>.tar.gz`.
@@ -1417,16 +1849,17 @@ fn pr_staging_url_path(head_sha: &str, os_arch: &str) -> Result<String> {
     Ok(format!(
         "bin/{}/julia-{}-{}.tar.gz",
         head_sha,
-        &head_sha[..PR_SHORT_SHA_LENGTH],
+        &head_sha[..SHORT_SHA_LENGTH],
         os_arch
     ))
 }
 
 /// Maps a juliaup arch identifier (as produced by `channel_to_name`) to the
 /// `<os>-<arch>` suffix that julia-buildkite uses for the file names of
-/// staged PR builds (the `UPLOAD_FILENAME` vocabulary of its
-/// `utilities/build_envs.sh` and `utilities/extract_triplet.sh`).
-fn pr_staging_os_arch(arch: &str) -> Result<&'static str> {
+/// staged PR builds and per-commit nightlies (the `UPLOAD_FILENAME`
+/// vocabulary of its `utilities/build_envs.sh` and
+/// `utilities/extract_triplet.sh`).
+fn buildkite_os_arch(arch: &str) -> Result<&'static str> {
     match arch {
         "macos-x86_64" => Ok("macos-x86_64"),
         "macos-aarch64" => Ok("macos-aarch64"),
@@ -1436,7 +1869,7 @@ fn pr_staging_os_arch(arch: &str) -> Result<&'static str> {
         "linux-i686" => Ok("linux-i686"),
         "linux-aarch64" => Ok("linux-aarch64"),
         "freebsd-x86_64" => Ok("freebsd-x86_64"),
-        _ => Err(anyhow!("Unknown pr.")),
+        _ => Err(anyhow!("Unknown platform `{}`.", arch)),
     }
 }
 
@@ -1483,7 +1916,7 @@ fn resolve_pr_download_url(id: &str, arch: &str) -> Result<(Url, Option<PrState>
         .unwrap_or(id)
         .parse()
         .with_context(|| format!("Failed to parse a pull request number from `{}`.", id))?;
-    let os_arch = pr_staging_os_arch(arch)?;
+    let os_arch = buildkite_os_arch(arch)?;
 
     // The GitHub API lookup can fail without dooming the install (e.g.
     // anonymous requests are rate-limited), so remember the error and try
@@ -1542,14 +1975,257 @@ fn resolve_pr_download_url(id: &str, arch: &str) -> Result<(Url, Option<PrState>
     })
 }
 
-/// Installs a non-database version (nightly/PR) of Julia.
-/// Returns the config channel and a bool indicating whether a DMG installer was used (macOS only).
-pub fn install_non_db_version(
-    channel: &str,
-    name: &String,
-    paths: &GlobalPaths,
-) -> Result<(crate::config_file::JuliaupConfigChannel, bool)> {
-    // Check if the nightly server supports etag headers (required for nightly/PR channels)
+/// The folder on the nightlies server that holds the builds for a juliaup
+/// arch identifier (as produced by `channel_to_name`).
+fn nightly_platform_dir(arch: &str) -> Result<&'static str> {
+    match arch {
+        "macos-x86_64" => Ok("bin/macos/x86_64"),
+        "macos-aarch64" => Ok("bin/macos/aarch64"),
+        "win64" => Ok("bin/winnt/x64"),
+        "win32" => Ok("bin/winnt/x86"),
+        "linux-x86_64" => Ok("bin/linux/x86_64"),
+        "linux-i686" => Ok("bin/linux/i686"),
+        "linux-aarch64" => Ok("bin/linux/aarch64"),
+        "freebsd-x86_64" => Ok("bin/freebsd/x86_64"),
+        _ => Err(anyhow!("Unknown nightly.")),
+    }
+}
+
+/// Relative path of the nightly build of a single commit:
+/// `<platform dir>/<major.minor>/julia-<short sha>-<os>-<arch>.tar.gz`.
+fn nightly_commit_url_path(major_minor: &str, sha: &str, arch: &str) -> Result<String> {
+    if sha.len() != 40 || !sha.bytes().all(|b| b.is_ascii_hexdigit()) {
+        bail!("`{}` is not a valid full commit sha.", sha);
+    }
+    Ok(format!(
+        "{}/{}/julia-{}-{}.tar.gz",
+        nightly_platform_dir(arch)?,
+        major_minor,
+        &sha[..SHORT_SHA_LENGTH],
+        buildkite_os_arch(arch)?
+    ))
+}
+
+/// Extracts the commit shas from a GitHub API response that is either a
+/// single commit or a list of commits.
+fn commit_shas_from_api_response(body: &str) -> Result<Vec<String>> {
+    let parsed: serde_json::Value = serde_json::from_str(body)
+        .with_context(|| "Failed to parse the GitHub API response as JSON.")?;
+    let commits = match parsed.as_array() {
+        Some(commits) => commits.iter().collect::<Vec<_>>(),
+        None => vec![&parsed],
+    };
+
+    commits
+        .into_iter()
+        .map(|commit| {
+            commit
+                .get("sha")
+                .and_then(|sha| sha.as_str())
+                .map(str::to_lowercase)
+                .ok_or_else(|| anyhow!("The GitHub API response did not contain a commit sha."))
+        })
+        .collect()
+}
+
+/// Whether the full commit `sha` is the head of pull request `pr_number` or
+/// one of its commits. GitHub lists at most 250 commits of a pull request,
+/// and none that were force-pushed over.
+fn pr_contains_commit(pr_number: u64, sha: &str) -> Result<bool> {
+    if resolve_pr_info(pr_number)?.head_sha == sha {
+        return Ok(true);
+    }
+
+    const PER_PAGE: usize = 100;
+    for page in 1..=3 {
+        let commits = commit_shas_from_api_response(&github_api_get(
+            &format!(
+                "https://api.github.com/repos/JuliaLang/julia/pulls/{}/commits?per_page={}&page={}",
+                pr_number, PER_PAGE, page
+            ),
+            &format!(
+                "https://github.com/JuliaLang/julia/pull/{} does not exist.",
+                pr_number
+            ),
+        )?)?;
+        if commits.iter().any(|commit| commit == sha) {
+            return Ok(true);
+        }
+        if commits.len() < PER_PAGE {
+            break;
+        }
+    }
+
+    Ok(false)
+}
+
+/// The `major.minor` part of the contents of Julia's `VERSION` file.
+fn major_minor_from_version_file(content: &str) -> Result<String> {
+    let version = Version::parse(content.trim()).with_context(|| {
+        format!(
+            "Failed to parse `{}` as a Julia version number.",
+            content.trim()
+        )
+    })?;
+    Ok(format!("{}.{}", version.major, version.minor))
+}
+
+/// How many commits before a pinned date are tried, because not every commit
+/// gets a nightly build.
+const PINNED_NIGHTLY_DATE_CANDIDATES: usize = 10;
+
+/// Determines the download URL of the nightly build a `nightly@<pin>` or
+/// `x.y-nightly@<pin>` channel refers to. `nightly_version` is the `x.y` of
+/// the channel, or empty for `nightly`.
+///
+/// CI uploads every nightly build a second time under the short sha of its
+/// commit, into a folder named after the `major.minor` version of that
+/// commit. The pin is resolved to commits via the GitHub API, and for dates
+/// the most recent commit of that day that has a build wins.
+fn resolve_pinned_nightly_url(nightly_version: &str, pin: &NightlyPin, arch: &str) -> Result<Url> {
+    let branch = if nightly_version.is_empty() {
+        "master".to_string()
+    } else {
+        format!("release-{}", nightly_version)
+    };
+
+    let candidates = match pin {
+        NightlyPin::Commit(sha) => commit_shas_from_api_response(&github_api_get(
+            &format!("https://api.github.com/repos/JuliaLang/julia/commits/{}", sha),
+            &format!(
+                "`{}` is not a commit of https://github.com/JuliaLang/julia.",
+                sha
+            ),
+        )?)?,
+        NightlyPin::Date(date) => commit_shas_from_api_response(&github_api_get(
+            &format!(
+                "https://api.github.com/repos/JuliaLang/julia/commits?sha={}&until={}T23:59:59Z&per_page={}",
+                branch, date, PINNED_NIGHTLY_DATE_CANDIDATES
+            ),
+            &format!(
+                "The branch `{}` does not exist in https://github.com/JuliaLang/julia.",
+                branch
+            ),
+        )?)?,
+    };
+
+    let first_candidate = candidates
+        .first()
+        .ok_or_else(|| anyhow!("There are no commits on `{}` before that date.", branch))?;
+
+    // All candidates are close together in history, so they share the
+    // version of the first one, except right at a version bump.
+    let major_minor = if nightly_version.is_empty() {
+        major_minor_from_version_file(&download_text(&format!(
+            "https://raw.githubusercontent.com/JuliaLang/julia/{}/VERSION",
+            first_candidate
+        ))?)?
+    } else {
+        nightly_version.to_string()
+    };
+
+    let base_url = get_julianightlies_base_url()?;
+    for sha in &candidates {
+        let path = nightly_commit_url_path(&major_minor, sha, arch)?;
+        let url = base_url.join(&path).with_context(|| {
+            format!(
+                "Failed to construct a valid url from '{}' and '{}'.",
+                base_url, path
+            )
+        })?;
+        if url_exists(url.as_str())? {
+            return Ok(url);
+        }
+    }
+
+    bail!(
+        "No nightly build of {} is available for `{}`. Not every commit gets a nightly build, \
+         and old nightly builds are eventually deleted from the server.",
+        match pin {
+            NightlyPin::Commit(sha) => format!("commit {}", sha),
+            NightlyPin::Date(date) => format!("`{}` on {}", branch, date),
+        },
+        arch
+    )
+}
+
+/// Determines the download URL of the staged CI build a `commit-<sha>` or
+/// `pr<number>@<sha>` channel refers to.
+///
+/// Staged builds are keyed by the full commit sha, so an abbreviated sha is
+/// expanded via the GitHub API first. For `pr<number>@<sha>` the commit is
+/// checked against that pull request, which catches typos in either part.
+fn resolve_staged_build_url(pin: &StagedBuildPin, arch: &str) -> Result<Url> {
+    let os_arch = buildkite_os_arch(arch)?;
+
+    let sha = if pin.sha.len() == 40 {
+        pin.sha.clone()
+    } else {
+        commit_shas_from_api_response(&github_api_get(
+            &format!(
+                "https://api.github.com/repos/JuliaLang/julia/commits/{}",
+                pin.sha
+            ),
+            &format!(
+                "`{}` is not a commit of https://github.com/JuliaLang/julia.",
+                pin.sha
+            ),
+        )?)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("The GitHub API did not return a commit for `{}`.", pin.sha))?
+    };
+
+    if let Some(pr_number) = pin.pr_number {
+        let problem = match pr_contains_commit(pr_number, &sha) {
+            Ok(true) => None,
+            Ok(false) => Some(
+                "is not one of its current commits. It may have been force-pushed over."
+                    .to_string(),
+            ),
+            Err(e) => Some(format!("could not be checked: {:#}", e)),
+        };
+        if let Some(problem) = problem {
+            print_juliaup_style(
+                "Warning",
+                &format!(
+                    "Commit {} of https://github.com/JuliaLang/julia/pull/{} {}",
+                    sha, pr_number, problem
+                ),
+                JuliaupMessageType::Warning,
+            );
+        }
+    }
+
+    let base_url = get_juliaprs_base_url()?;
+    let path = pr_staging_url_path(&sha, os_arch)?;
+    let url = base_url.join(&path).with_context(|| {
+        format!(
+            "Failed to construct a valid url from '{}' and '{}'.",
+            base_url, path
+        )
+    })?;
+
+    if !url_exists(url.as_str())? {
+        bail!(
+            "No CI build of commit {} is currently available for `{}`. CI only stages builds \
+             of commits it has built, and they expire about 90 days later.",
+            sha,
+            arch
+        );
+    }
+
+    Ok(url)
+}
+
+/// Installs a non-database version (nightly/PR) of Julia.
+/// Returns the config channel and a bool indicating whether a DMG installer was used (macOS only).
+pub fn install_non_db_version(
+    channel: &str,
+    name: &String,
+    paths: &GlobalPaths,
+) -> Result<(crate::config_file::JuliaupConfigChannel, bool)> {
+    // Check if the nightly server supports etag headers (required for nightly/PR channels)
     if !check_server_supports_nightlies()
         .context("Failed to check if nightly server supports etag headers")?
     {
@@ -1579,48 +2255,23 @@ pub fn install_non_db_version(
     }
 
     let nightly_version = parse_nightly_channel_or_id(&id);
+    let nightly_pin = parse_nightly_pin(channel)?;
 
-    let download_url = if let Some(nightly_version) = nightly_version {
+    let download_url = if let (Some(nightly_version), Some(pin)) = (&nightly_version, &nightly_pin)
+    {
+        resolve_pinned_nightly_url(nightly_version, pin, arch)?
+    } else if let Some(nightly_version) = nightly_version {
         let nightly_folder = if nightly_version.is_empty() {
             "".to_string() // No version folder
         } else {
             format!("/{}", nightly_version) // Use version as folder
         };
-        let download_url_path = match arch {
-            "macos-x86_64" => Ok(format!(
-                "bin/macos/x86_64{}/julia-latest-macos-x86_64.tar.gz",
-                nightly_folder
-            )),
-            "macos-aarch64" => Ok(format!(
-                "bin/macos/aarch64{}/julia-latest-macos-aarch64.tar.gz",
-                nightly_folder
-            )),
-            "win64" => Ok(format!(
-                "bin/winnt/x64{}/julia-latest-win64.tar.gz",
-                nightly_folder
-            )),
-            "win32" => Ok(format!(
-                "bin/winnt/x86{}/julia-latest-win32.tar.gz",
-                nightly_folder
-            )),
-            "linux-x86_64" => Ok(format!(
-                "bin/linux/x86_64{}/julia-latest-linux-x86_64.tar.gz",
-                nightly_folder
-            )),
-            "linux-i686" => Ok(format!(
-                "bin/linux/i686{}/julia-latest-linux-i686.tar.gz",
-                nightly_folder
-            )),
-            "linux-aarch64" => Ok(format!(
-                "bin/linux/aarch64{}/julia-latest-linux-aarch64.tar.gz",
-                nightly_folder
-            )),
-            "freebsd-x86_64" => Ok(format!(
-                "bin/freebsd/x86_64{}/julia-latest-freebsd-x86_64.tar.gz",
-                nightly_folder
-            )),
-            _ => Err(anyhow!("Unknown nightly.")),
-        }?;
+        let download_url_path = format!(
+            "{}{}/julia-latest-{}.tar.gz",
+            nightly_platform_dir(arch)?,
+            nightly_folder,
+            arch
+        );
 
         let download_url_base = get_julianightlies_base_url()?;
         download_url_base
@@ -1631,6 +2282,8 @@ pub fn install_non_db_version(
                     download_url_base, download_url_path
                 )
             })?
+    } else if let Some(pin) = parse_staged_build_pin(channel) {
+        resolve_staged_build_url(&pin, &channel_to_arch_suffix(channel)?)?
     } else if id.starts_with("pr") {
         resolve_pr_download_url(&id, arch)?.0
     } else {
@@ -1645,7 +2298,10 @@ pub fn install_non_db_version(
 
     print_juliaup_style(
         "Installing",
-        &format!("Julia {}", name),
+        &match nightly_pin {
+            Some(_) => format!("Julia {} ({})", name, download_url),
+            None => format!("Julia {}", name),
+        },
         JuliaupMessageType::Progress,
     );
 
@@ -1694,6 +2350,8 @@ pub fn garbage_collect_versions(
         }
     }
 
+    remove_leftover_temp_dirs(&paths.juliauphome);
+
     if versions_to_uninstall.is_empty() {
         print_juliaup_style(
             "Tidyup",
@@ -1725,14 +2383,108 @@ pub fn garbage_collect_versions(
             }
         }
         for channel in channels_to_uninstall {
+            remove_channel_shim(&channel)?;
             remove_symlink(&format!("julia-{}", channel))?;
             config_data.installed_channels.remove(&channel);
         }
     }
 
+    config_data
+        .finished_pr_channels
+        .retain(|channel, _| config_data.installed_channels.contains_key(channel));
+
     Ok(())
 }
 
+/// Removes PR channels whose pull request was merged or closed, based on the
+/// state recorded during the last update check, together with their install
+/// directory, shim and symlink. Channels that are still referenced as the
+/// default, by an override or by an alias are kept. Returns the removed
+/// channels.
+pub fn prune_finished_pr_channels(
+    config_data: &mut JuliaupConfig,
+    paths: &GlobalPaths,
+) -> Result<Vec<String>> {
+    let mut finished: Vec<(String, PrState)> = config_data
+        .finished_pr_channels
+        .iter()
+        .map(|(channel, state)| (channel.clone(), *state))
+        .collect();
+    finished.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut removed = Vec::new();
+
+    for (channel, state) in finished {
+        let path = match config_data.installed_channels.get(&channel) {
+            Some(JuliaupConfigChannel::DirectDownloadChannel { path, .. }) => path.clone(),
+            _ => continue,
+        };
+        let description = state.description().unwrap_or("finished");
+
+        let in_use = if config_data.default.as_deref() == Some(channel.as_str()) {
+            Some("it is the default channel")
+        } else if config_data.overrides.iter().any(|i| i.channel == channel) {
+            Some("it is used in a directory override")
+        } else if config_data.installed_channels.values().any(|i| {
+            matches!(i, JuliaupConfigChannel::AliasChannel { target, .. } if *target == channel)
+        }) {
+            Some("an alias points to it")
+        } else {
+            None
+        };
+
+        if let Some(reason) = in_use {
+            print_juliaup_style(
+                "Skipping",
+                &format!("{} was {}, but {}.", channel, description, reason),
+                JuliaupMessageType::Warning,
+            );
+            continue;
+        }
+
+        let path_to_delete = paths.juliauphome.join(&path);
+        if let Err(e) = std::fs::remove_dir_all(&path_to_delete) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                print_juliaup_style(
+                    "WARNING",
+                    &format!(
+                        "Failed to delete {}: {}. You can try to delete at a later point by running `juliaup gc`.",
+                        path_to_delete.display(),
+                        e
+                    ),
+                    JuliaupMessageType::Warning,
+                );
+            }
+        }
+
+        config_data.installed_channels.remove(&channel);
+        config_data.finished_pr_channels.remove(&channel);
+        remove_channel_shim(&channel)?;
+        remove_symlink(&format!("julia-{}", channel))?;
+
+        print_juliaup_style(
+            "Tidyup",
+            &format!(
+                "Removed channel {}, its pull request was {}",
+                channel, description
+            ),
+            JuliaupMessageType::Success,
+        );
+
+        removed.push(channel);
+    }
+
+    if removed.is_empty() {
+        print_juliaup_style(
+            "Tidyup",
+            "No finished PR channels to remove.",
+            JuliaupMessageType::Success,
+        );
+    }
+
+    Ok(removed)
+}
+
 fn _remove_symlink(symlink_path: &Path) -> Result<Option<PathBuf>> {
     let parent = symlink_path.parent().ok_or_else(|| {
         anyhow!(
@@ -1920,62 +2672,672 @@ fn create_linked_channel_shim(
     })
 }
 
-#[cfg(not(windows))]
-pub fn create_symlink(
-    channel: &JuliaupConfigChannel,
-    symlink_name: &String,
-    paths: &GlobalPaths,
-) -> Result<()> {
-    let symlink_folder = get_bin_dir()
-        .with_context(|| "Failed to retrieve binary directory while trying to create a symlink.")?;
+#[cfg(not(windows))]
+pub fn create_symlink(
+    channel: &JuliaupConfigChannel,
+    symlink_name: &String,
+    paths: &GlobalPaths,
+) -> Result<()> {
+    let symlink_folder = get_bin_dir()
+        .with_context(|| "Failed to retrieve binary directory while trying to create a symlink.")?;
+
+    let symlink_path = symlink_folder.join(symlink_name);
+
+    // Channel shims never go stale, so they take precedence over symlinks.
+    if is_channel_shim(&symlink_path) {
+        return Ok(());
+    }
+
+    let updating = _remove_symlink(&symlink_path)?;
+
+    match channel {
+        JuliaupConfigChannel::SystemChannel { version } => {
+            create_system_channel_symlink(version, symlink_name, &symlink_path, paths, &updating)
+        }
+        JuliaupConfigChannel::DirectDownloadChannel { path, version, .. } => {
+            create_direct_download_symlink(
+                path,
+                version,
+                symlink_name,
+                &symlink_path,
+                paths,
+                &updating,
+            )
+        }
+        JuliaupConfigChannel::LinkedChannel { command, args } => {
+            create_linked_channel_shim(command, args, symlink_name, &symlink_path, &updating)
+        }
+        JuliaupConfigChannel::AliasChannel { .. } => Ok(()), // Aliases have their symlinks resolved at runtime
+    }?;
+
+    if updating.is_none() {
+        if let Ok(path) = std::env::var("PATH") {
+            if !path.split(':').any(|p| Path::new(p) == symlink_folder) {
+                eprintln!(
+                "Symlink {} added in {}. Add this directory to the system PATH to make the command available in your shell.",
+                symlink_name, symlink_folder.display(),
+            );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+pub fn create_symlink(_: &JuliaupConfigChannel, _: &String, _paths: &GlobalPaths) -> Result<()> {
+    Ok(())
+}
+
+/// Marks the `julia-<channel>` launcher shims, so that they can be told apart
+/// from channel symlinks and user files with the same name.
+const CHANNEL_SHIM_MARKER: &str = "juliaup channel shim";
+
+fn channel_shim_path(bin_dir: &Path, channel_name: &str) -> PathBuf {
+    if cfg!(windows) {
+        bin_dir.join(format!("julia-{}.cmd", channel_name))
+    } else {
+        bin_dir.join(format!("julia-{}", channel_name))
+    }
+}
+
+fn is_channel_shim(path: &Path) -> bool {
+    if !path
+        .symlink_metadata()
+        .is_ok_and(|metadata| metadata.file_type().is_file())
+    {
+        return false;
+    }
+
+    let mut head = [0u8; 128];
+    let len = std::fs::File::open(path)
+        .and_then(|mut file| file.read(&mut head))
+        .unwrap_or(0);
+
+    head[..len].find(CHANNEL_SHIM_MARKER).is_some()
+}
+
+/// Whether `path` is a command that juliaup created for a channel: a channel
+/// shim, or a channel symlink into `juliauphome`. Anything else with the same
+/// name belongs to the user.
+fn is_juliaup_channel_command(path: &Path, juliauphome: &Path) -> bool {
+    is_channel_shim(path)
+        || std::fs::read_link(path).is_ok_and(|target| target.starts_with(juliauphome))
+}
+
+/// The Julia launcher that channel shims run.
+fn launcher_path_for_shims() -> Result<PathBuf> {
+    // The MSIX package can't be run from its install location, only through
+    // its app execution alias.
+    #[cfg(windows)]
+    if let Some(local_app_data) = dirs::data_local_dir() {
+        let alias = local_app_data
+            .join("Microsoft")
+            .join("WindowsApps")
+            .join("julia.exe");
+        if alias.exists() {
+            return Ok(alias);
+        }
+    }
+
+    julia_launcher_binary()
+}
+
+/// The Julia launcher binary that was installed next to the running juliaup.
+pub fn julia_launcher_binary() -> Result<PathBuf> {
+    let exe_dir = std::env::current_exe()
+        .with_context(|| "Could not determine the path of the running exe.")?
+        .parent()
+        .ok_or_else(|| anyhow!("Could not determine parent."))?
+        .to_path_buf();
+
+    let launcher = exe_dir.join(format!("julialauncher{}", std::env::consts::EXE_SUFFIX));
+    if launcher.exists() {
+        return Ok(launcher);
+    }
+
+    // Some distributions only ship the launcher as `julia`.
+    Ok(exe_dir.join(format!("julia{}", std::env::consts::EXE_SUFFIX)))
+}
+
+fn channel_shim_content(launcher: &str, channel_name: &str) -> String {
+    if cfg!(windows) {
+        format!(
+            "@rem {}\r\n@\"{}\" +{} %*\r\n",
+            CHANNEL_SHIM_MARKER, launcher, channel_name
+        )
+    } else {
+        format!(
+            "#!/bin/sh\n# {}\nexec '{}' '+{}' \"$@\"\n",
+            CHANNEL_SHIM_MARKER,
+            launcher.replace('\'', r"'\''"),
+            channel_name.replace('\'', r"'\''")
+        )
+    }
+}
+
+/// Creates a `julia-<channel>` shim that starts the Julia launcher with
+/// `+<channel>`. Unlike a channel symlink it never needs to be updated, and
+/// the launcher applies alias arguments and auto-installation as usual.
+pub fn create_channel_shim(channel_name: &str) -> Result<()> {
+    let bin_dir = get_bin_dir()
+        .with_context(|| "Failed to retrieve binary directory while trying to create a shim.")?;
+
+    std::fs::create_dir_all(&bin_dir).with_context(|| {
+        format!(
+            "Failed to create the binary directory `{}`.",
+            bin_dir.display()
+        )
+    })?;
+
+    let shim_path = channel_shim_path(&bin_dir, channel_name);
+    let shim_name = shim_path.file_name().unwrap().to_string_lossy().to_string();
+
+    let launcher = launcher_path_for_shims()?;
+    let launcher = launcher.to_str().ok_or_else(|| {
+        anyhow!(
+            "Only valid UTF-8 paths are supported for the Julia launcher `{}`.",
+            launcher.display()
+        )
+    })?;
+
+    // Replaces a channel symlink of the same name, too.
+    let updating = shim_path.symlink_metadata().is_ok();
+    if updating {
+        let juliauphome = crate::global_paths::get_paths()?.juliauphome;
+        if !is_juliaup_channel_command(&shim_path, &juliauphome) {
+            print_juliaup_style(
+                "WARNING",
+                &format!(
+                    "Not creating shim {} for channel {}, `{}` already exists and was not created by Juliaup.",
+                    shim_name,
+                    channel_name,
+                    shim_path.display()
+                ),
+                JuliaupMessageType::Warning,
+            );
+            return Ok(());
+        }
+
+        std::fs::remove_file(&shim_path)
+            .with_context(|| format!("Failed to remove `{}`.", shim_path.display()))?;
+    }
+
+    print_juliaup_style(
+        if updating { "Updating" } else { "Creating" },
+        &format!("shim {} for channel {}", shim_name, channel_name),
+        JuliaupMessageType::Progress,
+    );
+
+    std::fs::write(&shim_path, channel_shim_content(launcher, channel_name))
+        .with_context(|| format!("failed to create shim `{}`.", shim_path.display()))?;
+
+    #[cfg(not(windows))]
+    std::fs::set_permissions(&shim_path, std::fs::Permissions::from_mode(0o755)).with_context(
+        || {
+            format!(
+                "failed to change permissions for shim `{}`.",
+                shim_path.display()
+            )
+        },
+    )?;
+
+    if !updating {
+        if let Some(path) = std::env::var_os("PATH") {
+            if !std::env::split_paths(&path).any(|p| p == bin_dir) {
+                eprintln!(
+                    "Shim {} added in {}. Add this directory to the system PATH to make the command available in your shell.",
+                    shim_name,
+                    bin_dir.display(),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the `julia-<channel>` shim, if there is one.
+pub fn remove_channel_shim(channel_name: &str) -> Result<()> {
+    let bin_dir = get_bin_dir()
+        .with_context(|| "Failed to retrieve binary directory while trying to remove a shim.")?;
+
+    let shim_path = channel_shim_path(&bin_dir, channel_name);
+
+    if is_channel_shim(&shim_path) {
+        print_juliaup_style(
+            "Deleting",
+            &format!("shim {}.", shim_path.file_name().unwrap().to_string_lossy()),
+            JuliaupMessageType::Progress,
+        );
+
+        std::fs::remove_file(&shim_path)
+            .with_context(|| format!("Failed to remove shim `{}`.", shim_path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// The directory next to the juliaup binaries that holds the binaries
+/// replaced by the last self-update, for `juliaup self rollback`.
+pub fn previous_binaries_dir(install_dir: &Path) -> Result<PathBuf> {
+    let mut name = install_dir
+        .file_name()
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not determine the name of `{}`.",
+                install_dir.display()
+            )
+        })?
+        .to_os_string();
+    name.push(".previous");
+
+    Ok(install_dir.with_file_name(name))
+}
+
+/// Lists, inside a backup of replaced binaries, the files that the swap added
+/// without replacing anything. Swapping the backup back in removes them.
+const ADDED_BINARIES_FILE: &str = ".added";
+
+/// Moves every entry of `source_dir` into `install_dir` and the entries it
+/// replaces into `backup_dir`. Files that `source_dir` lists as added by an
+/// earlier swap are moved into `backup_dir` as well, so swapping a backup back
+/// in leaves exactly the files it had. Each entry is swapped with a rename; if
+/// any step fails, everything that was already moved is put back.
+pub fn swap_in_binaries(source_dir: &Path, install_dir: &Path, backup_dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(backup_dir)
+        .with_context(|| format!("Failed to create directory `{}`.", backup_dir.display()))?;
+
+    let names = std::fs::read_dir(source_dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|e| e.file_name()))
+                .filter(|name| !matches!(name, Ok(name) if name == ADDED_BINARIES_FILE))
+                .collect::<std::io::Result<Vec<_>>>()
+        })
+        .with_context(|| format!("Failed to read directory `{}`.", source_dir.display()))?;
+
+    let added_by_earlier_swap: Vec<std::ffi::OsString> =
+        std::fs::read_to_string(source_dir.join(ADDED_BINARIES_FILE))
+            .map(|added| added.lines().map(Into::into).collect())
+            .unwrap_or_default();
+
+    let mut backed_up = Vec::new();
+    let mut installed = Vec::new();
+    let mut added = Vec::new();
+
+    let result = (|| -> Result<()> {
+        for name in &added_by_earlier_swap {
+            let current = install_dir.join(name);
+
+            if !names.contains(name) && current.symlink_metadata().is_ok() {
+                std::fs::rename(&current, backup_dir.join(name)).with_context(|| {
+                    format!("Failed to move `{}` out of the way.", current.display())
+                })?;
+                backed_up.push(name);
+            }
+        }
+
+        for name in &names {
+            let current = install_dir.join(name);
+
+            if current.symlink_metadata().is_ok() {
+                std::fs::rename(&current, backup_dir.join(name)).with_context(|| {
+                    format!("Failed to move `{}` out of the way.", current.display())
+                })?;
+                backed_up.push(name);
+            } else {
+                added.push(name.to_string_lossy().into_owned());
+            }
+
+            std::fs::rename(source_dir.join(name), &current)
+                .with_context(|| format!("Failed to install `{}`.", current.display()))?;
+            installed.push(name);
+        }
+
+        if !added.is_empty() {
+            let added_file = backup_dir.join(ADDED_BINARIES_FILE);
+            std::fs::write(&added_file, added.join("\n"))
+                .with_context(|| format!("Failed to write `{}`.", added_file.display()))?;
+        }
+
+        Ok(())
+    })();
+
+    if result.is_err() {
+        for name in installed.iter().rev() {
+            let _ = std::fs::rename(install_dir.join(name), source_dir.join(name));
+        }
+        for name in backed_up.iter().rev() {
+            let _ = std::fs::rename(backup_dir.join(name), install_dir.join(name));
+        }
+    }
+
+    result
+}
+
+/// Runs `<juliaup> --version` and returns the version it reports.
+#[cfg(feature = "selfupdate")]
+pub fn juliaup_binary_version(juliaup_path: &Path) -> Result<String> {
+    let output = std::process::Command::new(juliaup_path)
+        .arg("--version")
+        .output()
+        .with_context(|| format!("Failed to run `{}`.", juliaup_path.display()))?;
+
+    if !output.status.success() {
+        bail!(
+            "`{} --version` exited with {}.",
+            juliaup_path.display(),
+            output.status
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    stdout
+        .split_whitespace()
+        .last()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("`{} --version` printed nothing.", juliaup_path.display()))
+}
+
+/// Runs the `_post-update` hook of the juliaup binary at `juliaup_path`.
+#[cfg(feature = "selfupdate")]
+pub fn run_post_update_hook(juliaup_path: &Path) -> Result<()> {
+    let status = std::process::Command::new(juliaup_path)
+        .arg("_post-update")
+        .status()
+        .with_context(|| format!("Failed to run `{}`.", juliaup_path.display()))?;
+
+    if !status.success() {
+        bail!("The post-update hook exited with {}.", status);
+    }
+
+    Ok(())
+}
+
+/// The mechanism that runs the background self-update.
+#[cfg(feature = "selfupdate")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelfUpdateScheduler {
+    /// A Windows scheduled task, used when juliaup runs inside WSL.
+    WindowsTask,
+    /// A systemd `--user` service and timer.
+    SystemdTimer,
+    /// A crontab entry.
+    Cron,
+}
+
+#[cfg(feature = "selfupdate")]
+impl SelfUpdateScheduler {
+    pub fn description(&self) -> &'static str {
+        match self {
+            SelfUpdateScheduler::WindowsTask => "Windows scheduled task",
+            SelfUpdateScheduler::SystemdTimer => "systemd user timer",
+            SelfUpdateScheduler::Cron => "cron",
+        }
+    }
+}
+
+/// The hidden `juliaup` subcommand that runs a background self-update. The
+/// scheduled jobs are recognised by it, so it must never change.
+#[cfg(feature = "selfupdate")]
+pub const SECRET_SELFUPDATE_COMMAND: &str = "4c79c12db1d34bbbab1f6c6f838f423f";
+
+#[cfg(feature = "selfupdate")]
+const SYSTEMD_SERVICE_NAME: &str = "juliaup-selfupdate.service";
+
+#[cfg(feature = "selfupdate")]
+const SYSTEMD_TIMER_NAME: &str = "juliaup-selfupdate.timer";
+
+#[cfg(feature = "selfupdate")]
+fn wsl_task_name(distribution: &str) -> String {
+    format!("Juliaup self update for WSL {} distribution", distribution)
+}
+
+/// Whether a systemd user manager is running that we can install a timer
+/// into. Many Linux desktops run one but no cron daemon.
+#[cfg(feature = "selfupdate")]
+fn systemd_user_available() -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+
+    std::process::Command::new("systemctl")
+        .args(["--user", "show-environment"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(feature = "selfupdate")]
+fn systemd_user_unit_dir() -> Result<PathBuf> {
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => dirs::home_dir()
+            .ok_or_else(|| anyhow!("Could not determine the home directory."))?
+            .join(".config"),
+    };
+
+    Ok(config_home.join("systemd").join("user"))
+}
+
+/// Quotes a path for use in an `ExecStart=` line of a systemd unit.
+#[cfg(feature = "selfupdate")]
+fn systemd_quote(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('%', "%%")
+    )
+}
+
+/// The contents of the service and timer units that run the background
+/// self-update every `interval` minutes.
+#[cfg(feature = "selfupdate")]
+fn systemd_unit_contents(juliaup_path: &str, interval: i64) -> (String, String) {
+    let service = formatdoc!(
+        "
+        [Unit]
+        Description=Juliaup self update
+
+        [Service]
+        Type=oneshot
+        ExecStart={} {}
+        ",
+        systemd_quote(juliaup_path),
+        SECRET_SELFUPDATE_COMMAND
+    );
+
+    let timer = formatdoc!(
+        "
+        [Unit]
+        Description=Run the Juliaup self update every {interval} minutes
+
+        [Timer]
+        OnStartupSec={interval}min
+        OnUnitActiveSec={interval}min
+
+        [Install]
+        WantedBy=timers.target
+        "
+    );
+
+    (service, timer)
+}
+
+#[cfg(feature = "selfupdate")]
+fn run_systemctl_user(args: &[&str]) -> Result<()> {
+    let output = std::process::Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run `systemctl --user {}`.", args.join(" ")))?;
+
+    if !output.status.success() {
+        bail!(
+            "`systemctl --user {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "selfupdate")]
+fn install_systemd_timer(juliaup_path: &str, interval: i64) -> Result<()> {
+    let unit_dir = systemd_user_unit_dir()?;
+
+    std::fs::create_dir_all(&unit_dir).with_context(|| {
+        format!(
+            "Failed to create the systemd user unit directory `{}`.",
+            unit_dir.display()
+        )
+    })?;
+
+    let (service, timer) = systemd_unit_contents(juliaup_path, interval);
+
+    for (name, content) in [(SYSTEMD_SERVICE_NAME, service), (SYSTEMD_TIMER_NAME, timer)] {
+        let path = unit_dir.join(name);
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write `{}`.", path.display()))?;
+    }
+
+    run_systemctl_user(&["daemon-reload"])?;
+    // Restart so that a changed interval takes effect right away.
+    run_systemctl_user(&["enable", SYSTEMD_TIMER_NAME])?;
+    run_systemctl_user(&["restart", SYSTEMD_TIMER_NAME])?;
+
+    Ok(())
+}
+
+/// Removes the systemd units, if they were installed. Returns whether
+/// anything was removed.
+#[cfg(feature = "selfupdate")]
+fn uninstall_systemd_timer() -> Result<bool> {
+    let unit_dir = systemd_user_unit_dir()?;
+    let unit_paths = [
+        unit_dir.join(SYSTEMD_TIMER_NAME),
+        unit_dir.join(SYSTEMD_SERVICE_NAME),
+    ];
 
-    let symlink_path = symlink_folder.join(symlink_name);
-    let updating = _remove_symlink(&symlink_path)?;
+    if !unit_paths.iter().any(|path| path.exists()) {
+        return Ok(false);
+    }
 
-    match channel {
-        JuliaupConfigChannel::SystemChannel { version } => {
-            create_system_channel_symlink(version, symlink_name, &symlink_path, paths, &updating)
-        }
-        JuliaupConfigChannel::DirectDownloadChannel { path, version, .. } => {
-            create_direct_download_symlink(
-                path,
-                version,
-                symlink_name,
-                &symlink_path,
-                paths,
-                &updating,
-            )
-        }
-        JuliaupConfigChannel::LinkedChannel { command, args } => {
-            create_linked_channel_shim(command, args, symlink_name, &symlink_path, &updating)
-        }
-        JuliaupConfigChannel::AliasChannel { .. } => Ok(()), // Aliases have their symlinks resolved at runtime
-    }?;
+    // The user manager might not be reachable (e.g. over ssh without
+    // lingering), we still want the unit files gone in that case.
+    if let Err(e) = run_systemctl_user(&["disable", "--now", SYSTEMD_TIMER_NAME]) {
+        log::debug!("Failed to disable the juliaup timer: {:#}", e);
+    }
 
-    if updating.is_none() {
-        if let Ok(path) = std::env::var("PATH") {
-            if !path.split(':').any(|p| Path::new(p) == symlink_folder) {
-                eprintln!(
-                "Symlink {} added in {}. Add this directory to the system PATH to make the command available in your shell.",
-                symlink_name, symlink_folder.display(),
-            );
+    for path in &unit_paths {
+        match std::fs::remove_file(path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to remove `{}`.", path.display()))
             }
         }
     }
 
-    Ok(())
+    if let Err(e) = run_systemctl_user(&["daemon-reload"]) {
+        log::debug!("Failed to reload the systemd user manager: {:#}", e);
+    }
+
+    Ok(true)
 }
 
-#[cfg(windows)]
-pub fn create_symlink(_: &JuliaupConfigChannel, _: &String, _paths: &GlobalPaths) -> Result<()> {
+/// The current crontab, or `None` if `crontab` is not installed.
+#[cfg(feature = "selfupdate")]
+fn read_crontab() -> Result<Option<String>> {
+    match std::process::Command::new("crontab").args(["-l"]).output() {
+        Ok(output) => Ok(Some(String::from_utf8(output.stdout)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| "Failed to retrieve crontab configuration."),
+    }
+}
+
+#[cfg(feature = "selfupdate")]
+fn write_crontab(content: &str) -> Result<()> {
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new("crontab")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    let mut child_stdin = child.stdin.take().unwrap();
+
+    child_stdin.write_all(content.as_bytes())?;
+
+    // Close stdin to finish and avoid indefinite blocking
+    drop(child_stdin);
+
+    child.wait_with_output()?;
+
     Ok(())
 }
 
 #[cfg(feature = "selfupdate")]
-pub fn install_background_selfupdate(interval: i64) -> Result<()> {
+fn install_cron_entry(juliaup_path: &str, interval: i64) -> Result<()> {
     use itertools::Itertools;
-    use std::process::Stdio;
 
+    let current = read_crontab()?.ok_or_else(|| {
+        anyhow!("Neither a systemd user manager nor `crontab` is available to schedule the background self update.")
+    })?;
+
+    let new_crontab_content = current
+        .lines()
+        .filter(|x| !x.contains(SECRET_SELFUPDATE_COMMAND))
+        .chain([
+            &format!(
+                "*/{} * * * * {} {}",
+                interval, juliaup_path, SECRET_SELFUPDATE_COMMAND
+            ),
+            "",
+        ])
+        .join("\n");
+
+    write_crontab(&new_crontab_content)
+}
+
+/// Removes the crontab entry, if there is one. Returns whether anything was
+/// removed.
+#[cfg(feature = "selfupdate")]
+fn uninstall_cron_entry() -> Result<bool> {
+    use itertools::Itertools;
+
+    let current = match read_crontab().with_context(|| "Failed to remove cron task.")? {
+        Some(current) => current,
+        None => return Ok(false),
+    };
+
+    if !current.contains(SECRET_SELFUPDATE_COMMAND) {
+        return Ok(false);
+    }
+
+    let new_crontab_content = current
+        .lines()
+        .filter(|x| !x.contains(SECRET_SELFUPDATE_COMMAND))
+        .chain([""])
+        .join("\n");
+
+    write_crontab(&new_crontab_content)?;
+
+    Ok(true)
+}
+
+/// Schedules the background self-update to run every `interval` minutes.
+/// On Linux a systemd user timer is used when a user manager is running,
+/// otherwise a crontab entry.
+#[cfg(feature = "selfupdate")]
+pub fn install_background_selfupdate(interval: i64) -> Result<()> {
     let own_exe_path = std::env::current_exe()
         .with_context(|| "Could not determine the path of the running exe.")?;
 
@@ -1992,7 +3354,7 @@ pub fn install_background_selfupdate(interval: i64) -> Result<()> {
                     "/mo",
                     &interval.to_string(),
                     "/tn",
-                    &format!("Juliaup self update for WSL {} distribution", val),
+                    &wsl_task_name(&val),
                     "/f",
                     "/it",
                     "/tr",
@@ -2002,89 +3364,76 @@ pub fn install_background_selfupdate(interval: i64) -> Result<()> {
                 .with_context(|| "Failed to create new Windows task for juliaup.")?;
         }
         Err(_e) => {
-            let output = std::process::Command::new("crontab")
-                .args(["-l"])
-                .output()
-                .with_context(|| "Failed to retrieve crontab configuration.")?;
-
-            let new_crontab_content = String::from_utf8(output.stdout)?
-                .lines()
-                .filter(|x| !x.contains("4c79c12db1d34bbbab1f6c6f838f423f"))
-                .chain([
-                    &format!(
-                        "*/{} * * * * {} 4c79c12db1d34bbbab1f6c6f838f423f",
-                        interval, my_own_path
-                    ),
-                    "",
-                ])
-                .join("\n");
-
-            let mut child = std::process::Command::new("crontab")
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .spawn()?;
-
-            let mut child_stdin = child.stdin.take().unwrap();
-
-            child_stdin.write_all(new_crontab_content.as_bytes())?;
+            // Only ever keep one backend around, e.g. when systemd became
+            // available since the task was first installed.
+            if systemd_user_available() {
+                if let Err(e) = uninstall_cron_entry() {
+                    log::debug!("Failed to remove the juliaup cron entry: {:#}", e);
+                }
 
-            // Close stdin to finish and avoid indefinite blocking
-            drop(child_stdin);
+                install_systemd_timer(my_own_path, interval)?;
+            } else {
+                if let Err(e) = uninstall_systemd_timer() {
+                    log::debug!("Failed to remove the juliaup systemd timer: {:#}", e);
+                }
 
-            child.wait_with_output()?;
+                install_cron_entry(my_own_path, interval)?;
+            }
         }
     };
 
     Ok(())
 }
 
+/// Removes the background self-update from whichever scheduler it was
+/// installed into.
 #[cfg(feature = "selfupdate")]
 pub fn uninstall_background_selfupdate() -> Result<()> {
-    use itertools::Itertools;
-    use std::process::Stdio;
-
     match std::env::var("WSL_DISTRO_NAME") {
         // This is the WSL case, where we schedule a Windows task to do the update
         Ok(val) => {
             std::process::Command::new("schtasks.exe")
-                .args([
-                    "/delete",
-                    "/tn",
-                    &format!("Juliaup self update for WSL {} distribution", val),
-                    "/f",
-                ])
+                .args(["/delete", "/tn", &wsl_task_name(&val), "/f"])
                 .output()
                 .with_context(|| "Failed to remove Windows task for juliaup.")?;
         }
         Err(_e) => {
-            let output = std::process::Command::new("crontab")
-                .args(["-l"])
-                .output()
-                .with_context(|| "Failed to remove cron task.")?;
-
-            let new_crontab_content = String::from_utf8(output.stdout)?
-                .lines()
-                .filter(|x| !x.contains("4c79c12db1d34bbbab1f6c6f838f423f"))
-                .chain([""])
-                .join("\n");
+            let systemd_result = uninstall_systemd_timer();
+            let cron_result = uninstall_cron_entry();
 
-            let mut child = std::process::Command::new("crontab")
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .spawn()?;
+            systemd_result?;
+            cron_result?;
+        }
+    };
 
-            let mut child_stdin = child.stdin.take().unwrap();
+    Ok(())
+}
 
-            child_stdin.write_all(new_crontab_content.as_bytes())?;
+/// The scheduler that currently has the background self-update installed,
+/// if any.
+#[cfg(feature = "selfupdate")]
+pub fn active_background_selfupdate_scheduler() -> Result<Option<SelfUpdateScheduler>> {
+    if let Ok(val) = std::env::var("WSL_DISTRO_NAME") {
+        let found = std::process::Command::new("schtasks.exe")
+            .args(["/query", "/tn", &wsl_task_name(&val)])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+
+        return Ok(found.then_some(SelfUpdateScheduler::WindowsTask));
+    }
 
-            // Close stdin to finish and avoid indefinite blocking
-            drop(child_stdin);
+    if systemd_user_unit_dir()?.join(SYSTEMD_TIMER_NAME).exists() {
+        return Ok(Some(SelfUpdateScheduler::SystemdTimer));
+    }
 
-            child.wait_with_output()?;
-        }
-    };
+    if read_crontab()?.is_some_and(|crontab| crontab.contains(SECRET_SELFUPDATE_COMMAND)) {
+        return Ok(Some(SelfUpdateScheduler::Cron));
+    }
 
-    Ok(())
+    Ok(None)
 }
 
 const S_MARKER: &[u8] = b"# >>> juliaup initialize >>>";
@@ -2114,7 +3463,11 @@ fn get_shell_script_juliaup_content(
         .file_name()
         .and_then(|n| n.to_str())
         .ok_or_else(|| anyhow!("Could not determine file name for path: {}", path.display()))?;
-    if file_name == ".zshrc" {
+    if file_name.ends_with(".fish") {
+        append_fish_content(&mut result, bin_path_str, juliauphome_str);
+    } else if file_name.ends_with(".nu") {
+        append_nushell_content(&mut result, bin_path_str, juliauphome_str);
+    } else if file_name == ".zshrc" {
         append_zsh_content(&mut result, bin_path_str);
     } else if path.file_name().unwrap() == ".cshrc" || path.file_name().unwrap() == ".tcshrc" {
         append_csh_content(&mut result, bin_path_str);
@@ -2175,6 +3528,35 @@ fn append_sh_content(buf: &mut Vec<u8>, path_str: &str) {
     buf.extend_from_slice(content.as_bytes());
 }
 
+fn append_fish_content(buf: &mut Vec<u8>, path_str: &str, juliauphome: &str) {
+    // fish keeps PATH as a list, only prepend if it's not there already
+    let content = formatdoc!(
+        r#"
+            if not contains -- "{path_str}" $PATH
+                set -gx PATH "{path_str}" $PATH
+            end
+
+            # Tab completion for juliaup and julia channel selection
+            test -f "{juliauphome}/completions/fish.fish"; and source "{juliauphome}/completions/fish.fish"
+        "#,
+    );
+    buf.extend_from_slice(content.as_bytes());
+}
+
+fn append_nushell_content(buf: &mut Vec<u8>, path_str: &str, juliauphome: &str) {
+    // `source` is resolved when nushell parses the file, so the completion
+    // file has to exist. It is written before this block is added.
+    let content = formatdoc!(
+        r#"
+            $env.PATH = ($env.PATH | split row (char esep) | where $it != "{path_str}" | prepend "{path_str}")
+
+            # Tab completion for juliaup and julia channel selection
+            source "{juliauphome}/completions/nushell.nu"
+        "#,
+    );
+    buf.extend_from_slice(content.as_bytes());
+}
+
 fn append_completions_content(buf: &mut Vec<u8>, file_name: &str, juliauphome: &str) {
     let (shell, ext) = if file_name == ".zshrc" {
         ("zsh", "zsh")
@@ -2217,6 +3599,11 @@ fn match_markers(buffer: &[u8]) -> Result<Option<(usize, usize)>> {
 }
 
 fn add_path_to_specific_file(bin_path: &Path, juliauphome: &Path, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}.", parent.display()))?;
+    }
+
     let mut file = std::fs::OpenOptions::new()
         .read(true)
         .write(true)
@@ -2292,6 +3679,15 @@ fn remove_path_from_specific_file(path: &Path) -> Result<()> {
     if let Some(pos) = existing_code_pos {
         buffer.replace_range(pos.0..pos.1, "");
 
+        // The fish and nushell files only exist for juliaup, so remove them
+        // rather than leaving an empty file behind.
+        if is_juliaup_owned_init_file(path) && buffer.trim().is_empty() {
+            drop(file);
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove file {}.", path.display()))?;
+            return Ok(());
+        }
+
         file.rewind().unwrap();
 
         file.set_len(0).unwrap();
@@ -2304,6 +3700,32 @@ fn remove_path_from_specific_file(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// The base directory of fish's configuration, which always follows the XDG
+/// layout.
+fn fish_config_dir(home_dir: &Path) -> PathBuf {
+    match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("fish"),
+        _ => home_dir.join(".config").join("fish"),
+    }
+}
+
+/// The default configuration directory of nushell, i.e. `$nu.default-config-dir`.
+fn nushell_config_dir(home_dir: &Path) -> PathBuf {
+    match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("nushell"),
+        _ => dirs::config_dir()
+            .unwrap_or_else(|| home_dir.join(".config"))
+            .join("nushell"),
+    }
+}
+
+/// Whether `path` is one of the startup files that juliaup creates itself,
+/// as opposed to a user's shell rc file that juliaup adds a section to.
+fn is_juliaup_owned_init_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == "juliaup.fish" || name == "juliaup.nu")
+}
+
 pub fn find_shell_scripts_to_be_modified(add_case: bool) -> Result<Vec<PathBuf>> {
     let home_dir = dirs::home_dir().unwrap();
 
@@ -2328,10 +3750,28 @@ pub fn find_shell_scripts_to_be_modified(add_case: bool) -> Result<Vec<PathBuf>>
             }, // On MacOS, always edit .zshrc as that is the default shell, but only when we add things
         )
         .cloned()
+        .chain(shell_config_dirs_files(&home_dir, add_case))
         .collect();
     Ok(result)
 }
 
+/// The juliaup files in the drop-in directories of fish and nushell. When
+/// adding, a file is created for each of these shells that has a
+/// configuration directory, i.e. that seems to be in use.
+fn shell_config_dirs_files(home_dir: &Path, add_case: bool) -> Vec<PathBuf> {
+    let fish_dir = fish_config_dir(home_dir);
+    let nushell_dir = nushell_config_dir(home_dir);
+
+    [
+        (fish_dir.join("conf.d").join("juliaup.fish"), fish_dir),
+        (nushell_dir.join("autoload").join("juliaup.nu"), nushell_dir),
+    ]
+    .into_iter()
+    .filter(|(file, config_dir)| file.exists() || (add_case && config_dir.is_dir()))
+    .map(|(file, _)| file)
+    .collect()
+}
+
 pub fn add_binfolder_to_path_in_shell_scripts(bin_path: &Path, juliauphome: &Path) -> Result<()> {
     write_completion_files::<Juliaup>(juliauphome, "juliaup")
         .with_context(|| "Failed to write completion files.")?;
@@ -2507,7 +3947,22 @@ pub fn update_version_db(channel: &Option<String>, paths: &GlobalPaths) -> Resul
         return Ok(());
     }
 
-    for (channel, update_info) in direct_download_etags {
+    for (channel, update_info, pr_state) in direct_download_etags {
+        // Remember finished PRs so that `juliaup gc --prune-finished-prs` can
+        // act on them without asking GitHub again.
+        match pr_state {
+            Some(PrState::Open) => {
+                new_config_file.data.finished_pr_channels.remove(&channel);
+            }
+            Some(state) => {
+                new_config_file
+                    .data
+                    .finished_pr_channels
+                    .insert(channel.clone(), state);
+            }
+            None => {}
+        }
+
         let channel_data = new_config_file
             .data
             .installed_channels
@@ -2623,10 +4078,20 @@ fn print_pr_finished_notice(channel: &str, state: PrState) {
 /// location keyed by the head commit sha of the PR, so every push to the PR
 /// moves them. Re-resolve PR channels rather than trusting the URL recorded
 /// at install time, and fall back to the recorded URL if resolution fails
-/// (e.g. offline, GitHub API rate limit).
-fn current_direct_download_url(channel: &str, recorded_url: &str) -> String {
-    if !is_pr_channel(channel) {
-        return recorded_url.to_string();
+/// (e.g. offline, GitHub API rate limit). For PR channels the state of the
+/// pull request is returned as well. `pr<number>@<sha>` channels keep the
+/// recorded URL, but still report that state.
+fn current_direct_download_url(channel: &str, recorded_url: &str) -> (String, Option<PrState>) {
+    if let Some(pin) = parse_staged_build_pin(channel) {
+        let pr_state = pinned_pr_state(&pin, resolve_pr_info);
+        if let Some(state) = pr_state {
+            print_pr_finished_notice(channel, state);
+        }
+        return (recorded_url.to_string(), pr_state);
+    }
+
+    if !is_pr_channel(channel) || is_pinned_channel(channel) {
+        return (recorded_url.to_string(), None);
     }
 
     let resolved = channel_to_name(channel).and_then(|name| {
@@ -2641,7 +4106,7 @@ fn current_direct_download_url(channel: &str, recorded_url: &str) -> String {
             if let Some(state) = pr_state {
                 print_pr_finished_notice(channel, state);
             }
-            url.to_string()
+            (url.to_string(), pr_state)
         }
         Err(e) => {
             log::debug!(
@@ -2650,7 +4115,27 @@ fn current_direct_download_url(channel: &str, recorded_url: &str) -> String {
                 recorded_url,
                 e
             );
-            recorded_url.to_string()
+            (recorded_url.to_string(), None)
+        }
+    }
+}
+
+/// The state of the pull request a `pr<number>@<sha>` pin belongs to, looked
+/// up with `resolve`. `None` for `commit-<sha>` pins and failed lookups.
+fn pinned_pr_state(
+    pin: &StagedBuildPin,
+    resolve: impl FnOnce(u64) -> Result<PrInfo>,
+) -> Option<PrState> {
+    let pr_number = pin.pr_number?;
+    match resolve(pr_number) {
+        Ok(info) => Some(info.state),
+        Err(e) => {
+            log::debug!(
+                "Failed to look up the state of pull request #{}: {:?}",
+                pr_number,
+                e
+            );
+            None
         }
     }
 }
@@ -2675,8 +4160,8 @@ fn direct_download_etag_url(download_url: &str, _binary_path: &Option<String>) -
 
 /// For each direct-download channel, `Some((url, etag))` of the build the
 /// channel should currently point at, or `None` if that could not be
-/// determined.
-type DirectDownloadUpdateInfo = Vec<(String, Option<(String, String)>)>;
+/// determined, along with the state of the pull request for PR channels.
+type DirectDownloadUpdateInfo = Vec<(String, Option<(String, String)>, Option<PrState>)>;
 
 #[cfg(windows)]
 fn download_direct_download_etags(
@@ -2703,6 +4188,23 @@ fn download_direct_download_etags(
             }
         }
 
+        // A pinned channel always stays at the build it was installed from,
+        // but the state of its pull request is still worth recording.
+        if is_pinned_channel(channel_name) {
+            if let JuliaupConfigChannel::DirectDownloadChannel {
+                url, server_etag, ..
+            } = installed_channel
+            {
+                let (url, pr_state) = current_direct_download_url(channel_name, url);
+                requests.push((
+                    channel_name.clone(),
+                    Some((url, server_etag.clone())),
+                    pr_state,
+                ));
+            }
+            continue;
+        }
+
         if let JuliaupConfigChannel::DirectDownloadChannel {
             url, binary_path, ..
         } = installed_channel
@@ -2710,7 +4212,7 @@ fn download_direct_download_etags(
             // If server doesn't support etag, we can't check for updates on nightly/PR channels
             // Return None gracefully so the update process can continue with other channels
             if !server_supports_etag {
-                requests.push((channel_name.clone(), None));
+                requests.push((channel_name.clone(), None, None));
                 continue;
             }
 
@@ -2725,26 +4227,39 @@ fn download_direct_download_etags(
                 channel_name
             );
 
-            let update_info = run_with_slow_message(
+            let (update_info, pr_state) = run_with_slow_message(
                 move || {
                     // PR builds move when the PR receives new commits, so the
                     // URL needs to be re-resolved before checking the etag.
-                    let url = current_direct_download_url(&channel_name_resolve, &url_clone);
+                    let (url, pr_state) =
+                        current_direct_download_url(&channel_name_resolve, &url_clone);
                     let etag_url = direct_download_etag_url(&url, &binary_path_clone);
 
                     let request_uri = Uri::CreateUri(&HSTRING::from(&etag_url))
                         .with_context(|| format!("Failed to create URI from {etag_url}"))?;
 
-                    let request = HttpRequestMessage::Create(&HttpMethod::Head()?, &request_uri)
-                        .with_context(|| "Failed to create HttpRequestMessage.")?;
-
-                    let async_op = http_client
-                        .SendRequestAsync(&request)
-                        .map_err(|e| anyhow!("Failed to send request: {:?}", e))?;
-
-                    let response = async_op
-                        .join()
-                        .map_err(|e| anyhow!("Failed to get response: {:?}", e))?;
+                    // A request message can only be sent once, so every
+                    // attempt creates a fresh one.
+                    let response = with_network_retries(&etag_url, |last_attempt| {
+                        let request = || -> Result<_> {
+                            let request =
+                                HttpRequestMessage::Create(&HttpMethod::Head()?, &request_uri)
+                                    .with_context(|| "Failed to create HttpRequestMessage.")?;
+                            apply_credentials(&request.Headers()?, &etag_url)?;
+                            Ok(request)
+                        };
+
+                        match request() {
+                            Ok(request) => windows_retry_decision(
+                                http_client
+                                    .SendRequestAsync(&request)
+                                    .and_then(|op| op.join()),
+                                last_attempt,
+                            ),
+                            Err(e) => OperationResult::Err(e),
+                        }
+                    })
+                    .with_context(|| format!("Failed to send request to `{}`.", etag_url))?;
 
                     if response.IsSuccessStatusCode()? {
                         // Gracefully handle missing etag - return None instead of error
@@ -2754,16 +4269,16 @@ fn download_direct_download_etags(
                             .and_then(|headers| headers.Lookup(&HSTRING::from("ETag")).ok())
                             .map(|s| s.to_string());
 
-                        Ok::<Option<(String, String)>, anyhow::Error>(etag.map(|etag| (url, etag)))
+                        Ok::<_, anyhow::Error>((etag.map(|etag| (url, etag)), pr_state))
                     } else {
-                        Ok::<Option<(String, String)>, anyhow::Error>(None)
+                        Ok::<_, anyhow::Error>((None, pr_state))
                     }
                 },
                 3, // Timeout in seconds
                 &message,
             )?;
 
-            requests.push((channel_name_clone, update_info));
+            requests.push((channel_name_clone, update_info, pr_state));
         }
     }
 
@@ -2792,6 +4307,23 @@ fn download_direct_download_etags(
             }
         }
 
+        // A pinned channel always stays at the build it was installed from,
+        // but the state of its pull request is still worth recording.
+        if is_pinned_channel(channel_name) {
+            if let JuliaupConfigChannel::DirectDownloadChannel {
+                url, server_etag, ..
+            } = installed_channel
+            {
+                let (url, pr_state) = current_direct_download_url(channel_name, url);
+                requests.push((
+                    channel_name.clone(),
+                    Some((url, server_etag.clone())),
+                    pr_state,
+                ));
+            }
+            continue;
+        }
+
         if let JuliaupConfigChannel::DirectDownloadChannel {
             url, binary_path, ..
         } = installed_channel
@@ -2799,7 +4331,7 @@ fn download_direct_download_etags(
             // If server doesn't support etag, we can't check for updates on nightly/PR channels
             // Return None gracefully so the update process can continue with other channels
             if !server_supports_etag {
-                requests.push((channel_name.clone(), None));
+                requests.push((channel_name.clone(), None, None));
                 continue;
             }
 
@@ -2815,17 +4347,18 @@ fn download_direct_download_etags(
                 channel_name
             );
 
-            let update_info = run_with_slow_message(
+            let (update_info, pr_state) = run_with_slow_message(
                 move || {
                     // PR builds move when the PR receives new commits, so the
                     // URL needs to be re-resolved before checking the etag.
-                    let url = current_direct_download_url(&channel_name_resolve, &url_clone);
+                    let (url, pr_state) =
+                        current_direct_download_url(&channel_name_resolve, &url_clone);
                     let etag_url = direct_download_etag_url(&url, &binary_path_clone);
 
-                    let response = client
-                        .head(&etag_url)
-                        .send()
-                        .with_context(|| format!("Failed to send HEAD request to {}", etag_url))?;
+                    let response = send_with_retries(&etag_url, || {
+                        apply_credentials(client.head(&etag_url), &etag_url)
+                    })
+                    .with_context(|| format!("Failed to send HEAD request to {}", etag_url))?;
 
                     if response.status().is_success() {
                         // Gracefully handle missing etag - return None instead of error
@@ -2835,16 +4368,16 @@ fn download_direct_download_etags(
                             .and_then(|h| h.to_str().ok())
                             .map(|s| s.to_string());
 
-                        Ok::<Option<(String, String)>, anyhow::Error>(etag.map(|etag| (url, etag)))
+                        Ok::<_, anyhow::Error>((etag.map(|etag| (url, etag)), pr_state))
                     } else {
-                        Ok::<Option<(String, String)>, anyhow::Error>(None)
+                        Ok::<_, anyhow::Error>((None, pr_state))
                     }
                 },
                 3, // Timeout in seconds
                 &message,
             )?;
 
-            requests.push((channel_name_clone, update_info));
+            requests.push((channel_name_clone, update_info, pr_state));
         }
     }
 
@@ -3131,6 +4664,55 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn fish_and_nushell_files_get_their_own_syntax() -> Result<()> {
+        let bin = Path::new("/home/me/.juliaup/bin");
+        let home = Path::new("/home/me/.julia/juliaup");
+
+        let fish = get_shell_script_juliaup_content(
+            bin,
+            home,
+            Path::new("/home/me/.config/fish/conf.d/juliaup.fish"),
+        )?;
+        let fish = String::from_utf8(fish)?;
+        assert!(fish.contains("set -gx PATH \"/home/me/.juliaup/bin\" $PATH"));
+        assert!(fish.contains("source \"/home/me/.julia/juliaup/completions/fish.fish\""));
+        assert!(!fish.contains("export PATH"));
+
+        let nu = get_shell_script_juliaup_content(
+            bin,
+            home,
+            Path::new("/home/me/.config/nushell/autoload/juliaup.nu"),
+        )?;
+        let nu = String::from_utf8(nu)?;
+        assert!(nu.contains("prepend \"/home/me/.juliaup/bin\""));
+        assert!(nu.contains("source \"/home/me/.julia/juliaup/completions/nushell.nu\""));
+        assert!(match_markers(nu.as_bytes())?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn removing_the_block_deletes_juliaup_owned_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let bin = Path::new("/opt/juliaup/bin");
+
+        let fish = dir.path().join("conf.d").join("juliaup.fish");
+        add_path_to_specific_file(bin, dir.path(), &fish)?;
+        add_path_to_specific_file(bin, dir.path(), &fish)?;
+        let content = std::fs::read(&fish)?;
+        assert_eq!(content.find_iter(S_MARKER).count(), 1);
+        remove_path_from_specific_file(&fish)?;
+        assert!(!fish.exists());
+
+        // A user's rc file is kept, only the juliaup section is removed.
+        let bashrc = dir.path().join(".bashrc");
+        std::fs::write(&bashrc, "alias ll='ls -l'\n")?;
+        add_path_to_specific_file(bin, dir.path(), &bashrc)?;
+        remove_path_from_specific_file(&bashrc)?;
+        assert!(std::fs::read_to_string(&bashrc)?.starts_with("alias ll='ls -l'\n"));
+        Ok(())
+    }
+
     #[cfg(target_os = "macos")]
     #[test]
     fn symlink_uses_app_bundle_for_system_channel() -> Result<()> {
@@ -3374,6 +4956,68 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn swap_in_binaries_moves_replaced_files_aside() -> Result<()> {
+        let root = tempfile::TempDir::new()?;
+        let install = root.path().join("bin");
+        let new = root.path().join("new");
+        let backup = root.path().join("backup");
+        std::fs::create_dir_all(&install)?;
+        std::fs::create_dir_all(&new)?;
+        std::fs::write(install.join("juliaup"), "old")?;
+        std::fs::write(install.join("julia-1.10"), "symlink stand-in")?;
+        std::fs::write(new.join("juliaup"), "new")?;
+        std::fs::write(new.join("juliaupgui"), "new")?;
+
+        swap_in_binaries(&new, &install, &backup)?;
+
+        assert_eq!(std::fs::read_to_string(install.join("juliaup"))?, "new");
+        assert_eq!(std::fs::read_to_string(install.join("juliaupgui"))?, "new");
+        assert!(install.join("julia-1.10").exists());
+        assert_eq!(std::fs::read_to_string(backup.join("juliaup"))?, "old");
+        assert!(!backup.join("juliaupgui").exists());
+        assert_eq!(std::fs::read_dir(&new)?.count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn swapping_the_backup_back_removes_added_files() -> Result<()> {
+        let root = tempfile::TempDir::new()?;
+        let install = root.path().join("bin");
+        let new = root.path().join("new");
+        let backup = root.path().join("backup");
+        let undo = root.path().join("undo");
+        std::fs::create_dir_all(&install)?;
+        std::fs::create_dir_all(&new)?;
+        std::fs::write(install.join("juliaup"), "old")?;
+        std::fs::write(install.join("julia-1.10"), "symlink stand-in")?;
+        std::fs::write(new.join("juliaup"), "new")?;
+        std::fs::write(new.join("juliaupgui"), "new")?;
+
+        swap_in_binaries(&new, &install, &backup)?;
+        swap_in_binaries(&backup, &install, &undo)?;
+
+        assert_eq!(std::fs::read_to_string(install.join("juliaup"))?, "old");
+        assert!(!install.join("juliaupgui").exists());
+        assert!(!install.join(ADDED_BINARIES_FILE).exists());
+        assert!(install.join("julia-1.10").exists());
+
+        // The replaced files can be swapped in again, e.g. to undo a rollback.
+        swap_in_binaries(&undo, &install, &root.path().join("redo"))?;
+        assert_eq!(std::fs::read_to_string(install.join("juliaup"))?, "new");
+        assert_eq!(std::fs::read_to_string(install.join("juliaupgui"))?, "new");
+        Ok(())
+    }
+
+    #[test]
+    fn previous_binaries_dir_is_a_sibling() -> Result<()> {
+        assert_eq!(
+            previous_binaries_dir(Path::new("/home/user/.juliaup/bin"))?,
+            Path::new("/home/user/.juliaup/bin.previous")
+        );
+        Ok(())
+    }
+
     #[test]
     fn pr_staging_url_path_shortens_sha() -> Result<()> {
         let head_sha = "0123456789abcdef0123456789abcdef01234567";
@@ -3396,18 +5040,124 @@ mod tests {
     }
 
     #[test]
-    fn pr_staging_os_arch_matches_buildkite_vocabulary() -> Result<()> {
+    fn buildkite_os_arch_matches_buildkite_vocabulary() -> Result<()> {
         // These `<os>-<arch>` suffixes must match the `UPLOAD_FILENAME`
         // vocabulary of julia-buildkite's `utilities/build_envs.sh`.
-        assert_eq!(pr_staging_os_arch("macos-x86_64")?, "macos-x86_64");
-        assert_eq!(pr_staging_os_arch("macos-aarch64")?, "macos-aarch64");
-        assert_eq!(pr_staging_os_arch("win64")?, "windows-x86_64");
-        assert_eq!(pr_staging_os_arch("win32")?, "windows-i686");
-        assert_eq!(pr_staging_os_arch("linux-x86_64")?, "linux-x86_64");
-        assert_eq!(pr_staging_os_arch("linux-i686")?, "linux-i686");
-        assert_eq!(pr_staging_os_arch("linux-aarch64")?, "linux-aarch64");
-        assert_eq!(pr_staging_os_arch("freebsd-x86_64")?, "freebsd-x86_64");
-        assert!(pr_staging_os_arch("linux-powerpc64le").is_err());
+        assert_eq!(buildkite_os_arch("macos-x86_64")?, "macos-x86_64");
+        assert_eq!(buildkite_os_arch("macos-aarch64")?, "macos-aarch64");
+        assert_eq!(buildkite_os_arch("win64")?, "windows-x86_64");
+        assert_eq!(buildkite_os_arch("win32")?, "windows-i686");
+        assert_eq!(buildkite_os_arch("linux-x86_64")?, "linux-x86_64");
+        assert_eq!(buildkite_os_arch("linux-i686")?, "linux-i686");
+        assert_eq!(buildkite_os_arch("linux-aarch64")?, "linux-aarch64");
+        assert_eq!(buildkite_os_arch("freebsd-x86_64")?, "freebsd-x86_64");
+        assert!(buildkite_os_arch("linux-powerpc64le").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn nightly_pins_are_parsed() -> Result<()> {
+        assert_eq!(
+            parse_nightly_pin("nightly@0123ABC")?,
+            Some(NightlyPin::Commit("0123abc".to_string()))
+        );
+        assert_eq!(
+            parse_nightly_pin("1.12-nightly@2026-09-30~aarch64")?,
+            Some(NightlyPin::Date(
+                chrono::NaiveDate::from_ymd_opt(2026, 9, 30).unwrap()
+            ))
+        );
+        assert_eq!(parse_nightly_pin("nightly")?, None);
+        assert_eq!(parse_nightly_pin("pr123")?, None);
+        assert!(parse_nightly_pin("nightly@yesterday").is_err());
+        assert!(parse_nightly_pin("nightly@2026-02-30").is_err());
+        assert!(is_pinned_nightly_channel("nightly@0123abc~x64"));
+        assert!(!is_pinned_nightly_channel("nightly~x64"));
+        Ok(())
+    }
+
+    #[test]
+    fn pinned_pr_channels_report_the_pr_state() {
+        let pin = parse_staged_build_pin("pr1234@0123abc~x64").unwrap();
+        let merged = |pr_number| {
+            assert_eq!(pr_number, 1234);
+            Ok(PrInfo {
+                head_sha: "fedcba9".to_string(),
+                state: PrState::Merged,
+            })
+        };
+        assert_eq!(pinned_pr_state(&pin, merged), Some(PrState::Merged));
+        assert_eq!(
+            pinned_pr_state(&pin, |_| Err(anyhow!("rate limited"))),
+            None
+        );
+
+        let commit_pin = parse_staged_build_pin("commit-0123abc").unwrap();
+        assert_eq!(
+            pinned_pr_state(&commit_pin, |_| panic!("commit pins have no pull request")),
+            None
+        );
+    }
+
+    #[test]
+    fn staged_build_pins_are_parsed() {
+        assert_eq!(
+            parse_staged_build_pin("commit-0123ABC~x64"),
+            Some(StagedBuildPin {
+                pr_number: None,
+                sha: "0123abc".to_string()
+            })
+        );
+        assert_eq!(
+            parse_staged_build_pin("pr1234@0123abc"),
+            Some(StagedBuildPin {
+                pr_number: Some(1234),
+                sha: "0123abc".to_string()
+            })
+        );
+        assert_eq!(parse_staged_build_pin("pr1234"), None);
+        assert_eq!(parse_staged_build_pin("commit-xyz"), None);
+
+        assert!(is_pr_channel("pr1234"));
+        assert!(is_pr_channel("pr1234@0123abc~aarch64"));
+        assert!(is_pr_channel("commit-0123abc"));
+        assert!(!is_pr_channel("commit-ab"));
+        assert!(!is_pr_channel("pr1234@ab"));
+        assert!(!is_pr_channel("nightly"));
+
+        assert!(is_pinned_channel("commit-0123abc"));
+        assert!(is_pinned_channel("pr1234@0123abc"));
+        assert!(is_pinned_channel("nightly@2026-09-30"));
+        assert!(!is_pinned_channel("pr1234"));
+    }
+
+    #[test]
+    fn nightly_commit_url_path_uses_version_folder() -> Result<()> {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(
+            nightly_commit_url_path("1.13", sha, "linux-x86_64")?,
+            "bin/linux/x86_64/1.13/julia-0123456789-linux-x86_64.tar.gz"
+        );
+        assert_eq!(
+            nightly_commit_url_path("1.12", sha, "win64")?,
+            "bin/winnt/x64/1.12/julia-0123456789-windows-x86_64.tar.gz"
+        );
+        assert!(nightly_commit_url_path("1.13", "0123456", "linux-x86_64").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn commit_shas_are_read_from_api_responses() -> Result<()> {
+        assert_eq!(
+            commit_shas_from_api_response(r#"{"sha": "ABC123", "commit": {}}"#)?,
+            vec!["abc123".to_string()]
+        );
+        assert_eq!(
+            commit_shas_from_api_response(r#"[{"sha": "abc"}, {"sha": "def"}]"#)?,
+            vec!["abc".to_string(), "def".to_string()]
+        );
+        assert!(commit_shas_from_api_response(r#"{"message": "Not Found"}"#).is_err());
+        assert_eq!(major_minor_from_version_file("1.13.0-DEV\n")?, "1.13");
         Ok(())
     }
 
@@ -3489,4 +5239,59 @@ mod tests {
         assert!(pr_title_from_api_response("not json").is_err());
         Ok(())
     }
+
+    #[cfg(feature = "selfupdate")]
+    #[test]
+    fn systemd_units_run_the_secret_selfupdate_command() {
+        let (service, timer) = systemd_unit_contents("/home/me/50% off/juliaup", 60);
+
+        assert!(service.contains(
+            "ExecStart=\"/home/me/50%% off/juliaup\" 4c79c12db1d34bbbab1f6c6f838f423f\n"
+        ));
+        assert!(timer.contains("OnUnitActiveSec=60min\n"));
+        assert!(timer.contains("WantedBy=timers.target\n"));
+    }
+
+    #[test]
+    fn channel_shims_run_the_launcher_with_the_channel() {
+        let content = channel_shim_content("/opt/o'neil/julialauncher", "1.10");
+
+        if cfg!(windows) {
+            assert!(content.contains("+1.10 %*"));
+        } else {
+            assert!(content.starts_with("#!/bin/sh\n"));
+            assert!(content.ends_with("exec '/opt/o'\\''neil/julialauncher' '+1.10' \"$@\"\n"));
+        }
+        assert!(content.contains(CHANNEL_SHIM_MARKER));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn only_juliaup_channel_commands_are_replaced_by_shims() -> Result<()> {
+        let root = tempfile::TempDir::new()?;
+        let juliauphome = root.path().join("juliaup");
+        let bin = root.path().join("bin");
+        std::fs::create_dir_all(juliauphome.join("julia-1.10.4+0.x64.linux.gnu/bin"))?;
+        std::fs::create_dir_all(&bin)?;
+
+        let symlink = bin.join("julia-1.10");
+        std::os::unix::fs::symlink(
+            juliauphome.join("julia-1.10.4+0.x64.linux.gnu/bin/julia"),
+            &symlink,
+        )?;
+        assert!(is_juliaup_channel_command(&symlink, &juliauphome));
+
+        let shim = bin.join("julia-release");
+        std::fs::write(&shim, channel_shim_content("/opt/julialauncher", "release"))?;
+        assert!(is_juliaup_channel_command(&shim, &juliauphome));
+
+        let own_script = bin.join("julia-dev");
+        std::fs::write(&own_script, "#!/bin/sh\nexec ~/src/julia/julia \"$@\"\n")?;
+        assert!(!is_juliaup_channel_command(&own_script, &juliauphome));
+
+        let own_link = bin.join("julia-other");
+        std::os::unix::fs::symlink("/usr/bin/julia", &own_link)?;
+        assert!(!is_juliaup_channel_command(&own_link, &juliauphome));
+        Ok(())
+    }
 }
@@ -0,0 +1,88 @@
+//! Verification of signed juliaup release archives.
+//!
+//! Every `juliaup-<version>-<target>.tar.gz` is published together with a
+//! detached minisign signature, `<archive>.minisig`. The public key is
+//! embedded at build time from the `JULIAUP_SELFUPDATE_PUBLIC_KEY` environment
+//! variable, either as the bare base64 key or as the contents of a minisign
+//! `.pub` file. Builds without a key refuse to self-update rather than install
+//! an unverified binary.
+
+use anyhow::{anyhow, bail, Context, Result};
+use minisign_verify::{PublicKey, Signature};
+
+/// Suffix of the detached signature published next to each release archive.
+pub const SIGNATURE_SUFFIX: &str = ".minisig";
+
+const EMBEDDED_PUBLIC_KEY: Option<&str> = option_env!("JULIAUP_SELFUPDATE_PUBLIC_KEY");
+
+fn parse_public_key(key: &str) -> Result<PublicKey> {
+    let key = key
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty() && !line.starts_with("untrusted comment:"))
+        .ok_or_else(|| anyhow!("The self-update public key is empty."))?;
+
+    PublicKey::from_base64(key).with_context(|| "Failed to parse the self-update public key.")
+}
+
+/// Verifies `data` against a minisign `signature` made with `public_key`.
+/// Only pre-hashed signatures, the minisign default, are accepted.
+pub fn verify_with_key(public_key: &str, data: &[u8], signature: &str) -> Result<()> {
+    let public_key = parse_public_key(public_key)?;
+    let signature =
+        Signature::decode(signature).with_context(|| "Failed to parse the signature file.")?;
+
+    public_key
+        .verify(data, &signature, false)
+        .with_context(|| "The signature does not match the downloaded file.")
+}
+
+/// Verifies a downloaded release archive against the public key embedded in
+/// this build.
+pub fn verify_release_archive(data: &[u8], signature: &str) -> Result<()> {
+    let Some(public_key) = EMBEDDED_PUBLIC_KEY.filter(|key| !key.trim().is_empty()) else {
+        bail!(
+            "This build of juliaup does not contain a public key to verify updates with, \
+             so it cannot update itself. Please reinstall juliaup from https://julialang.org/install/."
+        );
+    };
+
+    verify_with_key(public_key, data, signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLIC_KEY: &str = "RWTxzq4KVOe29l8pskUxSEKck7QYmKJ6B+8VKPgQfZUiPPuP+TwM/I9e";
+    const OTHER_PUBLIC_KEY: &str = "RWTxzq4KVOe29pxbbaqNeBbNwa9QHPkRNfD4Kc81TSMN3Iy/wtqWTs1g";
+    const DATA: &[u8] = b"juliaup release archive";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key\n\
+        RUTxzq4KVOe29rY2x1pO41iwGvEs4JB8qjuuCXjWlO9q+/qw6y2VNipNC6WH8zb/aOYh5o+QgV3ctE9wNyVLdLj02Zc8LTf0cAE=\n\
+        trusted comment: timestamp:1760000000\tfile:juliaup-test.tar.gz\n\
+        sc/dt2dCdW1IN1T14wJHanHYp3RP2Df/N1gqXWidZq5LpyJe5ewYanCzECupYPs2womLASoo1zxvskIth07KCg==\n";
+
+    #[test]
+    fn valid_signature_is_accepted() {
+        verify_with_key(PUBLIC_KEY, DATA, SIGNATURE).unwrap();
+
+        let pub_file = format!("untrusted comment: minisign public key\n{}\n", PUBLIC_KEY);
+        verify_with_key(&pub_file, DATA, SIGNATURE).unwrap();
+    }
+
+    #[test]
+    fn tampered_data_is_rejected() {
+        assert!(verify_with_key(PUBLIC_KEY, b"juliaup release archivE", SIGNATURE).is_err());
+    }
+
+    #[test]
+    fn signature_from_another_key_is_rejected() {
+        assert!(verify_with_key(OTHER_PUBLIC_KEY, DATA, SIGNATURE).is_err());
+    }
+
+    #[test]
+    fn malformed_signature_is_rejected() {
+        assert!(verify_with_key(PUBLIC_KEY, DATA, "").is_err());
+        assert!(verify_with_key(PUBLIC_KEY, DATA, "<html>Not Found</html>").is_err());
+    }
+}
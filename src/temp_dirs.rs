@@ -0,0 +1,230 @@
+//! Ownership of the `julia-temp-*` directories that downloads extract into.
+//!
+//! Every temporary directory has a sibling `julia-temp-<id>.lock` file that
+//! the downloading process holds an exclusive lock on until the directory has
+//! been moved into its final location. The operating system releases the lock
+//! when the process dies, whether it finished, crashed or was killed, so a
+//! directory whose lock can be taken is a leftover that is safe to delete.
+
+use crate::disk_usage::{dir_size, find_temp_dirs, format_size};
+use crate::utils::{print_juliaup_style, JuliaupMessageType};
+use anyhow::{anyhow, Context, Result};
+use cluFlock::{ExclusiveFlock, FlockLock};
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tempfile::{Builder, TempDir};
+
+/// Locks of the temporary directories this process is downloading into.
+static DOWNLOAD_LOCKS: Mutex<Vec<(PathBuf, FlockLock<File>)>> = Mutex::new(Vec::new());
+
+/// Directories created by older Juliaup versions have no lock file. They are
+/// only treated as leftovers once nothing has touched them for this long.
+const UNLOCKED_TEMP_DIR_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Leftovers above this size are worth a warning when a command starts.
+const LEFTOVER_WARNING_SIZE: u64 = 100 * 1024 * 1024;
+
+fn lock_path(temp_dir: &Path) -> PathBuf {
+    let mut path = temp_dir.as_os_str().to_owned();
+    path.push(".lock");
+    PathBuf::from(path)
+}
+
+/// Creates a `julia-temp-*` directory in `juliauphome` that stays marked as
+/// in use until [`release_download_temp_dir`] is called or the process exits.
+pub fn create_download_temp_dir(juliauphome: &Path) -> Result<TempDir> {
+    // The lock file comes first, so the directory never exists without it.
+    let (lock_file, lock_file_path) = Builder::new()
+        .prefix("julia-temp-")
+        .suffix(".lock")
+        .tempfile_in(juliauphome)
+        .with_context(|| "Failed to create lock file for download.")?
+        .keep()
+        .with_context(|| "Failed to create lock file for download.")?;
+    let lock = ExclusiveFlock::try_lock(lock_file)
+        .map_err(|e| anyhow!("Failed to lock `{}`: {}.", lock_file_path.display(), e))?;
+
+    let temp_dir_name = lock_file_path
+        .file_stem()
+        .ok_or_else(|| anyhow!("Invalid lock file `{}`.", lock_file_path.display()))?;
+    let temp_dir = Builder::new()
+        .prefix(temp_dir_name)
+        .rand_bytes(0)
+        .tempdir_in(juliauphome)
+        .with_context(|| "Failed to create temporary directory for download.")?;
+
+    DOWNLOAD_LOCKS
+        .lock()
+        .unwrap()
+        .push((temp_dir.path().to_path_buf(), lock));
+
+    Ok(temp_dir)
+}
+
+/// Drops the lock of a temporary directory once it has been moved into place.
+pub fn release_download_temp_dir(temp_dir: &Path) {
+    DOWNLOAD_LOCKS
+        .lock()
+        .unwrap()
+        .retain(|(path, _)| path != temp_dir);
+
+    let _ = std::fs::remove_file(lock_path(temp_dir));
+}
+
+/// The lock of a leftover, held while it is deleted.
+struct Claim {
+    _lock: Option<FlockLock<File>>,
+}
+
+/// Takes over `temp_dir` if no running process owns it.
+fn claim_leftover(temp_dir: &Path) -> Option<Claim> {
+    match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(lock_path(temp_dir))
+    {
+        Ok(file) => ExclusiveFlock::try_lock(file)
+            .ok()
+            .map(|lock| Claim { _lock: Some(lock) }),
+        Err(_) => {
+            let modified = std::fs::metadata(temp_dir)
+                .and_then(|m| m.modified())
+                .ok()?;
+            (modified.elapsed().ok()? >= UNLOCKED_TEMP_DIR_MIN_AGE).then_some(Claim { _lock: None })
+        }
+    }
+}
+
+/// `julia-temp-*` directories in `juliauphome` that no running process owns.
+pub fn find_leftover_temp_dirs(juliauphome: &Path) -> Vec<PathBuf> {
+    find_temp_dirs(juliauphome)
+        .into_iter()
+        .filter(|temp_dir| claim_leftover(temp_dir).is_some())
+        .collect()
+}
+
+/// Deletes the `julia-temp-*` directories left behind by interrupted
+/// downloads, and lock files whose directory is already gone.
+pub fn remove_leftover_temp_dirs(juliauphome: &Path) {
+    for temp_dir in find_temp_dirs(juliauphome) {
+        let claim = match claim_leftover(&temp_dir) {
+            Some(claim) => claim,
+            None => continue,
+        };
+
+        let size = dir_size(&temp_dir);
+        match std::fs::remove_dir_all(&temp_dir) {
+            Ok(()) => print_juliaup_style(
+                "Tidyup",
+                &format!(
+                    "Removed interrupted download `{}` ({})",
+                    temp_dir.display(),
+                    format_size(size)
+                ),
+                JuliaupMessageType::Success,
+            ),
+            Err(e) => print_juliaup_style(
+                "WARNING",
+                &format!("Failed to delete `{}`: {}", temp_dir.display(), e),
+                JuliaupMessageType::Warning,
+            ),
+        }
+
+        drop(claim);
+        let _ = std::fs::remove_file(lock_path(&temp_dir));
+    }
+
+    let stale_locks = std::fs::read_dir(juliauphome)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "lock")
+                && path
+                    .file_stem()
+                    .is_some_and(|stem| stem.to_string_lossy().starts_with("julia-temp-"))
+                && !path.with_extension("").exists()
+        });
+    for stale_lock in stale_locks {
+        let unlocked = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&stale_lock)
+            .ok()
+            .and_then(|file| ExclusiveFlock::try_lock(file).ok());
+        if let Some(lock) = unlocked {
+            drop(lock);
+            let _ = std::fs::remove_file(&stale_lock);
+        }
+    }
+}
+
+/// Warns when interrupted downloads take up a lot of space in `juliauphome`.
+pub fn warn_about_leftover_temp_dirs(juliauphome: &Path) {
+    let size: u64 = find_leftover_temp_dirs(juliauphome)
+        .iter()
+        .map(|temp_dir| dir_size(temp_dir))
+        .sum();
+
+    if size > LEFTOVER_WARNING_SIZE {
+        print_juliaup_style(
+            "WARNING",
+            &format!(
+                "Interrupted downloads take up {} in `{}`. Run `juliaup gc` to remove them.",
+                format_size(size),
+                juliauphome.display()
+            ),
+            JuliaupMessageType::Warning,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn download_temp_dir_is_owned_until_released() {
+        let home = tempfile::tempdir().unwrap();
+
+        let temp_dir = create_download_temp_dir(home.path()).unwrap();
+        assert!(lock_path(temp_dir.path()).exists());
+        assert!(find_leftover_temp_dirs(home.path()).is_empty());
+
+        remove_leftover_temp_dirs(home.path());
+        assert!(temp_dir.path().exists());
+
+        let kept = temp_dir.keep();
+        release_download_temp_dir(&kept);
+        assert!(!lock_path(&kept).exists());
+    }
+
+    #[test]
+    fn leftovers_are_removed() {
+        let home = tempfile::tempdir().unwrap();
+
+        // A directory whose owner died: the lock file is there, but unlocked.
+        let leftover = home.path().join("julia-temp-abc123");
+        std::fs::create_dir_all(leftover.join("bin")).unwrap();
+        std::fs::write(leftover.join("bin/julia"), "partial").unwrap();
+        std::fs::write(lock_path(&leftover), "").unwrap();
+
+        // A fresh directory from an older Juliaup without a lock file.
+        let unlocked = home.path().join("julia-temp-def456");
+        std::fs::create_dir(&unlocked).unwrap();
+
+        // A lock file whose directory is already gone.
+        let stale_lock = lock_path(&home.path().join("julia-temp-ghi789"));
+        std::fs::write(&stale_lock, "").unwrap();
+
+        remove_leftover_temp_dirs(home.path());
+
+        assert!(!leftover.exists());
+        assert!(!lock_path(&leftover).exists());
+        assert!(unlocked.exists());
+        assert!(!stale_lock.exists());
+    }
+}
@@ -0,0 +1,179 @@
+//! Extra TLS trust and client certificates for corporate proxies and mTLS
+//! mirrors.
+//!
+//! Each setting can come from an environment variable or be persisted with
+//! `juliaup config`, with the environment variable taking precedence. The CA
+//! bundle additionally falls back to `SSL_CERT_FILE`. Certificates from the CA
+//! bundle are trusted in addition to the built-in roots, so the official
+//! servers keep working.
+//!
+//! On Windows juliaup uses the system HTTP stack, which only trusts the
+//! Windows certificate store; these settings are ignored there.
+
+#[cfg(not(windows))]
+use anyhow::{bail, Context, Result};
+use std::path::PathBuf;
+
+use crate::config_file::JuliaupConfigSettings;
+use crate::utils::configured_settings;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TlsSetting {
+    CaBundle,
+    ClientCertificate,
+    ClientKey,
+}
+
+impl TlsSetting {
+    pub fn env_var(&self) -> &'static str {
+        match self {
+            TlsSetting::CaBundle => "JULIAUP_CA_BUNDLE",
+            TlsSetting::ClientCertificate => "JULIAUP_CLIENT_CERT",
+            TlsSetting::ClientKey => "JULIAUP_CLIENT_KEY",
+        }
+    }
+
+    /// Name of the `juliaup config` property that persists this setting.
+    pub fn config_property(&self) -> &'static str {
+        match self {
+            TlsSetting::CaBundle => "cabundle",
+            TlsSetting::ClientCertificate => "clientcertificate",
+            TlsSetting::ClientKey => "clientkey",
+        }
+    }
+
+    pub fn configured_value(&self, settings: &JuliaupConfigSettings) -> Option<String> {
+        match self {
+            TlsSetting::CaBundle => settings.ca_bundle.clone(),
+            TlsSetting::ClientCertificate => settings.client_certificate.clone(),
+            TlsSetting::ClientKey => settings.client_key.clone(),
+        }
+    }
+
+    /// The effective path for this setting and a description of where it
+    /// came from, if it is set at all.
+    pub fn resolve(&self) -> Option<(PathBuf, String)> {
+        if let Some(val) = std::env::var_os(self.env_var()).filter(|v| !v.is_empty()) {
+            return Some((PathBuf::from(val), self.env_var().to_string()));
+        }
+
+        if let Some(val) = configured_settings().and_then(|s| self.configured_value(s)) {
+            return Some((
+                PathBuf::from(val),
+                format!("juliaup config {}", self.config_property()),
+            ));
+        }
+
+        if *self == TlsSetting::CaBundle {
+            // `SSL_CERT_FILE` is shared with other tools and is often stale,
+            // so only use it when it points at an existing file.
+            if let Some(path) = std::env::var_os("SSL_CERT_FILE")
+                .map(PathBuf::from)
+                .filter(|p| p.is_file())
+            {
+                return Some((path, "SSL_CERT_FILE".to_string()));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(not(windows))]
+fn read_setting_file(setting: TlsSetting) -> Result<Option<Vec<u8>>> {
+    match setting.resolve() {
+        Some((path, origin)) => {
+            let content = std::fs::read(&path).with_context(|| {
+                format!(
+                    "Failed to read `{}` configured via {}.",
+                    path.display(),
+                    origin
+                )
+            })?;
+            Ok(Some(content))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Applies the configured CA bundle and client certificate to an HTTP client
+/// builder.
+#[cfg(not(windows))]
+pub fn configure_tls(
+    mut builder: reqwest::blocking::ClientBuilder,
+) -> Result<reqwest::blocking::ClientBuilder> {
+    if let Some(bundle) = read_setting_file(TlsSetting::CaBundle)? {
+        let certs = reqwest::Certificate::from_pem_bundle(&bundle)
+            .with_context(|| "Failed to parse the configured CA bundle as PEM.")?;
+        if certs.is_empty() {
+            bail!("The configured CA bundle does not contain any certificates.");
+        }
+        log::debug!("Trusting {} additional CA certificate(s).", certs.len());
+        builder = builder.tls_certs_merge(certs);
+    }
+
+    let cert = read_setting_file(TlsSetting::ClientCertificate)?;
+    let key = read_setting_file(TlsSetting::ClientKey)?;
+
+    match (cert, key) {
+        (Some(cert), Some(key)) => {
+            log::debug!("Using the configured client certificate.");
+            builder = builder.identity(client_identity(&cert, &key)?);
+        }
+        (None, None) => {}
+        _ => bail!(
+            "A client certificate and a client key must be configured together ({} and {}).",
+            TlsSetting::ClientCertificate.env_var(),
+            TlsSetting::ClientKey.env_var()
+        ),
+    }
+
+    Ok(builder)
+}
+
+#[cfg(target_os = "macos")]
+fn client_identity(cert: &[u8], key: &[u8]) -> Result<reqwest::Identity> {
+    reqwest::Identity::from_pkcs8_pem(cert, key)
+        .with_context(|| "Failed to load the configured client certificate and key.")
+}
+
+#[cfg(all(not(target_os = "macos"), not(windows)))]
+fn client_identity(cert: &[u8], key: &[u8]) -> Result<reqwest::Identity> {
+    let mut pem = key.to_vec();
+    pem.push(b'\n');
+    pem.extend_from_slice(cert);
+
+    reqwest::Identity::from_pem(&pem)
+        .with_context(|| "Failed to load the configured client certificate and key.")
+}
+
+/// Warns once that the TLS settings have no effect on Windows.
+#[cfg(windows)]
+pub fn warn_if_tls_configured() {
+    use crate::utils::{print_juliaup_style, JuliaupMessageType};
+    use std::sync::OnceLock;
+
+    static WARNING_SHOWN: OnceLock<()> = OnceLock::new();
+
+    let configured = [
+        TlsSetting::CaBundle,
+        TlsSetting::ClientCertificate,
+        TlsSetting::ClientKey,
+    ]
+    .iter()
+    .any(|s| {
+        s.resolve()
+            .is_some_and(|(_, origin)| origin != "SSL_CERT_FILE")
+    });
+
+    if configured {
+        WARNING_SHOWN.get_or_init(|| {
+            print_juliaup_style(
+                "Warning",
+                "CA bundle and client certificate settings are not supported on Windows; \
+                 add certificates to the Windows certificate store instead.",
+                JuliaupMessageType::Warning,
+            );
+        });
+    }
+}
@@ -9,6 +9,10 @@ use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 use url::Url;
 
+use crate::config_file::{load_config_db_lockfree, JuliaupConfigSettings};
+use crate::credentials::apply_credentials;
+use crate::global_paths::get_paths;
+
 /// Resolves the Julia binary path, accounting for .app bundles on macOS
 #[cfg(target_os = "macos")]
 pub fn resolve_julia_binary_path(base_path: &Path) -> Result<PathBuf> {
@@ -57,29 +61,27 @@ static CUSTOM_SERVER_WARNING_SHOWN: OnceLock<()> = OnceLock::new();
 static CUSTOM_NIGHTLY_SERVER_WARNING_SHOWN: OnceLock<()> = OnceLock::new();
 static CUSTOM_PR_SERVER_WARNING_SHOWN: OnceLock<()> = OnceLock::new();
 
+/// Whether the effective juliaup server (from `JULIAUP_SERVER` or the
+/// `server` setting) is the official one.
+fn is_default_juliaserver() -> bool {
+    match resolve_server(JuliaupServer::Release) {
+        Ok((url, _)) => Url::parse(JuliaupServer::Release.default_url()).is_ok_and(|d| d == url),
+        Err(_) => false,
+    }
+}
+
 /// Checks if the nightly server supports etag headers.
 /// This is required for nightly and PR channel support because we use etags
 /// to track versions of these builds.
 ///
 /// The result is cached after the first check.
-/// If JULIAUP_SERVER equals the default official ones, it works as usual (assumes ETAG support).
+/// If the juliaup server equals the default official one, it works as usual (assumes ETAG support).
 /// Otherwise, sends a HEAD check request to verify ETAG support.
 #[cfg(not(windows))]
 pub fn check_server_supports_nightlies() -> Result<bool> {
     Ok(*NIGHTLY_SERVER_SUPPORTS_ETAG.get_or_init(|| {
-        // Check if JULIAUP_SERVER equals the default official one
-        let is_default_server = {
-            let julia_server = std::env::var("JULIAUP_SERVER")
-                .unwrap_or_else(|_| "https://julialang-s3.julialang.org".to_string());
-
-            // Normalize URL (remove trailing slashes for comparison)
-            let julia_server_normalized = julia_server.trim_end_matches('/');
-
-            julia_server_normalized == "https://julialang-s3.julialang.org"
-        };
-
         // If using default official servers, assume ETAG support
-        if is_default_server {
+        if is_default_juliaserver() {
             return true;
         }
 
@@ -94,8 +96,21 @@ pub fn check_server_supports_nightlies() -> Result<bool> {
             Err(_) => return false,
         };
 
-        let client = reqwest::blocking::Client::new();
-        match client.head(test_url.as_str()).send() {
+        let client = match crate::operations::http_client() {
+            Ok(client) => client,
+            Err(e) => {
+                log::debug!("Failed to create HTTP client: {}", e);
+                return false;
+            }
+        };
+        let request = match apply_credentials(client.head(test_url.as_str()), test_url.as_str()) {
+            Ok(request) => request,
+            Err(e) => {
+                log::debug!("Failed to apply credentials: {}", e);
+                return false;
+            }
+        };
+        match request.send() {
             Ok(response) => {
                 let has_etag = response.headers().get("etag").is_some();
                 log::debug!("Server etag support check: {}", has_etag);
@@ -114,44 +129,18 @@ pub fn check_server_supports_nightlies() -> Result<bool> {
 /// to track versions of these builds.
 ///
 /// The result is cached after the first check.
-/// If JULIAUP_SERVER equals the default official ones, it works as usual (assumes ETAG support).
+/// If the juliaup server equals the default official one, it works as usual (assumes ETAG support).
 /// Otherwise, sends a HEAD check request to verify ETAG support.
 #[cfg(windows)]
 pub fn check_server_supports_nightlies() -> Result<bool> {
     use windows::core::HSTRING;
     use windows::Foundation::Uri;
-    use windows::Web::Http::HttpClient;
     use windows::Web::Http::HttpMethod;
     use windows::Web::Http::HttpRequestMessage;
 
     Ok(*NIGHTLY_SERVER_SUPPORTS_ETAG.get_or_init(|| {
-        // Check if JULIAUP_SERVER equals the default official one
-        let is_default_server = {
-            let julia_server = std::env::var("JULIAUP_SERVER")
-                .unwrap_or_else(|_| "https://julialang-s3.julialang.org".to_string());
-
-            // Parse and compare URLs properly to handle variations
-            match Url::parse(&julia_server) {
-                Ok(parsed) => {
-                    if let Ok(default_url) = Url::parse("https://julialang-s3.julialang.org") {
-                        parsed.scheme() == default_url.scheme()
-                            && parsed.host_str() == default_url.host_str()
-                            && parsed.path().trim_end_matches('/')
-                                == default_url.path().trim_end_matches('/')
-                    } else {
-                        false
-                    }
-                }
-                Err(_) => {
-                    // Fall back to simple string comparison if URL parsing fails
-                    let julia_server_normalized = julia_server.trim_end_matches('/');
-                    julia_server_normalized == "https://julialang-s3.julialang.org"
-                }
-            }
-        };
-
         // If using default official servers, assume ETAG support
-        if is_default_server {
+        if is_default_juliaserver() {
             return true;
         }
 
@@ -172,7 +161,7 @@ pub fn check_server_supports_nightlies() -> Result<bool> {
             }
         };
 
-        let http_client = match HttpClient::new() {
+        let http_client = match crate::operations::http_client() {
             Ok(client) => client,
             Err(e) => {
                 log::debug!("Failed to create HTTP client: {:?}", e);
@@ -204,6 +193,15 @@ pub fn check_server_supports_nightlies() -> Result<bool> {
             }
         };
 
+        if let Err(e) = request
+            .Headers()
+            .map_err(anyhow::Error::from)
+            .and_then(|headers| apply_credentials(&headers, test_url.as_str()))
+        {
+            log::debug!("Failed to apply credentials: {}", e);
+            return false;
+        }
+
         let response = match http_client.SendRequestAsync(&request) {
             Ok(async_op) => match async_op.join() {
                 Ok(resp) => resp,
@@ -244,127 +242,170 @@ fn is_loopback_http(url: &Url) -> bool {
         )
 }
 
-pub fn get_juliaserver_base_url() -> Result<Url> {
-    let (base_url, is_custom) = if let Ok(val) = std::env::var("JULIAUP_SERVER") {
-        let url = if val.ends_with('/') {
-            val
-        } else {
-            format!("{}/", val)
-        };
-        (url, true)
-    } else {
-        ("https://julialang-s3.julialang.org".to_string(), false)
-    };
+/// The servers juliaup downloads from. Each can be overridden by an
+/// environment variable or a persisted setting in `juliaup.json`, with the
+/// environment variable taking precedence.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JuliaupServer {
+    Release,
+    Nightly,
+    Pr,
+}
 
-    let parsed_url = Url::parse(&base_url).with_context(|| {
-        format!(
-            "Failed to parse the value of JULIAUP_SERVER '{}' as a uri.",
-            base_url
-        )
-    })?;
+/// Where the effective value of a [`JuliaupServer`] came from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JuliaupServerSource {
+    EnvironmentVariable,
+    ConfigFile,
+    Default,
+}
 
-    if parsed_url.scheme() != "https" && !is_loopback_http(&parsed_url) {
-        bail!("The value of JULIAUP_SERVER '{}' must use HTTPS.", base_url);
+impl JuliaupServer {
+    pub fn env_var(&self) -> &'static str {
+        match self {
+            JuliaupServer::Release => "JULIAUP_SERVER",
+            JuliaupServer::Nightly => "JULIAUP_NIGHTLY_SERVER",
+            JuliaupServer::Pr => "JULIAUP_PR_SERVER",
+        }
     }
 
-    if is_custom {
-        CUSTOM_SERVER_WARNING_SHOWN.get_or_init(|| {
-            print_juliaup_style(
-                "Info",
-                &format!("Using custom server '{}' (JULIAUP_SERVER).", parsed_url),
-                JuliaupMessageType::Progress,
-            );
-        });
+    /// Name of the `juliaup config` property that persists this server.
+    pub fn config_property(&self) -> &'static str {
+        match self {
+            JuliaupServer::Release => "server",
+            JuliaupServer::Nightly => "nightlyserver",
+            JuliaupServer::Pr => "prserver",
+        }
     }
 
-    Ok(parsed_url)
-}
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            JuliaupServer::Release => "https://julialang-s3.julialang.org",
+            JuliaupServer::Nightly => "https://julialangnightlies-s3.julialang.org",
+            JuliaupServer::Pr => "https://julialang-ephemeral-pr.s3.amazonaws.com",
+        }
+    }
 
-pub fn get_julianightlies_base_url() -> Result<Url> {
-    let (base_url, is_custom) = if let Ok(val) = std::env::var("JULIAUP_NIGHTLY_SERVER") {
-        let url = if val.ends_with('/') {
-            val
-        } else {
-            format!("{}/", val)
-        };
-        (url, true)
-    } else {
-        (
-            "https://julialangnightlies-s3.julialang.org".to_string(),
-            false,
-        )
-    };
+    fn description(&self) -> &'static str {
+        match self {
+            JuliaupServer::Release => "server",
+            JuliaupServer::Nightly => "nightly server",
+            JuliaupServer::Pr => "PR server",
+        }
+    }
 
-    let parsed_url = Url::parse(&base_url).with_context(|| {
-        format!(
-            "Failed to parse the value of JULIAUP_NIGHTLY_SERVER '{}' as a uri.",
-            base_url
-        )
-    })?;
+    pub fn configured_value(&self, settings: &JuliaupConfigSettings) -> Option<String> {
+        match self {
+            JuliaupServer::Release => settings.server.clone(),
+            JuliaupServer::Nightly => settings.nightly_server.clone(),
+            JuliaupServer::Pr => settings.pr_server.clone(),
+        }
+    }
 
-    if parsed_url.scheme() != "https" && !is_loopback_http(&parsed_url) {
-        bail!(
-            "The value of JULIAUP_NIGHTLY_SERVER '{}' must use HTTPS.",
-            base_url
-        );
+    fn warning_shown(&self) -> &'static OnceLock<()> {
+        match self {
+            JuliaupServer::Release => &CUSTOM_SERVER_WARNING_SHOWN,
+            JuliaupServer::Nightly => &CUSTOM_NIGHTLY_SERVER_WARNING_SHOWN,
+            JuliaupServer::Pr => &CUSTOM_PR_SERVER_WARNING_SHOWN,
+        }
     }
+}
 
-    if is_custom {
-        CUSTOM_NIGHTLY_SERVER_WARNING_SHOWN.get_or_init(|| {
-            print_juliaup_style(
-                "Info",
-                &format!(
-                    "Using custom nightly server '{}' (JULIAUP_NIGHTLY_SERVER).",
-                    parsed_url
-                ),
-                JuliaupMessageType::Progress,
-            );
-        });
+impl JuliaupServerSource {
+    pub fn describe(&self, server: JuliaupServer) -> String {
+        match self {
+            JuliaupServerSource::EnvironmentVariable => server.env_var().to_string(),
+            JuliaupServerSource::ConfigFile => {
+                format!("juliaup config {}", server.config_property())
+            }
+            JuliaupServerSource::Default => "default".to_string(),
+        }
     }
+}
 
-    Ok(parsed_url)
+/// Settings from `juliaup.json`, read once per process. Reading is lock-free
+/// so resolving a server or TLS setting never blocks on (or deadlocks with) a
+/// caller that holds the configuration lock.
+static CONFIGURED_SETTINGS: OnceLock<Option<JuliaupConfigSettings>> = OnceLock::new();
+
+pub(crate) fn configured_settings() -> Option<&'static JuliaupConfigSettings> {
+    CONFIGURED_SETTINGS
+        .get_or_init(|| {
+            let paths = match get_paths() {
+                Ok(paths) => paths,
+                Err(e) => {
+                    log::debug!("Failed to determine paths for server settings: {}", e);
+                    return None;
+                }
+            };
+
+            match load_config_db_lockfree(&paths) {
+                Ok(config_file) => Some(config_file.data.settings),
+                Err(e) => {
+                    log::debug!("Failed to load configuration for server settings: {}", e);
+                    None
+                }
+            }
+        })
+        .as_ref()
 }
 
-/// Base URL of the bucket that CI stages pull request builds to, keyed by the
-/// head commit sha of the PR (see JuliaCI/julia-buildkite#544). The builds
-/// stored there are ephemeral and expire roughly 90 days after CI uploads
-/// them.
-pub fn get_juliaprs_base_url() -> Result<Url> {
-    let (base_url, is_custom) = if let Ok(val) = std::env::var("JULIAUP_PR_SERVER") {
-        let url = if val.ends_with('/') {
-            val
-        } else {
-            format!("{}/", val)
-        };
-        (url, true)
+/// Parses a server URL, appending a trailing slash so that relative paths
+/// join onto it, and rejects anything that is not HTTPS (or loopback HTTP).
+/// `origin` names where the value came from for error messages.
+pub fn parse_server_url(value: &str, origin: &str) -> Result<Url> {
+    let base_url = if value.ends_with('/') {
+        value.to_string()
     } else {
-        (
-            "https://julialang-ephemeral-pr.s3.amazonaws.com".to_string(),
-            false,
-        )
+        format!("{}/", value)
     };
 
     let parsed_url = Url::parse(&base_url).with_context(|| {
         format!(
-            "Failed to parse the value of JULIAUP_PR_SERVER '{}' as a uri.",
-            base_url
+            "Failed to parse the value of {} '{}' as a uri.",
+            origin, base_url
         )
     })?;
 
     if parsed_url.scheme() != "https" && !is_loopback_http(&parsed_url) {
-        bail!(
-            "The value of JULIAUP_PR_SERVER '{}' must use HTTPS.",
-            base_url
-        );
+        bail!("The value of {} '{}' must use HTTPS.", origin, base_url);
     }
 
-    if is_custom {
-        CUSTOM_PR_SERVER_WARNING_SHOWN.get_or_init(|| {
+    Ok(parsed_url)
+}
+
+/// Resolves the effective URL of `server` and where it came from, without
+/// printing anything.
+pub fn resolve_server(server: JuliaupServer) -> Result<(Url, JuliaupServerSource)> {
+    if let Ok(val) = std::env::var(server.env_var()) {
+        let url = parse_server_url(&val, server.env_var())?;
+        return Ok((url, JuliaupServerSource::EnvironmentVariable));
+    }
+
+    if let Some(val) = configured_settings().and_then(|s| server.configured_value(s)) {
+        let origin = format!("`juliaup config {}`", server.config_property());
+        let url = parse_server_url(&val, &origin)?;
+        return Ok((url, JuliaupServerSource::ConfigFile));
+    }
+
+    let url = Url::parse(server.default_url())
+        .with_context(|| format!("Failed to parse the default {}.", server.description()))?;
+
+    Ok((url, JuliaupServerSource::Default))
+}
+
+fn get_server_base_url(server: JuliaupServer) -> Result<Url> {
+    let (parsed_url, source) = resolve_server(server)?;
+
+    if source != JuliaupServerSource::Default {
+        server.warning_shown().get_or_init(|| {
             print_juliaup_style(
                 "Info",
                 &format!(
-                    "Using custom PR server '{}' (JULIAUP_PR_SERVER).",
-                    parsed_url
+                    "Using custom {} '{}' ({}).",
+                    server.description(),
+                    parsed_url,
+                    source.describe(server)
                 ),
                 JuliaupMessageType::Progress,
             );
@@ -374,6 +415,22 @@ pub fn get_juliaprs_base_url() -> Result<Url> {
     Ok(parsed_url)
 }
 
+pub fn get_juliaserver_base_url() -> Result<Url> {
+    get_server_base_url(JuliaupServer::Release)
+}
+
+pub fn get_julianightlies_base_url() -> Result<Url> {
+    get_server_base_url(JuliaupServer::Nightly)
+}
+
+/// Base URL of the bucket that CI stages pull request builds to, keyed by the
+/// head commit sha of the PR (see JuliaCI/julia-buildkite#544). The builds
+/// stored there are ephemeral and expire roughly 90 days after CI uploads
+/// them.
+pub fn get_juliaprs_base_url() -> Result<Url> {
+    get_server_base_url(JuliaupServer::Pr)
+}
+
 pub fn get_bin_dir() -> Result<PathBuf> {
     let entry_sep = if std::env::consts::OS == "windows" {
         ';'
@@ -464,12 +521,12 @@ pub fn parse_versionstring(value: &String) -> Result<(String, Version)> {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use std::sync::Mutex;
 
     // Env-var mutation is process-global; serialise tests that set/remove it.
-    static ENV_LOCK: Mutex<()> = Mutex::new(());
+    pub(crate) static ENV_LOCK: Mutex<()> = Mutex::new(());
 
     #[test]
     fn test_parse_versionstring() {
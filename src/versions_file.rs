@@ -6,6 +6,11 @@ use std::{fs::File, io::BufReader};
 use crate::{
     get_bundled_dbversion, global_paths::GlobalPaths, jsonstructs_versionsdb::JuliaupVersionDB,
 };
+#[cfg(target_os = "linux")]
+use crate::{
+    libc_flavor::{add_libc_variants, default_libc},
+    utils::configured_settings,
+};
 use anyhow::{Context, Result};
 use semver::Version;
 
@@ -45,5 +50,36 @@ pub fn load_versions_db(paths: &GlobalPaths) -> Result<JuliaupVersionDB> {
         None => load_vendored_db().with_context(|| "Failed to load vendored version db.")?,
     };
 
+    #[cfg(target_os = "linux")]
+    let db = {
+        let mut db = db;
+        let setting = configured_settings().and_then(|settings| settings.libc.as_deref());
+        add_libc_variants(&mut db, default_libc(setting));
+        db
+    };
+
     Ok(db)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::compatible_archs;
+
+    #[test]
+    fn vendored_db_has_channels_for_all_compatible_archs() -> Result<()> {
+        let db = load_vendored_db()?;
+
+        for arch in compatible_archs()? {
+            for channel in ["release", "lts"] {
+                let channel = format!("{}~{}", channel, arch);
+                assert!(
+                    db.available_channels.contains_key(&channel),
+                    "vendored versions db is missing `{}`",
+                    channel
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use predicates::prelude::*;
+
+mod utils;
+use utils::TestEnv;
+
+#[test]
+fn bisect_requires_a_command() {
+    let env = TestEnv::new();
+
+    env.juliaup()
+        .arg("bisect")
+        .arg("--good")
+        .arg("1.10.0")
+        .arg("--bad")
+        .arg("nightly")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("<COMMAND>..."));
+}
+
+#[test]
+fn bisect_finds_the_first_bad_release_and_cleans_up() {
+    let env = TestEnv::new();
+
+    // A channel left behind by an interrupted bisection.
+    let juliauphome = env.config_path().parent().unwrap().to_path_buf();
+    std::fs::create_dir_all(juliauphome.join("julia-1.10.1+0.x64.linux.gnu").join("bin")).unwrap();
+    let config = serde_json::json!({
+        "Default": null,
+        "InstalledVersions": {
+            "1.10.1+0.x64.linux.gnu": { "Path": "./julia-1.10.1+0.x64.linux.gnu" }
+        },
+        "InstalledChannels": {
+            "bisect-1.10.1": { "Version": "1.10.1+0.x64.linux.gnu" }
+        }
+    });
+    std::fs::write(env.config_path(), config.to_string()).unwrap();
+
+    env.juliaup()
+        .arg("bisect")
+        .arg("--good")
+        .arg("1.10.0")
+        .arg("--bad")
+        .arg("1.10.2")
+        .arg("--")
+        .arg("julia")
+        .arg("--startup-file=no")
+        .arg("-e")
+        .arg("exit(VERSION >= v\"1.10.2\" ? 1 : 0)")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("bisect-1.10.1"))
+        .stderr(predicate::str::contains("Julia 1.10.1 is good"))
+        .stderr(predicate::str::contains(
+            "Julia 1.10.2 is the first bad version.",
+        ));
+
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(env.config_path()).unwrap()).unwrap();
+    assert!(config["InstalledChannels"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .all(|name| !name.starts_with("bisect-")));
+    assert!(config["InstalledVersions"].as_object().unwrap().is_empty());
+    assert!(!juliauphome.join("julia-1.10.1+0.x64.linux.gnu").exists());
+}
@@ -0,0 +1,54 @@
+#![cfg(not(windows))]
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod utils;
+use utils::TestEnv;
+
+#[test]
+fn channel_shims_follow_channel_lifecycle() {
+    let env = TestEnv::new();
+    let shim = env
+        .home_path()
+        .join(".local")
+        .join("bin")
+        .join("julia-custom");
+
+    env.juliaup()
+        .args(["link", "custom", "/bin/echo", "hello"])
+        .assert()
+        .success();
+
+    env.juliaup()
+        .args(["config", "channelshims", "true"])
+        .assert()
+        .success();
+    assert!(shim.exists());
+
+    // The shim runs the launcher, so the alias arguments of the linked channel apply.
+    let mut shim_cmd = Command::new(&shim);
+    env.apply_env(&mut shim_cmd);
+    shim_cmd
+        .arg("world")
+        .assert()
+        .success()
+        .stdout("hello world\n");
+
+    // Shims are never replaced by channel symlinks.
+    env.juliaup()
+        .args(["config", "channelsymlinks", "true"])
+        .assert()
+        .success();
+    assert!(!shim.symlink_metadata().unwrap().file_type().is_symlink());
+    assert!(std::fs::read_to_string(&shim)
+        .unwrap()
+        .contains("'+custom'"));
+
+    env.juliaup()
+        .args(["remove", "custom"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Deleting shim julia-custom."));
+    assert!(shim.symlink_metadata().is_err());
+}
@@ -0,0 +1,32 @@
+#![cfg(target_os = "linux")]
+
+use predicates::prelude::*;
+use predicates::str::contains;
+
+mod utils;
+use utils::TestEnv;
+
+#[test]
+fn command_config_libc_selects_musl_builds() {
+    let env = TestEnv::new();
+
+    env.juliaup()
+        .args(["config", "libc", "musl"])
+        .assert()
+        .success();
+
+    let config = std::fs::read_to_string(env.config_path()).unwrap();
+    let config: serde_json::Value = serde_json::from_str(&config).unwrap();
+    assert_eq!(config["Settings"]["Libc"], "musl");
+
+    env.juliaup()
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(contains("release~glibc").and(contains("x64.linux.musl")));
+
+    env.juliaup()
+        .args(["config", "libc", "uclibc"])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,50 @@
+mod utils;
+use utils::TestEnv;
+
+fn read_settings(env: &TestEnv) -> serde_json::Value {
+    let config = std::fs::read_to_string(env.config_path()).unwrap();
+    let config: serde_json::Value = serde_json::from_str(&config).unwrap();
+    config["Settings"].clone()
+}
+
+#[test]
+fn command_config_server_persists_and_unsets() {
+    let env = TestEnv::new();
+
+    env.juliaup()
+        .args(["config", "server", "https://mirror.example.com"])
+        .env_remove("JULIAUP_SERVER")
+        .assert()
+        .success();
+
+    assert_eq!(read_settings(&env)["Server"], "https://mirror.example.com");
+
+    env.juliaup()
+        .args(["config", "nightlyserver", "https://nightlies.example.com"])
+        .env_remove("JULIAUP_NIGHTLY_SERVER")
+        .assert()
+        .success();
+
+    assert_eq!(
+        read_settings(&env)["NightlyServer"],
+        "https://nightlies.example.com"
+    );
+
+    env.juliaup()
+        .args(["config", "server", "default"])
+        .assert()
+        .success();
+
+    assert!(read_settings(&env).get("Server").is_none());
+}
+
+#[test]
+fn command_config_server_rejects_http() {
+    let env = TestEnv::new();
+
+    env.juliaup()
+        .args(["config", "prserver", "http://evil.example.com"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("must use HTTPS"));
+}
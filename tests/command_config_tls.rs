@@ -0,0 +1,46 @@
+mod utils;
+use predicates::str::contains;
+use utils::TestEnv;
+
+#[test]
+fn command_config_cabundle_persists_absolute_path() {
+    let env = TestEnv::new();
+    let bundle = env.depot_path().join("corporate-ca.pem");
+    std::fs::write(&bundle, "").unwrap();
+
+    env.juliaup()
+        .args(["config", "cabundle"])
+        .arg(&bundle)
+        .assert()
+        .success();
+
+    let config = std::fs::read_to_string(env.config_path()).unwrap();
+    let config: serde_json::Value = serde_json::from_str(&config).unwrap();
+    assert_eq!(
+        config["Settings"]["CaBundle"]
+            .as_str()
+            .map(std::path::PathBuf::from),
+        Some(bundle)
+    );
+
+    env.juliaup()
+        .args(["config", "clientkey", "does-not-exist.pem"])
+        .assert()
+        .failure()
+        .stderr(contains("does not exist"));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn invalid_ca_bundle_fails_downloads() {
+    let env = TestEnv::new();
+    let bundle = env.depot_path().join("not-a-bundle.pem");
+    std::fs::write(&bundle, "not a certificate").unwrap();
+
+    env.juliaup()
+        .arg("update")
+        .env("JULIAUP_CA_BUNDLE", &bundle)
+        .assert()
+        .failure()
+        .stderr(contains("CA bundle"));
+}
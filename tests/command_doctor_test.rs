@@ -0,0 +1,55 @@
+use predicates::prelude::*;
+use predicates::str::contains;
+use std::thread;
+use tiny_http::{Response, Server};
+
+mod utils;
+use utils::TestEnv;
+
+/// Starts a local server that answers every request, so that the server
+/// check does not depend on the network. Returns its base url.
+fn start_server() -> String {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let port = server.server_addr().to_ip().unwrap().port();
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let _ = request.respond(Response::empty(200));
+        }
+    });
+    format!("http://127.0.0.1:{}", port)
+}
+
+#[test]
+fn command_doctor_reports_inconsistent_config() {
+    let env = TestEnv::new();
+
+    std::fs::create_dir_all(env.config_path().parent().unwrap()).unwrap();
+    let config = serde_json::json!({
+        "Default": "release",
+        "InstalledVersions": {},
+        "InstalledChannels": {
+            "release": { "Version": "1.12.0+0.x64.linux.gnu" }
+        }
+    });
+    std::fs::write(env.config_path(), config.to_string()).unwrap();
+
+    let server = start_server();
+    env.juliaup()
+        .arg("doctor")
+        .env("JULIAUP_SERVER", &server)
+        // Without nightly or PR channels their servers are never contacted.
+        .env("JULIAUP_NIGHTLY_SERVER", "http://127.0.0.1:9/")
+        .env("JULIAUP_PR_SERVER", "http://127.0.0.1:9/")
+        .assert()
+        .failure()
+        .stderr(
+            contains(
+                "Channel 'release' points at Julia 1.12.0+0.x64.linux.gnu, which is not installed.",
+            )
+            .and(contains("juliaup repair"))
+            .and(contains(format!("The server {}/", server)))
+            .and(contains("is reachable"))
+            .and(contains("is not reachable").not())
+            .and(contains("is not used by any installed channel")),
+        );
+}
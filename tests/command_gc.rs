@@ -52,3 +52,76 @@ fn command_gc() {
             .and(predicate::str::contains("julib").not()),
     );
 }
+
+#[test]
+fn command_gc_prune_finished_prs() {
+    let env = TestEnv::new();
+
+    let juliauphome = env.config_path().parent().unwrap().to_path_buf();
+    let pr_channel = |n: &str| {
+        std::fs::create_dir_all(juliauphome.join(format!("pr{n}"))).unwrap();
+        serde_json::json!({
+            "Path": format!("pr{n}"),
+            "Url": format!("https://example.com/pr{n}.tar.gz"),
+            "LocalETag": "etag",
+            "ServerETag": "etag",
+            "Version": "1.13.0-DEV"
+        })
+    };
+    let config = serde_json::json!({
+        "Default": "pr2",
+        "InstalledVersions": {},
+        "InstalledChannels": {
+            "pr1": pr_channel("1"),
+            "pr2": pr_channel("2"),
+            "pr3": pr_channel("3"),
+            "pr4@0123abc": pr_channel("4@0123abc")
+        },
+        "FinishedPrChannels": { "pr1": "Merged", "pr2": "Closed", "pr4@0123abc": "Merged" }
+    });
+    std::fs::write(env.config_path(), config.to_string()).unwrap();
+
+    env.juliaup()
+        .arg("gc")
+        .arg("--prune-finished-prs")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Removed channel pr1"));
+
+    assert!(!juliauphome.join("pr1").exists());
+    assert!(!juliauphome.join("pr4@0123abc").exists());
+    assert!(juliauphome.join("pr2").exists());
+
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(env.config_path()).unwrap()).unwrap();
+    let channels = config["InstalledChannels"].as_object().unwrap();
+    assert!(!channels.contains_key("pr1"));
+    assert!(channels.contains_key("pr2"));
+    assert!(channels.contains_key("pr3"));
+    assert!(!channels.contains_key("pr4@0123abc"));
+    assert_eq!(
+        config["FinishedPrChannels"],
+        serde_json::json!({ "pr2": "Closed" })
+    );
+}
+
+#[test]
+fn command_gc_removes_interrupted_downloads() {
+    let env = TestEnv::new();
+
+    let juliauphome = env.config_path().parent().unwrap().to_path_buf();
+    let leftover = juliauphome.join("julia-temp-abc123");
+    std::fs::create_dir_all(leftover.join("bin")).unwrap();
+    std::fs::write(leftover.join("bin").join("julia"), "partial").unwrap();
+    // Nobody holds the lock, so the download that created it is gone.
+    std::fs::write(juliauphome.join("julia-temp-abc123.lock"), "").unwrap();
+
+    env.juliaup()
+        .arg("gc")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Removed interrupted download"));
+
+    assert!(!leftover.exists());
+    assert!(!juliauphome.join("julia-temp-abc123.lock").exists());
+}
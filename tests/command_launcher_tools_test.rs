@@ -0,0 +1,59 @@
+#![cfg(not(windows))]
+
+use assert_cmd::Command;
+use std::os::unix::fs::PermissionsExt;
+
+mod utils;
+use utils::TestEnv;
+
+#[test]
+fn launcher_tools_run_from_the_selected_channel() {
+    let env = TestEnv::new();
+    let install = assert_fs::TempDir::new().unwrap();
+
+    // A fake Julia installation whose `julia` just echoes its arguments.
+    let bin = install.path().join("bin");
+    let juliac_dir = install.path().join("share").join("julia").join("juliac");
+    std::fs::create_dir_all(&bin).unwrap();
+    std::fs::create_dir_all(&juliac_dir).unwrap();
+    let julia = bin.join("julia");
+    std::fs::write(&julia, "#!/bin/sh\necho \"$@\"\n").unwrap();
+    std::fs::set_permissions(&julia, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let script = juliac_dir.join("juliac.jl");
+    std::fs::write(&script, "").unwrap();
+
+    env.juliaup()
+        .args(["link", "fake"])
+        .arg(&julia)
+        .args(["--", "--startup-file=no"])
+        .assert()
+        .success();
+
+    env.juliaup()
+        .args(["config", "launchertools", "juliac"])
+        .assert()
+        .success();
+
+    let entry = env.home_path().join(".local").join("bin").join("juliac");
+    let mut juliac = Command::new(&entry);
+    env.apply_env(&mut juliac);
+    juliac
+        .args(["+fake", "--output-exe", "hello", "hello.jl"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "--startup-file=no {} --output-exe hello hello.jl\n",
+            script.display()
+        ));
+
+    env.juliaup()
+        .args(["config", "launchertools", ""])
+        .assert()
+        .success();
+    assert!(entry.symlink_metadata().is_err());
+
+    env.juliaup()
+        .args(["config", "launchertools", "julia"])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,30 @@
+use predicates::str::contains;
+
+mod utils;
+use utils::TestEnv;
+
+#[test]
+fn command_repair_removes_dangling_alias() {
+    let env = TestEnv::new();
+
+    std::fs::create_dir_all(env.config_path().parent().unwrap()).unwrap();
+    let config = serde_json::json!({
+        "InstalledVersions": {},
+        "InstalledChannels": {
+            "myalias": { "Target": "doesnotexist", "Args": null }
+        }
+    });
+    std::fs::write(env.config_path(), config.to_string()).unwrap();
+
+    env.juliaup()
+        .arg("repair")
+        .assert()
+        .success()
+        .stderr(contains(
+            "Removed channel 'myalias', its target no longer exists.",
+        ));
+
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(env.config_path()).unwrap()).unwrap();
+    assert!(config["InstalledChannels"].get("myalias").is_none());
+}
@@ -0,0 +1,69 @@
+use predicates::prelude::*;
+
+mod utils;
+use utils::TestEnv;
+
+#[test]
+fn shell_init_prints_path_and_completions() {
+    let env = TestEnv::new();
+
+    env.juliaup()
+        .arg("shell-init")
+        .arg("bash")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("export PATH=")
+                .and(predicate::str::contains("complete -F _juliaup"))
+                .and(predicate::str::contains("_juliaup_override_hook").not()),
+        );
+
+    env.juliaup()
+        .arg("shell-init")
+        .arg("zsh")
+        .arg("--hook")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("add-zsh-hook chpwd _juliaup_override_hook")
+                .and(predicate::str::contains("compdef _juliaup juliaup")),
+        );
+}
+
+#[test]
+fn override_channel_follows_directory_overrides() {
+    let env = TestEnv::new();
+    let or_dir = assert_fs::TempDir::new().unwrap();
+    let sub_dir = or_dir.path().join("sub");
+    std::fs::create_dir(&sub_dir).unwrap();
+
+    env.juliaup()
+        .arg("link")
+        .arg("custom")
+        .arg("/usr/bin/false")
+        .assert()
+        .success();
+
+    env.juliaup()
+        .arg("override")
+        .arg("set")
+        .arg("--path")
+        .arg(or_dir.path())
+        .arg("custom")
+        .assert()
+        .success();
+
+    env.juliaup()
+        .arg("_override-channel")
+        .current_dir(&sub_dir)
+        .assert()
+        .success()
+        .stdout("custom\n");
+
+    env.juliaup()
+        .arg("_override-channel")
+        .current_dir(env.home_path())
+        .assert()
+        .success()
+        .stdout("");
+}
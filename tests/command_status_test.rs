@@ -17,3 +17,25 @@ fn command_status() {
         .success()
         .stdout(" Default  Channel  Version  Update \n-----------------------------------\n");
 }
+
+#[test]
+fn command_status_size() {
+    let env = TestEnv::new();
+
+    let temp_dir = env
+        .config_path()
+        .parent()
+        .unwrap()
+        .join("julia-temp-abc123");
+    std::fs::create_dir_all(&temp_dir).unwrap();
+    std::fs::write(temp_dir.join("partial"), [0u8; 10]).unwrap();
+
+    env.juliaup()
+        .args(["status", "--size"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "10 B  julia-temp-abc123  interrupted download",
+        ))
+        .stdout(predicates::str::contains("Total: 10 B"));
+}
@@ -0,0 +1,72 @@
+mod utils;
+use utils::TestEnv;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tiny_http::{Header, Response, Server};
+
+/// Starts a local server that records the value of the `X-Api-Key` header of
+/// every request and answers with `response`. Returns the port and the
+/// recorded values.
+fn start_server(
+    response: impl Fn() -> Response<std::io::Empty> + Send + 'static,
+) -> (u16, Arc<Mutex<Vec<Option<String>>>>) {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let port = server.server_addr().to_ip().unwrap().port();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    {
+        let seen = Arc::clone(&seen);
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let api_key = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("X-Api-Key"))
+                    .map(|h| h.value.to_string());
+                seen.lock().unwrap().push(api_key);
+                let _ = request.respond(response());
+            }
+        });
+    }
+
+    (port, seen)
+}
+
+#[test]
+fn credentials_are_not_sent_along_cross_host_redirects() {
+    let env = TestEnv::new();
+
+    let (target_port, target_seen) = start_server(|| Response::empty(200));
+    let (mirror_port, mirror_seen) = start_server(move || {
+        let location = format!("http://127.0.0.1:{}/elsewhere", target_port);
+        Response::empty(302).with_header(Header::from_bytes("Location", location).unwrap())
+    });
+
+    let juliauphome = env.config_path().parent().unwrap().to_path_buf();
+    std::fs::create_dir_all(&juliauphome).unwrap();
+    std::fs::write(
+        juliauphome.join("credentials.toml"),
+        format!(
+            "[hosts.\"127.0.0.1:{}\".headers]\nX-Api-Key = \"s3cret\"\n",
+            mirror_port
+        ),
+    )
+    .unwrap();
+
+    env.juliaup()
+        .arg("update")
+        .env(
+            "JULIAUP_SERVER",
+            format!("http://127.0.0.1:{}", mirror_port),
+        )
+        .assert()
+        .failure();
+
+    let mirror_seen = mirror_seen.lock().unwrap();
+    assert!(!mirror_seen.is_empty());
+    assert!(mirror_seen
+        .iter()
+        .all(|key| key.as_deref() == Some("s3cret")));
+    assert!(target_seen.lock().unwrap().is_empty());
+}
@@ -26,5 +26,9 @@ fn installer_help_documents_noninteractive_options() {
         ))
         .stdout(predicate::str::contains(
             "Check for Juliaup self-updates when Julia starts every MINUTES minutes [default: 1440]",
+        ))
+        .stdout(predicate::str::contains("--config <FILE>"))
+        .stdout(predicate::str::contains(
+            "Read installer settings from a TOML file",
         ));
 }
@@ -0,0 +1,68 @@
+mod utils;
+use utils::TestEnv;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Response, Server};
+
+/// Starts a local server that answers the first `failures` requests with
+/// HTTP 503 and all later ones with the bundled versions db version, so that
+/// no versions db download is triggered. Returns the base url and a counter
+/// of the requests received.
+fn start_flaky_server(failures: usize) -> (String, Arc<AtomicUsize>) {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let port = server.server_addr().to_ip().unwrap().port();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let db_version = juliaup::get_bundled_dbversion().unwrap().to_string();
+
+    {
+        let requests = Arc::clone(&requests);
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let n = requests.fetch_add(1, Ordering::SeqCst);
+                let response = if n < failures {
+                    Response::from_string("unavailable").with_status_code(503)
+                } else {
+                    Response::from_string(db_version.clone())
+                };
+                let _ = request.respond(response);
+            }
+        });
+    }
+
+    (format!("http://127.0.0.1:{}", port), requests)
+}
+
+#[test]
+fn server_errors_are_retried() {
+    let env = TestEnv::new();
+    let (server, requests) = start_flaky_server(2);
+
+    env.juliaup()
+        .arg("update")
+        .env("JULIAUP_SERVER", &server)
+        .assert()
+        .success();
+
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn retries_can_be_disabled() {
+    let env = TestEnv::new();
+    let (server, requests) = start_flaky_server(1);
+
+    env.juliaup()
+        .args(["config", "downloadretries", "0"])
+        .assert()
+        .success();
+
+    env.juliaup()
+        .arg("update")
+        .env("JULIAUP_SERVER", &server)
+        .assert()
+        .failure();
+
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}